@@ -2,6 +2,7 @@ use std::mem::swap;
 
 use crate::{ray::Ray, vec3::Point3};
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Default, Copy, Clone)]
 pub struct AABB {
     minimum: Point3,
@@ -21,7 +22,12 @@ impl AABB {
         self.maximum
     }
 
-    pub fn hit(&self, ray: &Ray, mut t_min: f64, mut t_max: f64) -> bool {
+    pub fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> bool {
+        self.clip(ray, t_min, t_max).is_some()
+    }
+
+    /// The part of `t_min..t_max` where `ray` is inside the box, if any.
+    pub fn clip(&self, ray: &Ray, mut t_min: f64, mut t_max: f64) -> Option<(f64, f64)> {
         for a in 0..3 {
             let inv_d = 1.0 / ray.direction()[a];
             let mut t0 = (self.minimum[a] - ray.origin()[a]) * inv_d;
@@ -40,11 +46,11 @@ impl AABB {
             }
 
             if t_max <= t_min {
-                return false;
+                return None;
             }
         }
 
-        true
+        Some((t_min, t_max))
     }
 }
 
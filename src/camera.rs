@@ -1,6 +1,6 @@
 use crate::{
     ray::Ray,
-    vec3::{cross, random_in_unit_disk, Point3, Vec3},
+    vec3::{cross, dot, random_in_unit_disk, Point3, Vec3},
 };
 use rand::Rng;
 
@@ -13,11 +13,13 @@ pub struct Camera {
     v: Vec3,
     w: Vec3,
     lens_radius: f64,
+    focus_dist: f64,
     time0: f64,
     time1: f64,
 }
 
 impl Camera {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         lookfrom: Point3,
         lookat: Point3,
@@ -51,6 +53,7 @@ impl Camera {
             v,
             w,
             lens_radius: aperture / 2.0,
+            focus_dist,
             time0,
             time1,
         }
@@ -67,4 +70,46 @@ impl Camera {
             rng.gen_range(self.time0..=self.time1),
         )
     }
+
+    /// Direction the camera looks in.
+    pub fn forward(&self) -> Vec3 {
+        -self.w
+    }
+
+    /// Area of the lens, `None` for a pinhole camera.
+    pub fn lens_area(&self) -> Option<f64> {
+        if self.lens_radius > 0.0 {
+            Some(std::f64::consts::PI * self.lens_radius * self.lens_radius)
+        } else {
+            None
+        }
+    }
+
+    /// Picks a point uniformly on the lens.
+    pub fn sample_lens(&self) -> Point3 {
+        let rd = self.lens_radius * random_in_unit_disk();
+        self.origin + self.u * rd.x() + self.v * rd.y()
+    }
+
+    /// Area the viewport covers for `s` and `t` in `[0, 1]`, at unit distance from the lens.
+    pub fn viewport_area(&self) -> f64 {
+        self.horizontal.length() * self.vertical.length() / (self.focus_dist * self.focus_dist)
+    }
+
+    /// The `(s, t)` that `get_ray` would need to send a ray from `lens_point` through `p`,
+    /// `None` if `p` is behind the camera.
+    pub fn viewport_coords(&self, lens_point: &Point3, p: &Point3) -> Option<(f64, f64)> {
+        let direction = *p - *lens_point;
+        let cos_theta = dot(&direction, &self.forward());
+        if cos_theta <= 0.0 {
+            return None;
+        }
+
+        let on_focus_plane = *lens_point + (self.focus_dist / cos_theta) * direction;
+        let offset = on_focus_plane - self.lower_left_corner;
+        Some((
+            dot(&offset, &self.horizontal) / self.horizontal.length_squared(),
+            dot(&offset, &self.vertical) / self.vertical.length_squared(),
+        ))
+    }
 }
@@ -0,0 +1,173 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use crate::{
+    aabb::{surrounding_box, AABB},
+    ray::Ray,
+    transform::{Rotation, Transform},
+    vec3::Vec3,
+};
+
+use super::hittable::{HitRecord, Hittable};
+
+/// Steps per keyframe interval when sweeping the bounding box through the motion.
+const BOX_STEPS: usize = 32;
+
+/// Pose of an object at `time`: scaled about its origin, then rotated by `angle` degrees
+/// around `axis`, then moved by `translation`.
+#[derive(Clone, Copy)]
+pub struct Keyframe {
+    pub time: f64,
+    pub translation: Vec3,
+    pub axis: Vec3,
+    pub angle: f64,
+    pub scale: Vec3,
+}
+
+impl Keyframe {
+    pub fn new(time: f64, translation: Vec3, axis: Vec3, angle: f64, scale: Vec3) -> Self {
+        Self {
+            time,
+            translation,
+            axis,
+            angle,
+            scale,
+        }
+    }
+
+    /// Keyframe that only moves the object.
+    pub fn translation(time: f64, translation: Vec3) -> Self {
+        Self::new(
+            time,
+            translation,
+            Vec3::with_values(0.0, 1.0, 0.0),
+            0.0,
+            Vec3::with_value(1.0),
+        )
+    }
+}
+
+/// Moves, turns and resizes an object over the shutter, blending between `keyframes` by the
+/// time of each ray: linearly for translation and scale, along the shortest arc for rotation.
+/// Any hittable can be motion blurred this way, not only a `MovingSphere`.
+pub struct AnimatedTransform {
+    ptr: Arc<Box<dyn Hittable>>,
+    keyframes: Vec<Keyframe>,
+    rotations: Vec<Rotation>,
+}
+
+impl AnimatedTransform {
+    pub fn new(p: &Arc<Box<dyn Hittable>>, mut keyframes: Vec<Keyframe>) -> Self {
+        assert!(!keyframes.is_empty());
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        let rotations = keyframes
+            .iter()
+            .map(|key| Rotation::new(&key.axis, key.angle))
+            .collect();
+
+        Self {
+            ptr: Arc::clone(p),
+            keyframes,
+            rotations,
+        }
+    }
+
+    /// The pose at `time`, held at the first or last keyframe outside their range.
+    fn pose(&self, time: f64) -> Transform {
+        let next = self.keyframes.partition_point(|key| key.time <= time);
+        if next == 0 || next == self.keyframes.len() {
+            let i = next.saturating_sub(1);
+            return Transform {
+                translation: self.keyframes[i].translation,
+                rotation: self.rotations[i],
+                scale: self.keyframes[i].scale,
+            };
+        }
+
+        let (a, b) = (&self.keyframes[next - 1], &self.keyframes[next]);
+        let f = (time - a.time) / (b.time - a.time);
+        Transform {
+            translation: a.translation + f * (b.translation - a.translation),
+            rotation: self.rotations[next - 1].slerp(&self.rotations[next], f),
+            scale: a.scale + f * (b.scale - a.scale),
+        }
+    }
+}
+
+impl Hittable for AnimatedTransform {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let pose = self.pose(ray.time());
+        if !self.ptr.hit(&pose.inverse_ray(ray), t_min, t_max, rec) {
+            return false;
+        }
+
+        // NOTE: normals take the inverse scale, which keeps them facing the ray
+        rec.p = pose.point(&rec.p);
+        rec.normal = pose.normal(&rec.normal);
+        rec.dpdu = pose.vector(&rec.dpdu);
+        rec.dpdv = pose.vector(&rec.dpdv);
+
+        true
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64, output_box: &mut AABB) -> bool {
+        let mut bbox = AABB::default();
+        if !self.ptr.bounding_box(time0, time1, &mut bbox) {
+            return false;
+        }
+
+        // NOTE: sweep the corners through every keyframe interval the shutter overlaps
+        let mut times = vec![time0, time1];
+        times.extend(
+            self.keyframes
+                .iter()
+                .map(|key| key.time)
+                .filter(|&time| time0 < time && time < time1),
+        );
+        times.sort_by(f64::total_cmp);
+
+        let mut extent = Vec3::new();
+        for c in 0..3 {
+            extent[c] = bbox.min()[c].abs().max(bbox.max()[c].abs());
+        }
+
+        let mut swept: Option<AABB> = None;
+        let mut reach: f64 = 0.0;
+        let mut widest_turn: f64 = 0.0;
+        for window in times.windows(2) {
+            let mut previous: Option<Rotation> = None;
+            for step in 0..=BOX_STEPS {
+                let time = window[0] + (window[1] - window[0]) * step as f64 / BOX_STEPS as f64;
+                let pose = self.pose(time);
+                let bounds = pose.bounds(&bbox);
+                swept = Some(match swept {
+                    Some(swept) => surrounding_box(&swept, &bounds),
+                    None => bounds,
+                });
+                reach = reach.max((pose.scale * extent).length());
+
+                if let Some(previous) = previous {
+                    widest_turn = widest_turn.max(previous.angle_to(&pose.rotation));
+                }
+                previous = Some(pose.rotation);
+            }
+        }
+
+        // NOTE: corners swing along arcs between steps, pad by how far an arc bulges out
+        // past its chord
+        let bulge = reach * (1.0 - (0.5 * widest_turn.min(PI)).cos()) + 0.0001;
+        let swept = swept.unwrap_or(bbox);
+        *output_box = AABB::new(
+            swept.min() - Vec3::with_value(bulge),
+            swept.max() + Vec3::with_value(bulge),
+        );
+        true
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_animated {
+    ( $ptr:expr, $keyframes:expr ) => {
+        Arc::new(Box::new(AnimatedTransform::new($ptr, $keyframes)))
+    };
+}
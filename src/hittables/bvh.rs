@@ -85,9 +85,16 @@ impl Hittable for BVHNode {
             return false;
         }
 
+        // NOTE: only meshes set a vertex color, don't let one leak into another hit. The left
+        // one is put back if the right side misses
+        rec.vertex_color = None;
         let hit_left = self.left.hit(ray, t_min, t_max, rec);
         let t_max = if hit_left { rec.t } else { t_max };
+        let left_color = rec.vertex_color.take();
         let hit_right = self.right.hit(ray, t_min, t_max, rec);
+        if !hit_right {
+            rec.vertex_color = left_color;
+        }
 
         hit_left || hit_right
     }
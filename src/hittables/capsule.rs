@@ -0,0 +1,141 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use super::{
+    cylinder::{around_y, nearest_axial_hit, AxialHit},
+    hittable::{HitRecord, Hittable},
+};
+use crate::{
+    aabb::AABB,
+    materials::material::Material,
+    ray::Ray,
+    vec3::{dot, Point3, Vec3},
+};
+
+/// A cylinder of `height` standing on `base` along the y axis with a hemisphere on each end,
+/// the points within `radius` of the segment between them. `u` goes around it like on a
+/// `Sphere` and `v` runs from the bottom pole to the top one at an even pace.
+pub struct Capsule {
+    base: Point3,
+    radius: f64,
+    height: f64,
+    mat_ptr: Arc<Box<dyn Material>>,
+}
+
+impl Capsule {
+    pub fn new(base: Point3, radius: f64, height: f64, mat_ptr: &Arc<Box<dyn Material>>) -> Self {
+        Self {
+            base,
+            radius,
+            height,
+            mat_ptr: Arc::clone(mat_ptr),
+        }
+    }
+
+    /// Length of a path from pole to pole, `v` is the distance along it over this.
+    fn length(&self) -> f64 {
+        PI * self.radius + self.height
+    }
+
+    /// Hits on the end sphere at height `y`, keeping the half facing away from the middle.
+    fn end_hits(&self, origin: &Point3, dir: &Vec3, y: f64, top: bool, hits: &mut Vec<AxialHit>) {
+        let center = Vec3::with_values(0.0, y, 0.0);
+        let oc = *origin - center;
+        let a = dir.length_squared();
+        let half_b = dot(&oc, dir);
+        let c = oc.length_squared() - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < 0.0 {
+            return;
+        }
+
+        let sqrtd = discriminant.sqrt();
+        for t in [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a] {
+            let p = *origin + t * *dir;
+            let rel = p - center;
+            if (top && rel.y() < 0.0) || (!top && rel.y() > 0.0) {
+                continue;
+            }
+
+            let outward_normal = rel / self.radius;
+            let rho = (rel.x() * rel.x() + rel.z() * rel.z()).sqrt().max(1e-12);
+            let theta = outward_normal.y().clamp(-1.0, 1.0).asin();
+            let distance = if top {
+                0.5 * PI * self.radius + self.height + self.radius * theta
+            } else {
+                self.radius * (theta + 0.5 * PI)
+            };
+
+            let (u, dpdu) = around_y(&rel);
+            // NOTE: the meridian's direction, kept finite at the poles
+            let meridian = Vec3::with_values(
+                -theta.sin() * rel.x() / rho,
+                theta.cos(),
+                -theta.sin() * rel.z() / rho,
+            );
+            hits.push(AxialHit {
+                t,
+                p,
+                outward_normal,
+                u,
+                v: distance / self.length(),
+                dpdu,
+                dpdv: self.length() * meridian,
+            });
+        }
+    }
+}
+
+impl Hittable for Capsule {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let origin = ray.origin() - self.base;
+        let dir = ray.direction();
+
+        let mut hits = Vec::with_capacity(6);
+
+        let a = dir.x() * dir.x() + dir.z() * dir.z();
+        let half_b = origin.x() * dir.x() + origin.z() * dir.z();
+        let c = origin.x() * origin.x() + origin.z() * origin.z() - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+        if a > 0.0 && discriminant >= 0.0 {
+            let sqrtd = discriminant.sqrt();
+            for t in [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a] {
+                let p = origin + t * dir;
+                if p.y() < 0.0 || p.y() > self.height {
+                    continue;
+                }
+
+                let (u, dpdu) = around_y(&p);
+                hits.push(AxialHit {
+                    t,
+                    p,
+                    outward_normal: Vec3::with_values(p.x(), 0.0, p.z()) / self.radius,
+                    u,
+                    v: (0.5 * PI * self.radius + p.y()) / self.length(),
+                    dpdu,
+                    dpdv: Vec3::with_values(0.0, self.length(), 0.0),
+                });
+            }
+        }
+
+        self.end_hits(&origin, &dir, 0.0, false, &mut hits);
+        self.end_hits(&origin, &dir, self.height, true, &mut hits);
+
+        nearest_axial_hit(hits, &self.base, &self.mat_ptr, ray, t_min, t_max, rec)
+    }
+
+    fn bounding_box(&self, _: f64, _: f64, output_box: &mut AABB) -> bool {
+        *output_box = AABB::new(
+            self.base - Vec3::with_value(self.radius),
+            self.base + Vec3::with_values(self.radius, self.height + self.radius, self.radius),
+        );
+        true
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_capsule {
+    ( $base:expr, $radius:expr, $height:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(Capsule::new($base, $radius, $height, $mat_ptr)))
+    };
+}
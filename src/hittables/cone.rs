@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use super::{
+    cylinder::{around_y, cap_hit, nearest_axial_hit, AxialHit},
+    hittable::{HitRecord, Hittable},
+};
+use crate::{
+    aabb::AABB,
+    materials::material::Material,
+    ray::Ray,
+    vec3::{Point3, Vec3},
+};
+
+/// A cone standing on the center of its bottom `base`, with its apex `height` above along
+/// the y axis. Mapped like a `Cylinder`, with `v` reaching `1` at the apex.
+pub struct Cone {
+    base: Point3,
+    radius: f64,
+    height: f64,
+    capped: bool,
+    mat_ptr: Arc<Box<dyn Material>>,
+}
+
+impl Cone {
+    pub fn new(
+        base: Point3,
+        radius: f64,
+        height: f64,
+        capped: bool,
+        mat_ptr: &Arc<Box<dyn Material>>,
+    ) -> Self {
+        Self {
+            base,
+            radius,
+            height,
+            capped,
+            mat_ptr: Arc::clone(mat_ptr),
+        }
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let origin = ray.origin() - self.base;
+        let dir = ray.direction();
+
+        let mut hits = Vec::with_capacity(3);
+
+        // NOTE: x² + z² = k²(h - y)², the double cone with its apex at the top
+        let k2 = (self.radius / self.height).powi(2);
+        let rise = self.height - origin.y();
+        let a = dir.x() * dir.x() + dir.z() * dir.z() - k2 * dir.y() * dir.y();
+        let half_b = origin.x() * dir.x() + origin.z() * dir.z() + k2 * rise * dir.y();
+        let c = origin.x() * origin.x() + origin.z() * origin.z() - k2 * rise * rise;
+
+        let roots = if a.abs() < 1e-12 {
+            vec![-c / (2.0 * half_b)]
+        } else {
+            let discriminant = half_b * half_b - a * c;
+            if discriminant < 0.0 {
+                vec![]
+            } else {
+                let sqrtd = discriminant.sqrt();
+                vec![(-half_b - sqrtd) / a, (-half_b + sqrtd) / a]
+            }
+        };
+
+        for t in roots.into_iter().filter(|t| t.is_finite()) {
+            let p = origin + t * dir;
+            if p.y() < 0.0 || p.y() > self.height {
+                continue;
+            }
+
+            let (u, dpdu) = around_y(&p);
+            let (cos_phi, sin_phi) = {
+                let rho = (p.x() * p.x() + p.z() * p.z()).sqrt().max(1e-12);
+                (p.x() / rho, p.z() / rho)
+            };
+            hits.push(AxialHit {
+                t,
+                p,
+                outward_normal: Vec3::with_values(p.x(), k2 * (self.height - p.y()), p.z()),
+                u,
+                v: p.y() / self.height,
+                dpdu,
+                dpdv: Vec3::with_values(
+                    -self.radius * cos_phi,
+                    self.height,
+                    -self.radius * sin_phi,
+                ),
+            });
+        }
+
+        if self.capped {
+            hits.extend(cap_hit(&origin, &dir, 0.0, self.radius, false));
+        }
+
+        nearest_axial_hit(hits, &self.base, &self.mat_ptr, ray, t_min, t_max, rec)
+    }
+
+    fn bounding_box(&self, _: f64, _: f64, output_box: &mut AABB) -> bool {
+        *output_box = AABB::new(
+            self.base - Vec3::with_values(self.radius, 0.0, self.radius),
+            self.base + Vec3::with_values(self.radius, self.height, self.radius),
+        );
+        true
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_cone {
+    ( $base:expr, $radius:expr, $height:expr, $capped:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(Cone::new(
+            $base, $radius, $height, $capped, $mat_ptr,
+        )))
+    };
+    ( $base:expr, $radius:expr, $height:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(Cone::new($base, $radius, $height, true, $mat_ptr)))
+    };
+}
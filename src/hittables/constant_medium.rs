@@ -4,7 +4,12 @@ use rand::Rng;
 
 use crate::{
     aabb::AABB,
-    materials::{isotropic::Isotropic, material::Material},
+    materials::{
+        chromatic_phase::ChromaticPhase,
+        henyey_greenstein::{DualLobeHenyeyGreenstein, HenyeyGreenstein},
+        isotropic::Isotropic,
+        material::Material,
+    },
     ray::Ray,
     vec3::{Color, Vec3},
 };
@@ -33,72 +38,126 @@ impl ConstantMedium {
             phase_function: Arc::new(Box::new(Isotropic::new(a))),
         }
     }
-}
-
-impl Hittable for ConstantMedium {
-    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
-        let mut rng = rand::thread_rng();
-        let enable_debug = false;
-        let debugging = enable_debug && rng.gen_range(0.0..1.0) < 0.00001;
 
-        let mut rec1: HitRecord = Default::default();
-        let mut rec2: HitRecord = Default::default();
-
-        if !self
-            .boundary
-            .hit(ray, -f64::INFINITY, f64::INFINITY, &mut rec1)
-        {
-            return false;
+    /// Medium whose scattering leans forward (`g > 0`) or backward (`g < 0`), like fog.
+    pub fn with_henyey_greenstein(b: &Arc<Box<dyn Hittable>>, d: f64, a: Color, g: f64) -> Self {
+        Self {
+            boundary: Arc::clone(b),
+            neg_inv_density: -1.0 / d,
+            phase_function: Arc::new(Box::new(HenyeyGreenstein::new(a, g))),
         }
-        if !self
-            .boundary
-            .hit(ray, rec1.t + 0.0001, f64::INFINITY, &mut rec2)
-        {
-            return false;
+    }
+
+    /// Medium scattering into two lobes, `weight` of it along `g1`, see
+    /// `DualLobeHenyeyGreenstein`.
+    pub fn with_dual_lobe(
+        b: &Arc<Box<dyn Hittable>>,
+        d: f64,
+        a: Color,
+        g1: f64,
+        g2: f64,
+        weight: f64,
+    ) -> Self {
+        Self {
+            boundary: Arc::clone(b),
+            neg_inv_density: -1.0 / d,
+            phase_function: Arc::new(Box::new(DualLobeHenyeyGreenstein::new(a, g1, g2, weight))),
         }
+    }
 
-        if debugging {
-            println!("\nt_min = {0}, t_max = {1}", rec1.t, rec2.t);
+    /// Medium with per channel coefficients and optional emission, see `ChromaticPhase`.
+    pub fn chromatic(b: &Arc<Box<dyn Hittable>>, phase: ChromaticPhase) -> Self {
+        Self {
+            boundary: Arc::clone(b),
+            neg_inv_density: -1.0 / phase.majorant(),
+            phase_function: Arc::new(Box::new(phase)),
         }
+    }
+}
 
-        if rec1.t < t_min {
-            rec1.t = t_min;
+/// Spans of `ray` within `[t_min, t_max]` that lie inside `boundary`, found by walking every
+/// crossing along the ray. Overlapping and non-convex boundaries are handled by counting how
+/// deep in the boundary each crossing is, and an open boundary is treated as inside on the
+/// side its normals point away from.
+pub fn inside_intervals(
+    boundary: &Arc<Box<dyn Hittable>>,
+    ray: &Ray,
+    t_min: f64,
+    t_max: f64,
+) -> Vec<(f64, f64)> {
+    // NOTE: guards against boundaries that keep reporting hits at the same t
+    const MAX_CROSSINGS: usize = 64;
+
+    let mut intervals = Vec::new();
+    let mut depth = 0;
+    let mut entered = -f64::INFINITY;
+    let mut last = -f64::INFINITY;
+
+    let mut push = |t0: f64, t1: f64| {
+        let t0 = t0.max(t_min).max(0.0);
+        let t1 = t1.min(t_max);
+        if t0 < t1 {
+            intervals.push((t0, t1));
         }
-        if rec2.t > t_max {
-            rec2.t = t_max;
+    };
+
+    for _ in 0..MAX_CROSSINGS {
+        let mut rec = HitRecord::default();
+        if !boundary.hit(ray, last, f64::INFINITY, &mut rec) {
+            break;
         }
 
-        if rec1.t >= rec2.t {
-            return false;
+        if rec.front_face {
+            if depth == 0 {
+                entered = rec.t;
+            }
+            depth += 1;
+        } else if depth > 0 {
+            depth -= 1;
+            if depth == 0 {
+                push(entered, rec.t);
+            }
+        } else {
+            push(last, rec.t);
         }
 
-        if rec1.t < 0.0 {
-            rec1.t = 0.0;
+        last = rec.t + 0.0001;
+        if last > t_max {
+            break;
         }
+    }
 
-        let ray_length = ray.direction().length();
-        let distance_inside_boundary = (rec2.t - rec1.t) * ray_length;
-        let hit_distance = self.neg_inv_density * rng.gen_range(0.0..1.0f64).ln();
+    if depth > 0 {
+        push(entered, f64::INFINITY);
+    }
 
-        if hit_distance > distance_inside_boundary {
-            return false;
-        }
+    intervals
+}
 
-        rec.t = rec1.t + hit_distance / ray_length;
-        rec.p = ray.at(rec.t);
+impl Hittable for ConstantMedium {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let mut rng = rand::thread_rng();
 
-        if debugging {
-            println!(
-                "hit_distance = {}\nnrec.t = {}\nnrec.p = {}",
-                hit_distance, rec.t, rec.p
-            );
+        let ray_length = ray.direction().length();
+        let mut hit_distance = self.neg_inv_density * rng.gen_range(0.0..1.0f64).ln();
+
+        for (t0, t1) in inside_intervals(&self.boundary, ray, t_min, t_max) {
+            let distance_inside_boundary = (t1 - t0) * ray_length;
+            if hit_distance > distance_inside_boundary {
+                hit_distance -= distance_inside_boundary;
+                continue;
+            }
+
+            rec.t = t0 + hit_distance / ray_length;
+            rec.p = ray.at(rec.t);
+            rec.normal = Vec3::with_values(1.0, 0.0, 0.0); // arbitrary
+            rec.front_face = true; // also arbitrary
+            rec.mat_ptr = Arc::clone(&self.phase_function);
+
+            return true;
         }
 
-        rec.normal = Vec3::with_values(1.0, 0.0, 0.0); // arbitrary
-        rec.front_face = true; // also arbitrary
-        rec.mat_ptr = Arc::clone(&self.phase_function);
-
-        true
+        false
     }
 
     fn bounding_box(&self, time0: f64, time1: f64, output_box: &mut AABB) -> bool {
@@ -108,6 +167,19 @@ impl Hittable for ConstantMedium {
 
 #[macro_export]
 macro_rules! rc_box_constant_medium {
+    ( $b:expr, $phase:expr, Chromatic ) => {
+        Arc::new(Box::new(ConstantMedium::chromatic($b, $phase)))
+    };
+    ( $b:expr, $d:expr, $color:expr, $g1:expr, $g2:expr, $weight:expr, DualLobe ) => {
+        Arc::new(Box::new(ConstantMedium::with_dual_lobe(
+            $b, $d, $color, $g1, $g2, $weight,
+        )))
+    };
+    ( $b:expr, $d:expr, $color:expr, $g:expr, HenyeyGreenstein ) => {
+        Arc::new(Box::new(ConstantMedium::with_henyey_greenstein(
+            $b, $d, $color, $g,
+        )))
+    };
     ( $b:expr, $d:expr, $color:expr, Color ) => {
         Arc::new(Box::new(ConstantMedium::with_color($b, $d, $color)))
     };
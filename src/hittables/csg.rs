@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use super::hittable::{HitRecord, Hittable};
+use crate::{
+    aabb::{surrounding_box, AABB},
+    ray::Ray,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    /// The left object with the right one carved out of it.
+    Difference,
+}
+
+impl CsgOperation {
+    fn inside(&self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => in_left || in_right,
+            CsgOperation::Intersection => in_left && in_right,
+            CsgOperation::Difference => in_left && !in_right,
+        }
+    }
+}
+
+/// Boolean combination of two closed objects. Each keeps its own material, and the surface
+/// of the right object facing into a difference has its front face turned around.
+pub struct Csg {
+    left: Arc<Box<dyn Hittable>>,
+    right: Arc<Box<dyn Hittable>>,
+    operation: CsgOperation,
+}
+
+impl Csg {
+    pub fn new(
+        left: &Arc<Box<dyn Hittable>>,
+        right: &Arc<Box<dyn Hittable>>,
+        operation: CsgOperation,
+    ) -> Self {
+        Self {
+            left: Arc::clone(left),
+            right: Arc::clone(right),
+            operation,
+        }
+    }
+}
+
+impl Hittable for Csg {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        match self.hit_all(ray, t_min, t_max).into_iter().next() {
+            Some(hit) => {
+                *rec = hit;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn hit_all(&self, ray: &Ray, t_min: f64, t_max: f64) -> Vec<HitRecord> {
+        // NOTE: crossings past `t_max` are still needed to know where the ray starts
+        let left = self.left.hit_all(ray, t_min, f64::INFINITY);
+        let right = self.right.hit_all(ray, t_min, f64::INFINITY);
+
+        // NOTE: a ray whose first crossing leaves an object started inside it
+        let mut in_left = left.first().is_some_and(|rec| !rec.front_face);
+        let mut in_right = right.first().is_some_and(|rec| !rec.front_face);
+        let mut inside = self.operation.inside(in_left, in_right);
+
+        let mut crossings: Vec<(bool, HitRecord)> = left
+            .into_iter()
+            .map(|rec| (true, rec))
+            .chain(right.into_iter().map(|rec| (false, rec)))
+            .collect();
+        crossings.sort_by(|a, b| a.1.t.total_cmp(&b.1.t));
+
+        let mut hits = Vec::new();
+        for (from_left, mut rec) in crossings {
+            if rec.t > t_max {
+                break;
+            }
+
+            if from_left {
+                in_left = rec.front_face;
+            } else {
+                in_right = rec.front_face;
+            }
+            let now_inside = self.operation.inside(in_left, in_right);
+            if now_inside == inside {
+                continue;
+            }
+
+            inside = now_inside;
+            rec.front_face = now_inside;
+            hits.push(rec);
+        }
+        hits
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64, output_box: &mut AABB) -> bool {
+        let mut left_box = AABB::default();
+        let mut right_box = AABB::default();
+        let has_left = self.left.bounding_box(time0, time1, &mut left_box);
+        let has_right = self.right.bounding_box(time0, time1, &mut right_box);
+
+        match self.operation {
+            CsgOperation::Union if has_left && has_right => {
+                *output_box = surrounding_box(&left_box, &right_box);
+                true
+            }
+            CsgOperation::Union => false,
+            CsgOperation::Intersection if has_left || has_right => {
+                *output_box = if has_left { left_box } else { right_box };
+                true
+            }
+            CsgOperation::Intersection => false,
+            CsgOperation::Difference => {
+                *output_box = left_box;
+                has_left
+            }
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_csg {
+    ( $left:expr, $right:expr, $operation:ident ) => {
+        Arc::new(Box::new(Csg::new($left, $right, CsgOperation::$operation)))
+    };
+}
@@ -0,0 +1,339 @@
+use std::f64::consts::SQRT_2;
+use std::sync::Arc;
+
+use super::hittable::{is_cutout, HitRecord, Hittable, HittableList};
+use crate::{
+    aabb::AABB,
+    materials::material::Material,
+    onb::Onb,
+    ray::Ray,
+    vec3::{cross, dot, Point3, Vec3},
+};
+
+/// How the width of a `Curve` is shaded.
+#[derive(Clone, Copy, Debug)]
+pub enum CurveType {
+    /// A ribbon always turned to face the ray, for hair and fur seen from afar.
+    Flat,
+    /// Shaded as a tube, with normals curving around the sides.
+    Round,
+}
+
+/// What the pieces of one split curve share.
+struct CurveCommon {
+    control_points: [Point3; 4],
+    width: [f64; 2],
+    kind: CurveType,
+    mat_ptr: Arc<Box<dyn Material>>,
+}
+
+/// Part of a cubic Bézier curve from `u_min` to `u_max`, with a width tapering linearly from
+/// one end of the whole curve to the other. Long curves are best split into a few pieces with
+/// `Curve::split` so each gets a tight box in the `BVHNode` over them. `u` runs along the
+/// curve and `v` across it, for hair the side of the fiber the ray hit.
+pub struct Curve {
+    common: Arc<CurveCommon>,
+    control_points: [Point3; 4],
+    u_min: f64,
+    u_max: f64,
+    max_depth: u32,
+}
+
+impl Curve {
+    pub fn new(
+        control_points: [Point3; 4],
+        width0: f64,
+        width1: f64,
+        kind: CurveType,
+        mat_ptr: &Arc<Box<dyn Material>>,
+    ) -> Self {
+        let common = Arc::new(CurveCommon {
+            control_points,
+            width: [width0, width1],
+            kind,
+            mat_ptr: Arc::clone(mat_ptr),
+        });
+        Self::segment(&common, 0.0, 1.0)
+    }
+
+    /// The curve cut into `count` pieces along `u`, ready for a `BVHNode`.
+    pub fn split(
+        control_points: [Point3; 4],
+        width0: f64,
+        width1: f64,
+        kind: CurveType,
+        mat_ptr: &Arc<Box<dyn Material>>,
+        count: usize,
+    ) -> HittableList {
+        let common = Arc::new(CurveCommon {
+            control_points,
+            width: [width0, width1],
+            kind,
+            mat_ptr: Arc::clone(mat_ptr),
+        });
+
+        let mut pieces = HittableList::new();
+        for i in 0..count {
+            let u_min = i as f64 / count as f64;
+            let u_max = (i + 1) as f64 / count as f64;
+            pieces.add(Arc::new(Box::new(Self::segment(&common, u_min, u_max))));
+        }
+        pieces
+    }
+
+    fn segment(common: &Arc<CurveCommon>, u_min: f64, u_max: f64) -> Self {
+        let cp = &common.control_points;
+        let control_points = [
+            blossom(cp, u_min, u_min, u_min),
+            blossom(cp, u_min, u_min, u_max),
+            blossom(cp, u_min, u_max, u_max),
+            blossom(cp, u_max, u_max, u_max),
+        ];
+
+        // NOTE: split until each piece is within a twentieth of the width of a straight line
+        let mut bend: f64 = 0.0;
+        for i in 0..2 {
+            let second = control_points[i] - 2.0 * control_points[i + 1] + control_points[i + 2];
+            bend = bend.max(second.x().abs().max(second.y().abs()).max(second.z().abs()));
+        }
+        let epsilon = 0.05 * common.width[0].max(common.width[1]);
+        let depth = (SQRT_2 * 6.0 * bend / (8.0 * epsilon)).log2() / 2.0;
+
+        Self {
+            common: Arc::clone(common),
+            control_points,
+            u_min,
+            u_max,
+            max_depth: depth.clamp(0.0, 10.0) as u32,
+        }
+    }
+
+    fn width(&self, u: f64) -> f64 {
+        let [w0, w1] = self.common.width;
+        w0 + u * (w1 - w0)
+    }
+
+    /// Finds the nearest hit of a piece within `z_range`, `cp` in ray space where the ray runs
+    /// along +z from the origin, shrinking the range's far end to it.
+    fn recursive_hit(
+        &self,
+        cp: &[Vec3; 4],
+        u0: f64,
+        u1: f64,
+        depth: u32,
+        z_range: &mut [f64; 2],
+        best: &mut Option<(f64, f64)>,
+    ) {
+        if depth > 0 {
+            let split = split_bezier(cp);
+            let u = [u0, 0.5 * (u0 + u1), u1];
+            for seg in 0..2 {
+                let sub = [
+                    split[3 * seg],
+                    split[3 * seg + 1],
+                    split[3 * seg + 2],
+                    split[3 * seg + 3],
+                ];
+                let reach = 0.5 * self.width(u[seg]).max(self.width(u[seg + 1]));
+                let mut low = Vec3::with_value(f64::INFINITY);
+                let mut high = Vec3::with_value(f64::NEG_INFINITY);
+                for p in &sub {
+                    for c in 0..3 {
+                        low[c] = low[c].min(p[c]);
+                        high[c] = high[c].max(p[c]);
+                    }
+                }
+
+                if low.x() - reach > 0.0
+                    || high.x() + reach < 0.0
+                    || low.y() - reach > 0.0
+                    || high.y() + reach < 0.0
+                    || high.z() + reach < z_range[0]
+                    || low.z() - reach > z_range[1]
+                {
+                    continue;
+                }
+                self.recursive_hit(&sub, u[seg], u[seg + 1], depth - 1, z_range, best);
+            }
+            return;
+        }
+
+        // NOTE: the ray has to pass between the lines through the ends square to the curve
+        let edge = (cp[1].y() - cp[0].y()) * -cp[0].y() + cp[0].x() * (cp[0].x() - cp[1].x());
+        if edge < 0.0 {
+            return;
+        }
+        let edge = (cp[2].y() - cp[3].y()) * -cp[3].y() + cp[3].x() * (cp[3].x() - cp[2].x());
+        if edge < 0.0 {
+            return;
+        }
+
+        let (dx, dy) = (cp[3].x() - cp[0].x(), cp[3].y() - cp[0].y());
+        let denom = dx * dx + dy * dy;
+        if denom == 0.0 {
+            return;
+        }
+        let w = ((-cp[0].x() * dx - cp[0].y() * dy) / denom).clamp(0.0, 1.0);
+        let u = (u0 + w * (u1 - u0)).clamp(u0, u1);
+
+        let pc = eval_bezier(cp, w);
+        let width = self.width(u);
+        if pc.x() * pc.x() + pc.y() * pc.y() > 0.25 * width * width {
+            return;
+        }
+        if pc.z() < z_range[0] || pc.z() > z_range[1] {
+            return;
+        }
+
+        // NOTE: rays leaving the curve start inside it, don't let them hit it again
+        if pc.length_squared() < 0.25 * width * width {
+            return;
+        }
+
+        z_range[1] = pc.z();
+        *best = Some((pc.z(), u));
+    }
+}
+
+impl Hittable for Curve {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let length = ray.direction().length();
+        let dir = ray.direction() / length;
+        let frame = Onb::build_from_w(&dir);
+        let cp = self.control_points.map(|p| {
+            let q = p - ray.origin();
+            Vec3::with_values(
+                dot(&q, &frame.u()),
+                dot(&q, &frame.v()),
+                dot(&q, &frame.w()),
+            )
+        });
+
+        let mut z_range = [t_min * length, t_max * length];
+        let mut best = None;
+        self.recursive_hit(
+            &cp,
+            self.u_min,
+            self.u_max,
+            self.max_depth,
+            &mut z_range,
+            &mut best,
+        );
+        let Some((z, u)) = best else {
+            return false;
+        };
+
+        let t = z / length;
+
+        let common = &self.common;
+        let tangent = bezier_derivative(&common.control_points, u);
+        let across = cross(&tangent, &dir);
+        if across.near_zero() {
+            return false;
+        }
+        let across = across.unit_vector();
+
+        // NOTE: `v` grows towards `across`, from one edge of the curve to the other
+        let p = ray.at(t);
+        let width = self.width(u);
+        let offset = dot(&(p - eval_bezier(&common.control_points, u)), &across);
+        let v = (0.5 + offset / width).clamp(0.0, 1.0);
+        if is_cutout(&common.mat_ptr, u, v, &p) {
+            return false;
+        }
+
+        let facing = cross(&tangent, &across).unit_vector();
+        let outward_normal = match common.kind {
+            CurveType::Flat => facing,
+            CurveType::Round => {
+                let side = 2.0 * v - 1.0;
+                side * across + (1.0 - side * side).max(0.0).sqrt() * facing
+            }
+        };
+
+        rec.t = t;
+        rec.p = p;
+        rec.u = u;
+        rec.v = v;
+        rec.set_face_normal(ray, &outward_normal);
+        rec.dpdu = tangent;
+        rec.dpdv = width * across;
+        rec.mat_ptr = Arc::clone(&common.mat_ptr);
+        true
+    }
+
+    fn bounding_box(&self, _: f64, _: f64, output_box: &mut AABB) -> bool {
+        let reach = 0.5 * self.width(self.u_min).max(self.width(self.u_max));
+        let mut min = Point3::with_value(f64::INFINITY);
+        let mut max = Point3::with_value(f64::NEG_INFINITY);
+        for p in &self.control_points {
+            for c in 0..3 {
+                min[c] = min[c].min(p[c] - reach);
+                max[c] = max[c].max(p[c] + reach);
+            }
+        }
+
+        *output_box = AABB::new(min, max);
+        true
+    }
+}
+
+fn lerp(t: f64, a: &Vec3, b: &Vec3) -> Vec3 {
+    (1.0 - t) * *a + t * *b
+}
+
+/// The blossom of the curve, its control points for a piece come from repeated arguments.
+fn blossom(cp: &[Point3; 4], u0: f64, u1: f64, u2: f64) -> Point3 {
+    let a = [
+        lerp(u0, &cp[0], &cp[1]),
+        lerp(u0, &cp[1], &cp[2]),
+        lerp(u0, &cp[2], &cp[3]),
+    ];
+    let b = [lerp(u1, &a[0], &a[1]), lerp(u1, &a[1], &a[2])];
+    lerp(u2, &b[0], &b[1])
+}
+
+/// Control points of both halves of the curve, sharing the middle one.
+fn split_bezier(cp: &[Vec3; 4]) -> [Vec3; 7] {
+    let a = [
+        lerp(0.5, &cp[0], &cp[1]),
+        lerp(0.5, &cp[1], &cp[2]),
+        lerp(0.5, &cp[2], &cp[3]),
+    ];
+    let b = [lerp(0.5, &a[0], &a[1]), lerp(0.5, &a[1], &a[2])];
+    let middle = lerp(0.5, &b[0], &b[1]);
+    [cp[0], a[0], b[0], middle, b[1], a[2], cp[3]]
+}
+
+fn eval_bezier(cp: &[Vec3; 4], u: f64) -> Vec3 {
+    blossom(cp, u, u, u)
+}
+
+fn bezier_derivative(cp: &[Vec3; 4], u: f64) -> Vec3 {
+    let a = [
+        lerp(u, &cp[0], &cp[1]),
+        lerp(u, &cp[1], &cp[2]),
+        lerp(u, &cp[2], &cp[3]),
+    ];
+    let derivative = 3.0 * (lerp(u, &a[1], &a[2]) - lerp(u, &a[0], &a[1]));
+
+    // NOTE: coincident control points leave no derivative at the ends, use the chord
+    if derivative.near_zero() {
+        cp[3] - cp[0]
+    } else {
+        derivative
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_curve {
+    ( $control_points:expr, $width0:expr, $width1:expr, $kind:ident, $mat_ptr:expr ) => {
+        Arc::new(Box::new(Curve::new(
+            $control_points,
+            $width0,
+            $width1,
+            CurveType::$kind,
+            $mat_ptr,
+        )))
+    };
+}
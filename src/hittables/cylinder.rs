@@ -0,0 +1,182 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use super::hittable::{is_cutout, HitRecord, Hittable};
+use crate::{
+    aabb::AABB,
+    materials::material::Material,
+    ray::Ray,
+    vec3::{Point3, Vec3},
+};
+
+/// A cylinder standing on the center of its bottom `base`, along the y axis. `u` goes around
+/// it like on a `Sphere` and `v` runs up the side, caps are mapped from above.
+pub struct Cylinder {
+    base: Point3,
+    radius: f64,
+    height: f64,
+    capped: bool,
+    mat_ptr: Arc<Box<dyn Material>>,
+}
+
+impl Cylinder {
+    pub fn new(
+        base: Point3,
+        radius: f64,
+        height: f64,
+        capped: bool,
+        mat_ptr: &Arc<Box<dyn Material>>,
+    ) -> Self {
+        Self {
+            base,
+            radius,
+            height,
+            capped,
+            mat_ptr: Arc::clone(mat_ptr),
+        }
+    }
+}
+
+/// Where a ray meets a part of a cylinder or cone, relative to its base.
+pub struct AxialHit {
+    pub t: f64,
+    pub p: Point3,
+    pub outward_normal: Vec3,
+    pub u: f64,
+    pub v: f64,
+    pub dpdu: Vec3,
+    pub dpdv: Vec3,
+}
+
+/// Hit on a cap of radius `radius` at height `y`, facing up or down.
+pub fn cap_hit(origin: &Point3, dir: &Vec3, y: f64, radius: f64, up: bool) -> Option<AxialHit> {
+    if dir.y() == 0.0 {
+        return None;
+    }
+
+    let t = (y - origin.y()) / dir.y();
+    let p = *origin + t * *dir;
+    if p.x() * p.x() + p.z() * p.z() > radius * radius {
+        return None;
+    }
+
+    // NOTE: `v` runs against z on top so the tangents keep facing out of both caps
+    let sign = if up { -1.0 } else { 1.0 };
+    Some(AxialHit {
+        t,
+        p,
+        outward_normal: Vec3::with_values(0.0, -sign, 0.0),
+        u: 0.5 * (p.x() / radius + 1.0),
+        v: 0.5 * (sign * p.z() / radius + 1.0),
+        dpdu: Vec3::with_values(2.0 * radius, 0.0, 0.0),
+        dpdv: Vec3::with_values(0.0, 0.0, sign * 2.0 * radius),
+    })
+}
+
+/// Angle around the y axis, as `u` from `0` to `1`, and its tangent for a point `p` relative
+/// to the axis.
+pub fn around_y(p: &Point3) -> (f64, Vec3) {
+    let phi = (-p.z()).atan2(p.x()) + PI;
+    (
+        phi / (2.0 * PI),
+        2.0 * PI * Vec3::with_values(p.z(), 0.0, -p.x()),
+    )
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let origin = ray.origin() - self.base;
+        let dir = ray.direction();
+
+        let mut hits = Vec::with_capacity(4);
+
+        let a = dir.x() * dir.x() + dir.z() * dir.z();
+        let half_b = origin.x() * dir.x() + origin.z() * dir.z();
+        let c = origin.x() * origin.x() + origin.z() * origin.z() - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+        if a > 0.0 && discriminant >= 0.0 {
+            let sqrtd = discriminant.sqrt();
+            for t in [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a] {
+                let p = origin + t * dir;
+                if p.y() < 0.0 || p.y() > self.height {
+                    continue;
+                }
+
+                let (u, dpdu) = around_y(&p);
+                hits.push(AxialHit {
+                    t,
+                    p,
+                    outward_normal: Vec3::with_values(p.x(), 0.0, p.z()) / self.radius,
+                    u,
+                    v: p.y() / self.height,
+                    dpdu,
+                    dpdv: Vec3::with_values(0.0, self.height, 0.0),
+                });
+            }
+        }
+
+        if self.capped {
+            hits.extend(cap_hit(&origin, &dir, 0.0, self.radius, false));
+            hits.extend(cap_hit(&origin, &dir, self.height, self.radius, true));
+        }
+
+        nearest_axial_hit(hits, &self.base, &self.mat_ptr, ray, t_min, t_max, rec)
+    }
+
+    fn bounding_box(&self, _: f64, _: f64, output_box: &mut AABB) -> bool {
+        *output_box = AABB::new(
+            self.base - Vec3::with_values(self.radius, 0.0, self.radius),
+            self.base + Vec3::with_values(self.radius, self.height, self.radius),
+        );
+        true
+    }
+}
+
+/// Fills `rec` with the nearest of `hits` in range that isn't cut out.
+pub fn nearest_axial_hit(
+    mut hits: Vec<AxialHit>,
+    base: &Point3,
+    mat_ptr: &Arc<Box<dyn Material>>,
+    ray: &Ray,
+    t_min: f64,
+    t_max: f64,
+    rec: &mut HitRecord,
+) -> bool {
+    hits.sort_by(|a, b| a.t.total_cmp(&b.t));
+    for hit in hits {
+        if hit.t < t_min || t_max < hit.t {
+            continue;
+        }
+
+        let p = hit.p + *base;
+        if is_cutout(mat_ptr, hit.u, hit.v, &p) {
+            continue;
+        }
+
+        rec.t = hit.t;
+        rec.p = p;
+        rec.u = hit.u;
+        rec.v = hit.v;
+        rec.set_face_normal(ray, &hit.outward_normal.unit_vector());
+        rec.dpdu = hit.dpdu;
+        rec.dpdv = hit.dpdv;
+        rec.mat_ptr = Arc::clone(mat_ptr);
+        return true;
+    }
+
+    false
+}
+
+#[macro_export]
+macro_rules! rc_box_cylinder {
+    ( $base:expr, $radius:expr, $height:expr, $capped:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(Cylinder::new(
+            $base, $radius, $height, $capped, $mat_ptr,
+        )))
+    };
+    ( $base:expr, $radius:expr, $height:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(Cylinder::new(
+            $base, $radius, $height, true, $mat_ptr,
+        )))
+    };
+}
@@ -0,0 +1,118 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use rand::Rng;
+
+use super::hittable::{is_cutout, HitRecord, Hittable, SurfaceSample};
+use crate::{
+    aabb::AABB,
+    materials::material::Material,
+    onb::Onb,
+    ray::Ray,
+    vec3::{cross, dot, Point3, Vec3},
+};
+
+/// A flat disk facing along `normal`. `u` goes once around it and `v` runs from `1` at the
+/// center to `0` at the rim.
+pub struct Disk {
+    center: Point3,
+    normal: Vec3,
+    radius: f64,
+    /// Directions in the disk's plane where the angle is `0` and a quarter turn.
+    tangent: Vec3,
+    bitangent: Vec3,
+    mat_ptr: Arc<Box<dyn Material>>,
+}
+
+impl Disk {
+    pub fn new(
+        center: Point3,
+        normal: Vec3,
+        radius: f64,
+        mat_ptr: &Arc<Box<dyn Material>>,
+    ) -> Self {
+        let normal = normal.unit_vector();
+        let tangent = Onb::build_from_w(&normal).u();
+        Self {
+            center,
+            normal,
+            radius,
+            tangent,
+            bitangent: cross(&normal, &tangent),
+            mat_ptr: Arc::clone(mat_ptr),
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let denom = dot(&self.normal, &ray.direction());
+        if denom.abs() < 1e-8 {
+            return false;
+        }
+
+        let t = dot(&self.normal, &(self.center - ray.origin())) / denom;
+        if t < t_min || t > t_max {
+            return false;
+        }
+
+        let p = ray.at(t);
+        let offset = p - self.center;
+        let r = offset.length();
+        if r > self.radius {
+            return false;
+        }
+
+        let x = dot(&offset, &self.tangent);
+        let y = dot(&offset, &self.bitangent);
+        let phi = y.atan2(x).rem_euclid(2.0 * PI);
+        let u = phi / (2.0 * PI);
+        let v = 1.0 - r / self.radius;
+        if is_cutout(&self.mat_ptr, u, v, &p) {
+            return false;
+        }
+
+        rec.u = u;
+        rec.v = v;
+        rec.t = t;
+        rec.p = p;
+        rec.set_face_normal(ray, &self.normal);
+
+        // NOTE: keep a sliver of radius so the tangents don't vanish at the center
+        let radial = phi.cos() * self.tangent + phi.sin() * self.bitangent;
+        let around = -phi.sin() * self.tangent + phi.cos() * self.bitangent;
+        rec.dpdu = 2.0 * PI * r.max(1e-8 * self.radius) * around;
+        rec.dpdv = -self.radius * radial;
+        rec.mat_ptr = Arc::clone(&self.mat_ptr);
+        true
+    }
+
+    fn bounding_box(&self, _: f64, _: f64, output_box: &mut AABB) -> bool {
+        let mut extent = Vec3::new();
+        for a in 0..3 {
+            let n = self.normal[a];
+            extent[a] = self.radius * (1.0 - n * n).max(0.0).sqrt() + 0.0001;
+        }
+
+        *output_box = AABB::new(self.center - extent, self.center + extent);
+        true
+    }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let mut rng = rand::thread_rng();
+        let r = self.radius * rng.gen::<f64>().sqrt();
+        let phi = 2.0 * PI * rng.gen::<f64>();
+        Some(SurfaceSample {
+            p: self.center + r * phi.cos() * self.tangent + r * phi.sin() * self.bitangent,
+            normal: self.normal,
+            area: PI * self.radius * self.radius,
+        })
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_disk {
+    ( $center:expr, $normal:expr, $radius:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(Disk::new($center, $normal, $radius, $mat_ptr)))
+    };
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use crate::{aabb::AABB, ray::Ray};
+
+use super::hittable::{HitRecord, Hittable, SurfaceSample};
+
+/// Swaps which side of a surface counts as the front, like turning a one-sided light
+/// around.
+pub struct FlipFace {
+    ptr: Arc<Box<dyn Hittable>>,
+}
+
+impl FlipFace {
+    pub fn new(p: &Arc<Box<dyn Hittable>>) -> Self {
+        Self { ptr: Arc::clone(p) }
+    }
+}
+
+impl Hittable for FlipFace {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        if !self.ptr.hit(ray, t_min, t_max, rec) {
+            return false;
+        }
+
+        rec.front_face = !rec.front_face;
+        true
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64, output_box: &mut AABB) -> bool {
+        self.ptr.bounding_box(time0, time1, output_box)
+    }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        self.ptr.sample_surface()
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_flip_face {
+    ( $ptr:expr ) => {
+        Arc::new(Box::new(FlipFace::new($ptr)))
+    };
+}
@@ -0,0 +1,321 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use image::io::Reader;
+
+use super::{
+    hittable::{is_cutout, HitRecord, Hittable},
+    mesh::triangle_hit,
+};
+use crate::{
+    aabb::AABB,
+    materials::material::Material,
+    ray::Ray,
+    textures::perlin::Perlin,
+    vec3::{cross, dot, Point3, Vec3},
+};
+
+/// Terrain from a grid of heights, spread over the `size.x` by `size.z` rectangle starting at
+/// `corner` and rising up to `size.y` above it. Each cell between four samples is split into
+/// two triangles, shaded with normals smoothed across the grid. `u` runs along x and `v`
+/// against z, so an image draped over it lines up with the one the heights came from.
+pub struct Heightfield {
+    /// Heights from `0` to `1`, x fastest, then z.
+    heights: Vec<f64>,
+    normals: Vec<Vec3>,
+    /// Lowest and highest height in each cell, to skip cells the ray passes over.
+    cell_ranges: Vec<(f64, f64)>,
+    nx: usize,
+    nz: usize,
+    corner: Point3,
+    size: Vec3,
+    bbox: AABB,
+    mat_ptr: Arc<Box<dyn Material>>,
+}
+
+impl Heightfield {
+    /// Heights in `[0, 1]` on a `resolution` of at least 2 by 2 samples, stored x fastest.
+    pub fn new(
+        heights: Vec<f64>,
+        resolution: [usize; 2],
+        corner: Point3,
+        size: Vec3,
+        mat_ptr: &Arc<Box<dyn Material>>,
+    ) -> Self {
+        let [nx, nz] = resolution;
+        assert!(nx >= 2 && nz >= 2 && heights.len() == nx * nz);
+
+        let mut cell_ranges = Vec::with_capacity((nx - 1) * (nz - 1));
+        for j in 0..nz - 1 {
+            for i in 0..nx - 1 {
+                let corners = [
+                    heights[j * nx + i],
+                    heights[j * nx + i + 1],
+                    heights[(j + 1) * nx + i],
+                    heights[(j + 1) * nx + i + 1],
+                ];
+                cell_ranges.push((
+                    corners.iter().copied().fold(f64::INFINITY, f64::min),
+                    corners.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                ));
+            }
+        }
+
+        let (low, high) = cell_ranges
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), range| {
+                (low.min(range.0), high.max(range.1))
+            });
+        // NOTE: pad flat terrain so the box keeps some thickness
+        let bbox = AABB::new(
+            corner + Vec3::with_values(0.0, low * size.y() - 0.0001, 0.0),
+            corner + Vec3::with_values(size.x(), high * size.y() + 0.0001, size.z()),
+        );
+
+        let mut field = Self {
+            heights,
+            normals: Vec::new(),
+            cell_ranges,
+            nx,
+            nz,
+            corner,
+            size,
+            bbox,
+            mat_ptr: Arc::clone(mat_ptr),
+        };
+        field.normals = (0..nz)
+            .flat_map(|j| (0..nx).map(move |i| (i, j)))
+            .map(|(i, j)| field.vertex_normal(i, j))
+            .collect();
+        field
+    }
+
+    /// Heights from the brightness of a grayscale image, white being the highest. Rows run
+    /// along z. A missing image gives flat ground, much like `ImageTexture` turns cyan.
+    pub fn from_image<T: AsRef<Path>>(
+        file: T,
+        corner: Point3,
+        size: Vec3,
+        mat_ptr: &Arc<Box<dyn Material>>,
+    ) -> Self {
+        let data = Reader::open(file)
+            .ok()
+            .and_then(|x| x.decode().map(|x| x.to_luma16()).ok());
+
+        match data {
+            Some(data) if data.width() >= 2 && data.height() >= 2 => {
+                let heights = data
+                    .pixels()
+                    .map(|pixel| pixel.0[0] as f64 / 65535.0)
+                    .collect();
+                Self::new(
+                    heights,
+                    [data.width() as usize, data.height() as usize],
+                    corner,
+                    size,
+                    mat_ptr,
+                )
+            }
+            _ => Self::new(vec![0.0; 4], [2, 2], corner, size, mat_ptr),
+        }
+    }
+
+    /// Rolling hills from `octaves` of Perlin fBm, sampled on a `resolution` grid. `scale` is
+    /// the frequency of the broadest layer over the terrain's width. Heights are stretched to
+    /// fill `size.y`.
+    pub fn from_fbm(
+        scale: f64,
+        octaves: u64,
+        resolution: [usize; 2],
+        corner: Point3,
+        size: Vec3,
+        mat_ptr: &Arc<Box<dyn Material>>,
+    ) -> Self {
+        let [nx, nz] = resolution;
+        let noise = Perlin::new();
+        let aspect = size.z() / size.x();
+        let mut heights: Vec<f64> = (0..nz)
+            .flat_map(|j| (0..nx).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                let p = Point3::with_values(
+                    scale * i as f64 / (nx - 1) as f64,
+                    0.0,
+                    scale * aspect * j as f64 / (nz - 1) as f64,
+                );
+                noise.fbm(&p, octaves)
+            })
+            .collect();
+
+        let low = heights.iter().copied().fold(f64::INFINITY, f64::min);
+        let high = heights.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let range = (high - low).max(1e-12);
+        for height in heights.iter_mut() {
+            *height = (*height - low) / range;
+        }
+
+        Self::new(heights, resolution, corner, size, mat_ptr)
+    }
+
+    fn cell_size(&self) -> (f64, f64) {
+        (
+            self.size.x() / (self.nx - 1) as f64,
+            self.size.z() / (self.nz - 1) as f64,
+        )
+    }
+
+    fn vertex(&self, i: usize, j: usize) -> Point3 {
+        let (dx, dz) = self.cell_size();
+        self.corner
+            + Vec3::with_values(
+                i as f64 * dx,
+                self.heights[j * self.nx + i] * self.size.y(),
+                j as f64 * dz,
+            )
+    }
+
+    /// Normal from the slope between the neighbouring samples.
+    fn vertex_normal(&self, i: usize, j: usize) -> Vec3 {
+        let left = self.vertex(i.saturating_sub(1), j);
+        let right = self.vertex((i + 1).min(self.nx - 1), j);
+        let back = self.vertex(i, j.saturating_sub(1));
+        let front = self.vertex(i, (j + 1).min(self.nz - 1));
+        cross(&(front - back), &(right - left)).unit_vector()
+    }
+
+    /// Tests the two triangles of cell `(i, j)`, filling `rec` with the nearest hit.
+    fn hit_cell(
+        &self,
+        i: usize,
+        j: usize,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        rec: &mut HitRecord,
+    ) -> bool {
+        let corners = [(i, j), (i + 1, j), (i + 1, j + 1), (i, j + 1)];
+        let mut hits: Vec<_> = [
+            [corners[0], corners[2], corners[1]],
+            [corners[0], corners[3], corners[2]],
+        ]
+        .into_iter()
+        .filter_map(|triangle| {
+            let [a, b, c] = triangle.map(|(i, j)| self.vertex(i, j));
+            triangle_hit(ray, &a, &b, &c)
+                .filter(|(t, _, _)| t_min < *t && *t < t_max)
+                .map(|(t, b1, b2)| (t, triangle, b1, b2))
+        })
+        .collect();
+        hits.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        for (t, triangle, b1, b2) in hits {
+            let [a, b, c] = triangle.map(|(i, j)| self.vertex(i, j));
+            let p = ray.at(t);
+            let u = (p.x() - self.corner.x()) / self.size.x();
+            let v = 1.0 - (p.z() - self.corner.z()) / self.size.z();
+            if is_cutout(&self.mat_ptr, u, v, &p) {
+                continue;
+            }
+
+            let [na, nb, nc] = triangle.map(|(i, j)| self.normals[j * self.nx + i]);
+            let shading_normal = ((1.0 - b1 - b2) * na + b1 * nb + b2 * nc).unit_vector();
+
+            // NOTE: tangents follow the triangle's slope so they stay on the surface
+            let face = cross(&(b - a), &(c - a));
+            let (slope_x, slope_z) = (-face.x() / face.y(), -face.z() / face.y());
+
+            rec.t = t;
+            rec.p = p;
+            rec.u = u;
+            rec.v = v;
+            rec.set_face_normal(ray, &shading_normal);
+            rec.dpdu = self.size.x() * Vec3::with_values(1.0, slope_x, 0.0);
+            rec.dpdv = -self.size.z() * Vec3::with_values(0.0, slope_z, 1.0);
+            rec.mat_ptr = Arc::clone(&self.mat_ptr);
+            return true;
+        }
+
+        false
+    }
+}
+
+impl Hittable for Heightfield {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let (t_enter, t_exit) = match self.bbox.clip(ray, t_min, t_max) {
+            Some(range) => range,
+            None => return false,
+        };
+
+        // NOTE: walk the cells under the ray in order, as in Amanatides and Woo's grid
+        // traversal, so the first cell with a hit holds the nearest one
+        let (dx, dz) = self.cell_size();
+        let origin = ray.origin() - self.corner;
+        let dir = ray.direction();
+        let start = origin + t_enter * dir;
+        let mut cell = [
+            ((start.x() / dx).floor() as isize).clamp(0, self.nx as isize - 2),
+            ((start.z() / dz).floor() as isize).clamp(0, self.nz as isize - 2),
+        ];
+
+        let mut step = [0; 2];
+        let mut t_next = [f64::INFINITY; 2];
+        let mut t_delta = [f64::INFINITY; 2];
+        let axes = [(dir.x(), origin.x(), dx), (dir.z(), origin.z(), dz)];
+        for (axis, (d, o, cell_size)) in axes.into_iter().enumerate() {
+            if d > 0.0 {
+                step[axis] = 1;
+                t_next[axis] = ((cell[axis] + 1) as f64 * cell_size - o) / d;
+                t_delta[axis] = cell_size / d;
+            } else if d < 0.0 {
+                step[axis] = -1;
+                t_next[axis] = (cell[axis] as f64 * cell_size - o) / d;
+                t_delta[axis] = -cell_size / d;
+            }
+        }
+
+        let mut t_cell = t_enter;
+        loop {
+            let t_leave = t_next[0].min(t_next[1]).min(t_exit);
+            let (i, j) = (cell[0] as usize, cell[1] as usize);
+
+            let (low, high) = self.cell_ranges[j * (self.nx - 1) + i];
+            let y_enter = origin.y() + t_cell * dir.y();
+            let y_leave = origin.y() + t_leave * dir.y();
+            let below = y_enter.min(y_leave) <= high * self.size.y() + 0.0001;
+            let above = y_enter.max(y_leave) >= low * self.size.y() - 0.0001;
+            if below && above && self.hit_cell(i, j, ray, t_min, t_max, rec) {
+                return true;
+            }
+
+            if t_leave >= t_exit {
+                return false;
+            }
+
+            let axis = if t_next[0] < t_next[1] { 0 } else { 1 };
+            cell[axis] += step[axis];
+            let limit = [self.nx, self.nz][axis] as isize - 2;
+            if cell[axis] < 0 || cell[axis] > limit {
+                return false;
+            }
+            t_cell = t_leave;
+            t_next[axis] += t_delta[axis];
+        }
+    }
+
+    fn bounding_box(&self, _: f64, _: f64, output_box: &mut AABB) -> bool {
+        *output_box = self.bbox;
+        true
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_heightfield {
+    ( $heights:expr, $resolution:expr, $corner:expr, $size:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(Heightfield::new(
+            $heights,
+            $resolution,
+            $corner,
+            $size,
+            $mat_ptr,
+        )))
+    };
+}
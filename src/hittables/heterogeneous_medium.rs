@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::{
+    aabb::AABB,
+    materials::{isotropic::Isotropic, material::Material},
+    ray::Ray,
+    textures::density::DensityField,
+    vec3::{Color, Vec3},
+};
+
+use super::{
+    constant_medium::inside_intervals,
+    hittable::{HitRecord, Hittable},
+};
+
+/// A participating medium whose density varies through space, like clouds and smoke.
+/// Collisions are found with delta tracking against the field's maximum density.
+pub struct HeterogeneousMedium {
+    boundary: Arc<Box<dyn Hittable>>,
+    density: Arc<Box<dyn DensityField>>,
+    phase_function: Arc<Box<dyn Material>>,
+}
+
+impl HeterogeneousMedium {
+    pub fn new(
+        b: &Arc<Box<dyn Hittable>>,
+        d: &Arc<Box<dyn DensityField>>,
+        a: &Arc<Box<dyn Material>>,
+    ) -> Self {
+        Self {
+            boundary: Arc::clone(b),
+            density: Arc::clone(d),
+            phase_function: Arc::clone(a),
+        }
+    }
+
+    pub fn with_color(
+        b: &Arc<Box<dyn Hittable>>,
+        d: &Arc<Box<dyn DensityField>>,
+        a: Color,
+    ) -> Self {
+        Self {
+            boundary: Arc::clone(b),
+            density: Arc::clone(d),
+            phase_function: Arc::new(Box::new(Isotropic::new(a))),
+        }
+    }
+}
+
+impl Hittable for HeterogeneousMedium {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let majorant = self.density.max_density();
+        if majorant <= 0.0 {
+            return false;
+        }
+
+        let mut rng = rand::thread_rng();
+        let ray_length = ray.direction().length();
+
+        for (t0, t1) in inside_intervals(&self.boundary, ray, t_min, t_max) {
+            let mut t = t0;
+            loop {
+                t -= (1.0 - rng.gen::<f64>()).ln() / (majorant * ray_length);
+                if t >= t1 {
+                    break;
+                }
+
+                // NOTE: real collision with probability density / majorant, otherwise a null one
+                if rng.gen::<f64>() * majorant < self.density.density(&ray.at(t)) {
+                    rec.t = t;
+                    rec.p = ray.at(t);
+                    rec.normal = Vec3::with_values(1.0, 0.0, 0.0); // arbitrary
+                    rec.front_face = true; // also arbitrary
+                    rec.mat_ptr = Arc::clone(&self.phase_function);
+
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64, output_box: &mut AABB) -> bool {
+        self.boundary.bounding_box(time0, time1, output_box)
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_heterogeneous_medium {
+    ( $b:expr, $d:expr, $color:expr, Color ) => {
+        Arc::new(Box::new(HeterogeneousMedium::with_color($b, $d, $color)))
+    };
+    ( $b:expr, $d:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(HeterogeneousMedium::new($b, $d, $mat_ptr)))
+    };
+}
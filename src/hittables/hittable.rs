@@ -1,10 +1,12 @@
 use std::sync::Arc;
 
+use rand::Rng;
+
 use crate::{
     aabb::{surrounding_box, AABB},
     materials::{lambertian::Lambertian, material::Material},
     ray::Ray,
-    vec3::{dot, Point3, Vec3},
+    vec3::{dot, Color, Point3, Vec3},
 };
 
 #[derive(Clone)]
@@ -16,6 +18,10 @@ pub struct HitRecord {
     pub u: f64,
     pub v: f64,
     pub front_face: bool,
+    pub dpdu: Vec3,
+    pub dpdv: Vec3,
+    /// Color interpolated from a mesh's vertex colors, for a `VertexColorTexture`.
+    pub vertex_color: Option<Color>,
 }
 
 impl Default for HitRecord {
@@ -28,6 +34,9 @@ impl Default for HitRecord {
             u: 0.0,
             v: 0.0,
             front_face: false,
+            dpdu: Vec3::new(),
+            dpdv: Vec3::new(),
+            vertex_color: None,
         }
     }
 }
@@ -43,9 +52,49 @@ impl HitRecord {
     }
 }
 
-pub trait Hittable {
+/// Whether a hit on `mat_ptr` should be skipped because its opacity lets the ray through.
+/// Partially transparent texels are resolved stochastically.
+pub fn is_cutout(mat_ptr: &Arc<Box<dyn Material>>, u: f64, v: f64, p: &Point3) -> bool {
+    let alpha = mat_ptr.alpha(u, v, p);
+    if alpha >= 1.0 {
+        return false;
+    }
+
+    alpha <= 0.0 || rand::thread_rng().gen::<f64>() >= alpha
+}
+
+/// A point picked uniformly over a surface, used to sample light from emissive objects.
+pub struct SurfaceSample {
+    pub p: Point3,
+    /// Unit outward normal at `p`.
+    pub normal: Vec3,
+    /// Total area of the surface, the density of `p` is its inverse.
+    pub area: f64,
+}
+
+pub trait Hittable: Send + Sync {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool;
     fn bounding_box(&self, time0: f64, time1: f64, output_box: &mut AABB) -> bool;
+
+    /// Picks a point uniformly over the surface, `None` for objects that can't be sampled.
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        None
+    }
+
+    /// Every point between `t_min` and `t_max` where `ray` crosses the surface, nearest first,
+    /// with `front_face` set where it enters. CSG uses these to tell where a ray is inside a
+    /// closed object. The default steps past each hit from `hit`, so grazing rays can come
+    /// out with an odd number of crossings.
+    fn hit_all(&self, ray: &Ray, t_min: f64, t_max: f64) -> Vec<HitRecord> {
+        let mut hits = Vec::new();
+        let mut rec = HitRecord::default();
+        let mut t_from = t_min;
+        while self.hit(ray, t_from, t_max, &mut rec) {
+            t_from = rec.t + 1e-7 * rec.t.abs().max(1.0);
+            hits.push(rec.clone());
+        }
+        hits
+    }
 }
 
 pub struct HittableList {
@@ -83,6 +132,8 @@ impl Hittable for HittableList {
         let mut closest_so_far = t_max;
 
         for object in self.objects.iter() {
+            // NOTE: only meshes set a vertex color, don't let one leak into another hit
+            temp_rec.vertex_color = None;
             if object.hit(ray, t_min, closest_so_far, &mut temp_rec) {
                 hit_anything = true;
                 closest_so_far = temp_rec.t;
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use crate::{aabb::AABB, materials::material::Material, ray::Ray, transform::Transform};
+
+use super::hittable::{HitRecord, Hittable};
+
+/// One placement of a shared `prototype`, so many copies of a detailed object cost a
+/// transform each rather than a copy of the geometry. Build the prototype's own `BVHNode`
+/// once and a top-level one over its instances. With `material` set, every hit on this copy
+/// is shaded with it instead of the prototype's materials, though cutouts still follow the
+/// prototype's.
+pub struct Instance {
+    prototype: Arc<Box<dyn Hittable>>,
+    transform: Transform,
+    material: Option<Arc<Box<dyn Material>>>,
+    has_box: bool,
+    bbox: AABB,
+}
+
+impl Instance {
+    pub fn new(
+        prototype: &Arc<Box<dyn Hittable>>,
+        transform: Transform,
+        material: Option<&Arc<Box<dyn Material>>>,
+    ) -> Self {
+        let mut bbox = AABB::default();
+        let has_box = prototype.bounding_box(0.0, 1.0, &mut bbox);
+
+        Self {
+            prototype: Arc::clone(prototype),
+            transform,
+            material: material.map(Arc::clone),
+            has_box,
+            bbox: transform.bounds(&bbox),
+        }
+    }
+}
+
+impl Hittable for Instance {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        if !self
+            .prototype
+            .hit(&self.transform.inverse_ray(ray), t_min, t_max, rec)
+        {
+            return false;
+        }
+
+        rec.p = self.transform.point(&rec.p);
+        rec.normal = self.transform.normal(&rec.normal);
+        rec.dpdu = self.transform.vector(&rec.dpdu);
+        rec.dpdv = self.transform.vector(&rec.dpdv);
+        if let Some(material) = &self.material {
+            rec.mat_ptr = Arc::clone(material);
+        }
+
+        true
+    }
+
+    fn bounding_box(&self, _: f64, _: f64, output_box: &mut AABB) -> bool {
+        *output_box = self.bbox;
+        self.has_box
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_instance {
+    ( $prototype:expr, $transform:expr, $material:expr ) => {
+        Arc::new(Box::new(Instance::new(
+            $prototype,
+            $transform,
+            Some($material),
+        )))
+    };
+    ( $prototype:expr, $transform:expr ) => {
+        Arc::new(Box::new(Instance::new($prototype, $transform, None)))
+    };
+}
@@ -0,0 +1,345 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use rand::Rng;
+
+use super::{
+    bvh::BVHNode,
+    hittable::{is_cutout, HitRecord, Hittable, HittableList, SurfaceSample},
+    ply, stl,
+};
+use crate::{
+    aabb::AABB,
+    materials::material::Material,
+    ray::Ray,
+    vec3::{cross, dot, Color, Point3, Vec3},
+};
+
+/// Vertices and triangles of a mesh. `normals`, `uvs` and `colors` are either empty or hold
+/// one entry per vertex, and each triangle lists its corners counter-clockwise seen from the
+/// outside.
+#[derive(Default)]
+pub struct MeshData {
+    pub positions: Vec<Point3>,
+    pub normals: Vec<Vec3>,
+    pub uvs: Vec<[f64; 2]>,
+    pub colors: Vec<Color>,
+    pub triangles: Vec<[usize; 3]>,
+}
+
+/// A triangle mesh under its own `BVHNode`, shaded with normals smoothed between the vertex
+/// normals when it has them. Without texture coordinates `u` and `v` are the barycentric
+/// coordinates of the hit on its triangle. Vertex colors are passed on to the hit for a
+/// `VertexColorTexture`.
+pub struct TriangleMesh {
+    bvh: Option<BVHNode>,
+    triangles: Vec<Arc<Box<dyn Hittable>>>,
+    /// Running total of triangle areas, to pick triangles in proportion to their size.
+    area_cdf: Vec<f64>,
+}
+
+impl TriangleMesh {
+    /// Vertex attributes that don't have one entry per vertex are left out with a warning.
+    pub fn new(mut data: MeshData, mat_ptr: &Arc<Box<dyn Material>>) -> Self {
+        let vertices = data.positions.len();
+        check_attribute("normals", &mut data.normals, vertices);
+        check_attribute("uvs", &mut data.uvs, vertices);
+        check_attribute("colors", &mut data.colors, vertices);
+
+        let data = Arc::new(data);
+        let mut triangles = HittableList::new();
+        let mut area_cdf = Vec::with_capacity(data.triangles.len());
+        let mut total_area = 0.0;
+        for (index, corners) in data.triangles.iter().enumerate() {
+            if corners.iter().any(|&i| i >= data.positions.len()) {
+                continue;
+            }
+
+            let [a, b, c] = corners.map(|i| data.positions[i]);
+            let area = 0.5 * cross(&(b - a), &(c - a)).length();
+            // NOTE: degenerate triangles can't be hit, and would trip up the BVH sort
+            if area == 0.0 || !area.is_finite() {
+                continue;
+            }
+
+            total_area += area;
+            area_cdf.push(total_area);
+            triangles.add(Arc::new(Box::new(Triangle {
+                data: Arc::clone(&data),
+                index,
+                mat_ptr: Arc::clone(mat_ptr),
+            })));
+        }
+
+        let bvh = if triangles.objects.is_empty() {
+            None
+        } else {
+            // NOTE: the BVH reorders its list, keep ours in step with `area_cdf`
+            let mut sorted = HittableList {
+                objects: triangles.objects.clone(),
+            };
+            Some(BVHNode::new(&mut sorted, 0.0, 1.0))
+        };
+
+        Self {
+            bvh,
+            triangles: triangles.objects,
+            area_cdf,
+        }
+    }
+
+    /// Loads an ASCII or binary PLY file, empty if that fails.
+    pub fn from_ply<T: AsRef<Path>>(file: T, mat_ptr: &Arc<Box<dyn Material>>) -> Self {
+        Self::from_file(file, ply::parse, mat_ptr)
+    }
+
+    /// Loads an ASCII or binary STL file, empty if that fails.
+    pub fn from_stl<T: AsRef<Path>>(file: T, mat_ptr: &Arc<Box<dyn Material>>) -> Self {
+        Self::from_file(file, stl::parse, mat_ptr)
+    }
+
+    fn from_file<T: AsRef<Path>>(
+        file: T,
+        parse: fn(&[u8]) -> Option<MeshData>,
+        mat_ptr: &Arc<Box<dyn Material>>,
+    ) -> Self {
+        match fs::read(&file).ok().and_then(|bytes| parse(&bytes)) {
+            Some(data) => Self::new(data, mat_ptr),
+            None => {
+                eprintln!("Unable to load mesh {}", file.as_ref().display());
+                Self::new(MeshData::default(), mat_ptr)
+            }
+        }
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        match &self.bvh {
+            Some(bvh) => bvh.hit(ray, t_min, t_max, rec),
+            None => false,
+        }
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64, output_box: &mut AABB) -> bool {
+        match &self.bvh {
+            Some(bvh) => bvh.bounding_box(time0, time1, output_box),
+            None => false,
+        }
+    }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let total_area = *self.area_cdf.last()?;
+        let target = rand::thread_rng().gen::<f64>() * total_area;
+        let index = self
+            .area_cdf
+            .partition_point(|&area| area < target)
+            .min(self.triangles.len() - 1);
+
+        let sample = self.triangles[index].sample_surface()?;
+        Some(SurfaceSample {
+            area: total_area,
+            ..sample
+        })
+    }
+}
+
+struct Triangle {
+    data: Arc<MeshData>,
+    index: usize,
+    mat_ptr: Arc<Box<dyn Material>>,
+}
+
+impl Triangle {
+    fn corners(&self) -> [usize; 3] {
+        self.data.triangles[self.index]
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let data = &self.data;
+        let [i0, i1, i2] = self.corners();
+        let (a, b, c) = (data.positions[i0], data.positions[i1], data.positions[i2]);
+        let Some((t, b1, b2)) = triangle_hit(ray, &a, &b, &c) else {
+            return false;
+        };
+        if t < t_min || t > t_max {
+            return false;
+        }
+
+        let b0 = 1.0 - b1 - b2;
+        let (u, v, dpdu, dpdv) = if data.uvs.is_empty() {
+            (b1, b2, b - a, c - a)
+        } else {
+            let [uv0, uv1, uv2] = [data.uvs[i0], data.uvs[i1], data.uvs[i2]];
+            let u = b0 * uv0[0] + b1 * uv1[0] + b2 * uv2[0];
+            let v = b0 * uv0[1] + b1 * uv1[1] + b2 * uv2[1];
+
+            // NOTE: solve for the directions `u` and `v` grow in from the edges and their uvs
+            let (du1, dv1) = (uv1[0] - uv0[0], uv1[1] - uv0[1]);
+            let (du2, dv2) = (uv2[0] - uv0[0], uv2[1] - uv0[1]);
+            let det = du1 * dv2 - dv1 * du2;
+            if det.abs() < 1e-12 {
+                (u, v, b - a, c - a)
+            } else {
+                let dpdu = (dv2 * (b - a) - dv1 * (c - a)) / det;
+                let dpdv = (du1 * (c - a) - du2 * (b - a)) / det;
+                (u, v, dpdu, dpdv)
+            }
+        };
+
+        let p = b0 * a + b1 * b + b2 * c;
+        if is_cutout(&self.mat_ptr, u, v, &p) {
+            return false;
+        }
+
+        let geometric = cross(&(b - a), &(c - a)).unit_vector();
+        let outward_normal = if data.normals.is_empty() {
+            geometric
+        } else {
+            let smooth = b0 * data.normals[i0] + b1 * data.normals[i1] + b2 * data.normals[i2];
+            if smooth.near_zero() {
+                geometric
+            } else {
+                smooth.unit_vector()
+            }
+        };
+
+        rec.t = t;
+        rec.p = p;
+        rec.u = u;
+        rec.v = v;
+        rec.set_face_normal(ray, &outward_normal);
+        rec.dpdu = dpdu;
+        rec.dpdv = dpdv;
+        rec.vertex_color = if data.colors.is_empty() {
+            None
+        } else {
+            Some(b0 * data.colors[i0] + b1 * data.colors[i1] + b2 * data.colors[i2])
+        };
+        rec.mat_ptr = Arc::clone(&self.mat_ptr);
+        true
+    }
+
+    fn bounding_box(&self, _: f64, _: f64, output_box: &mut AABB) -> bool {
+        let mut min = Point3::with_value(f64::INFINITY);
+        let mut max = Point3::with_value(f64::NEG_INFINITY);
+        for i in self.corners() {
+            let p = self.data.positions[i];
+            for c in 0..3 {
+                min[c] = min[c].min(p[c]);
+                max[c] = max[c].max(p[c]);
+            }
+        }
+
+        // NOTE: pad triangles lying in an axis plane so the box keeps some thickness
+        *output_box = AABB::new(
+            min - Vec3::with_value(0.0001),
+            max + Vec3::with_value(0.0001),
+        );
+        true
+    }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let mut rng = rand::thread_rng();
+        let [a, b, c] = self.corners().map(|i| self.data.positions[i]);
+
+        // NOTE: folding the far half of the square back keeps the points uniform
+        let (mut b1, mut b2) = (rng.gen::<f64>(), rng.gen::<f64>());
+        if b1 + b2 > 1.0 {
+            (b1, b2) = (1.0 - b1, 1.0 - b2);
+        }
+
+        let normal = cross(&(b - a), &(c - a));
+        Some(SurfaceSample {
+            p: a + b1 * (b - a) + b2 * (c - a),
+            normal: normal.unit_vector(),
+            area: 0.5 * normal.length(),
+        })
+    }
+}
+
+fn check_attribute<T>(name: &str, values: &mut Vec<T>, vertices: usize) {
+    if !values.is_empty() && values.len() != vertices {
+        eprintln!(
+            "Mesh has {} {name} for {vertices} vertices, ignoring them",
+            values.len()
+        );
+        values.clear();
+    }
+}
+
+/// Möller–Trumbore intersection of `ray` with the triangle `abc`, giving the ray distance and
+/// the barycentric weights of `b` and `c`.
+pub fn triangle_hit(ray: &Ray, a: &Point3, b: &Point3, c: &Point3) -> Option<(f64, f64, f64)> {
+    let edge1 = *b - *a;
+    let edge2 = *c - *a;
+    let pvec = cross(&ray.direction(), &edge2);
+    let det = dot(&edge1, &pvec);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+
+    let inv_det = 1.0 / det;
+    let tvec = ray.origin() - *a;
+    let b1 = dot(&tvec, &pvec) * inv_det;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+
+    let qvec = cross(&tvec, &edge1);
+    let b2 = dot(&ray.direction(), &qvec) * inv_det;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+
+    Some((dot(&edge2, &qvec) * inv_det, b1, b2))
+}
+
+#[macro_export]
+macro_rules! rc_box_triangle_mesh {
+    ( Ply, $file:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(TriangleMesh::from_ply($file, $mat_ptr)))
+    };
+    ( Stl, $file:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(TriangleMesh::from_stl($file, $mat_ptr)))
+    };
+    ( $data:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(TriangleMesh::new($data, $mat_ptr)))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::lambertian::Lambertian;
+
+    #[test]
+    fn drops_attributes_without_one_entry_per_vertex() {
+        let data = MeshData {
+            positions: vec![
+                Point3::with_values(0.0, 0.0, 0.0),
+                Point3::with_values(1.0, 0.0, 0.0),
+                Point3::with_values(0.0, 1.0, 0.0),
+            ],
+            normals: vec![Vec3::with_values(0.0, 0.0, 1.0)],
+            colors: vec![Color::with_value(1.0); 3],
+            triangles: vec![[0, 1, 2]],
+            ..MeshData::default()
+        };
+        let mat: Arc<Box<dyn Material>> = Arc::new(Box::new(Lambertian::with_color(Color::new())));
+        let mesh = TriangleMesh::new(data, &mat);
+
+        let ray = Ray::new(
+            Point3::with_values(0.25, 0.25, 1.0),
+            Vec3::with_values(0.0, 0.0, -1.0),
+            0.0,
+        );
+        let mut rec = HitRecord::default();
+        assert!(mesh.hit(&ray, 0.001, f64::INFINITY, &mut rec));
+        assert_eq!(rec.normal.z(), 1.0);
+        assert!(rec.vertex_color.is_some());
+    }
+}
@@ -1,10 +1,29 @@
+pub mod animated;
 pub mod bvh;
+pub mod capsule;
+pub mod cone;
 pub mod constant_medium;
+pub mod csg;
 pub mod cuboid;
+pub mod curve;
+pub mod cylinder;
+pub mod disk;
+pub mod flip_face;
+pub mod heightfield;
+pub mod heterogeneous_medium;
 pub mod hittable;
+pub mod instance;
+pub mod mesh;
 pub mod moving_sphere;
+pub mod plane;
+pub mod ply;
+pub mod quad;
 pub mod rotate;
+pub mod sdf;
 pub mod sphere;
+pub mod stl;
+pub mod subsurface;
+pub mod torus;
 pub mod translate;
 
 pub mod rect;
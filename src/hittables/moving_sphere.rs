@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use super::hittable::{HitRecord, Hittable};
+use super::{
+    hittable::{is_cutout, HitRecord, Hittable},
+    sphere::Sphere,
+};
 use crate::{
     aabb::{surrounding_box, AABB},
     materials::material::Material,
@@ -44,7 +47,8 @@ impl MovingSphere {
 
 impl Hittable for MovingSphere {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
-        let oc = ray.origin() - self.center(ray.time());
+        let center = self.center(ray.time());
+        let oc = ray.origin() - center;
         let dir = ray.direction();
 
         let a = dir.length_squared();
@@ -57,22 +61,32 @@ impl Hittable for MovingSphere {
         }
         let sqrtd = discriminant.sqrt();
 
-        // NOTE: Find the nearest root that lies in the acceptable range.
-        let mut root = (-half_b - sqrtd) / a;
-        if root < t_min || t_max < root {
-            root = (-half_b + sqrtd) / a;
+        // NOTE: Find the nearest root that lies in the acceptable range and isn't cut out.
+        for root in [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a] {
             if root < t_min || t_max < root {
-                return false;
+                continue;
             }
-        }
 
-        rec.t = root;
-        rec.p = ray.at(rec.t);
-        let outward_normal = (rec.p - self.center(ray.time())) / self.radius;
-        rec.set_face_normal(ray, &outward_normal);
-        rec.mat_ptr = Arc::clone(&self.mat_ptr);
+            let p = ray.at(root);
+            let outward_normal = (p - center) / self.radius;
+            let (mut u, mut v) = (0.0, 0.0);
+            Sphere::get_sphere_uv(&outward_normal, &mut u, &mut v);
+            if is_cutout(&self.mat_ptr, u, v, &p) {
+                continue;
+            }
 
-        true
+            rec.t = root;
+            rec.p = p;
+            rec.u = u;
+            rec.v = v;
+            rec.set_face_normal(ray, &outward_normal);
+            Sphere::get_sphere_tangents(&outward_normal, self.radius, &mut rec.dpdu, &mut rec.dpdv);
+            rec.mat_ptr = Arc::clone(&self.mat_ptr);
+
+            return true;
+        }
+
+        false
     }
 
     fn bounding_box(&self, time0: f64, time1: f64, output_box: &mut AABB) -> bool {
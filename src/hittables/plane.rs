@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use super::hittable::{is_cutout, HitRecord, Hittable};
+use crate::{
+    aabb::AABB,
+    materials::material::Material,
+    onb::Onb,
+    ray::Ray,
+    vec3::{cross, dot, Point3, Vec3},
+};
+
+/// An infinite plane through `point` facing along `normal`. Texture coordinates repeat every
+/// unit of distance along two directions in the plane, so image textures tile across it.
+///
+/// It has no bounding box, add it to the world directly rather than to a `BVHNode`.
+pub struct Plane {
+    point: Point3,
+    normal: Vec3,
+    tangent: Vec3,
+    bitangent: Vec3,
+    mat_ptr: Arc<Box<dyn Material>>,
+}
+
+impl Plane {
+    pub fn new(point: Point3, normal: Vec3, mat_ptr: &Arc<Box<dyn Material>>) -> Self {
+        let normal = normal.unit_vector();
+        let tangent = Onb::build_from_w(&normal).u();
+        Self {
+            point,
+            normal,
+            tangent,
+            bitangent: cross(&normal, &tangent),
+            mat_ptr: Arc::clone(mat_ptr),
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let denom = dot(&self.normal, &ray.direction());
+        if denom.abs() < 1e-8 {
+            return false;
+        }
+
+        let t = dot(&self.normal, &(self.point - ray.origin())) / denom;
+        if t < t_min || t > t_max {
+            return false;
+        }
+
+        let p = ray.at(t);
+        let offset = p - self.point;
+        let u = dot(&offset, &self.tangent).rem_euclid(1.0);
+        let v = dot(&offset, &self.bitangent).rem_euclid(1.0);
+        if is_cutout(&self.mat_ptr, u, v, &p) {
+            return false;
+        }
+
+        rec.u = u;
+        rec.v = v;
+        rec.t = t;
+        rec.p = p;
+        rec.set_face_normal(ray, &self.normal);
+        rec.dpdu = self.tangent;
+        rec.dpdv = self.bitangent;
+        rec.mat_ptr = Arc::clone(&self.mat_ptr);
+        true
+    }
+
+    fn bounding_box(&self, _: f64, _: f64, _: &mut AABB) -> bool {
+        false
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_plane {
+    ( $point:expr, $normal:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(Plane::new($point, $normal, $mat_ptr)))
+    };
+}
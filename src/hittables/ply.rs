@@ -0,0 +1,421 @@
+use std::str::SplitAsciiWhitespace;
+
+use super::mesh::MeshData;
+use crate::vec3::{Color, Point3, Vec3};
+
+#[derive(Clone, Copy)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => Self::I8,
+            "uchar" | "uint8" => Self::U8,
+            "short" | "int16" => Self::I16,
+            "ushort" | "uint16" => Self::U16,
+            "int" | "int32" => Self::I32,
+            "uint" | "uint32" => Self::U32,
+            "float" | "float32" => Self::F32,
+            "double" | "float64" => Self::F64,
+            _ => return None,
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Self::I8 | Self::U8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+
+    /// What a color channel stored as this type is divided by to bring it to `[0, 1]`.
+    fn color_scale(self) -> f64 {
+        match self {
+            Self::U8 => 255.0,
+            Self::U16 => 65535.0,
+            _ => 1.0,
+        }
+    }
+}
+
+enum Property {
+    Scalar(String, Scalar),
+    List(String, Scalar, Scalar),
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+/// Reads the values in the body of the file one at a time.
+enum Body<'a> {
+    Ascii(SplitAsciiWhitespace<'a>),
+    Binary {
+        bytes: &'a [u8],
+        offset: usize,
+        big_endian: bool,
+    },
+}
+
+impl Body<'_> {
+    fn read(&mut self, kind: Scalar) -> Option<f64> {
+        match self {
+            Self::Ascii(tokens) => tokens.next()?.parse().ok(),
+            Self::Binary {
+                bytes,
+                offset,
+                big_endian,
+            } => {
+                let raw = bytes.get(*offset..*offset + kind.size())?;
+                *offset += kind.size();
+
+                // NOTE: read everything as big endian, reversing little endian bytes first
+                let mut buf = [0u8; 8];
+                buf[..raw.len()].copy_from_slice(raw);
+                if !*big_endian {
+                    buf[..raw.len()].reverse();
+                }
+                Some(match kind {
+                    Scalar::I8 => i8::from_be_bytes([buf[0]]) as f64,
+                    Scalar::U8 => buf[0] as f64,
+                    Scalar::I16 => i16::from_be_bytes([buf[0], buf[1]]) as f64,
+                    Scalar::U16 => u16::from_be_bytes([buf[0], buf[1]]) as f64,
+                    Scalar::I32 => i32::from_be_bytes(buf[..4].try_into().ok()?) as f64,
+                    Scalar::U32 => u32::from_be_bytes(buf[..4].try_into().ok()?) as f64,
+                    Scalar::F32 => f32::from_be_bytes(buf[..4].try_into().ok()?) as f64,
+                    Scalar::F64 => f64::from_be_bytes(buf),
+                })
+            }
+        }
+    }
+
+    /// A list length or vertex index, `None` unless it's a whole number that isn't negative.
+    fn read_index(&mut self, kind: Scalar) -> Option<usize> {
+        let value = self.read(kind)?;
+        if value < 0.0 || value.fract() != 0.0 {
+            return None;
+        }
+        Some(value as usize)
+    }
+}
+
+/// Parses an ASCII or binary PLY file into a mesh. Vertex positions are read with normals,
+/// texture coordinates and colors where the file has them, and faces with more than three
+/// corners are split into a fan of triangles. Other elements are skipped.
+pub fn parse(bytes: &[u8]) -> Option<MeshData> {
+    let end = bytes.windows(10).position(|w| w == b"end_header")?;
+    let body_start = end + bytes[end..].iter().position(|&b| b == b'\n')? + 1;
+    let header = std::str::from_utf8(&bytes[..end]).ok()?;
+
+    let mut lines = header.lines().map(str::split_ascii_whitespace);
+    if lines.next()?.next()? != "ply" {
+        return None;
+    }
+
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    for mut words in lines {
+        match words.next() {
+            Some("format") => format = words.next(),
+            Some("element") => elements.push(Element {
+                name: words.next()?.to_string(),
+                count: words.next()?.parse().ok()?,
+                properties: Vec::new(),
+            }),
+            Some("property") => {
+                let kind = words.next()?;
+                let property = if kind == "list" {
+                    let count = Scalar::parse(words.next()?)?;
+                    let item = Scalar::parse(words.next()?)?;
+                    Property::List(words.next()?.to_string(), count, item)
+                } else {
+                    Property::Scalar(words.next()?.to_string(), Scalar::parse(kind)?)
+                };
+                elements.last_mut()?.properties.push(property);
+            }
+            _ => {}
+        }
+    }
+
+    let rest = &bytes[body_start..];
+    let mut body = match format? {
+        "ascii" => Body::Ascii(std::str::from_utf8(rest).ok()?.split_ascii_whitespace()),
+        "binary_little_endian" => Body::Binary {
+            bytes: rest,
+            offset: 0,
+            big_endian: false,
+        },
+        "binary_big_endian" => Body::Binary {
+            bytes: rest,
+            offset: 0,
+            big_endian: true,
+        },
+        _ => return None,
+    };
+
+    let mut mesh = MeshData::default();
+    for element in &elements {
+        match element.name.as_str() {
+            "vertex" => read_vertices(element, &mut body, &mut mesh)?,
+            "face" => read_faces(element, &mut body, &mut mesh)?,
+            _ => {
+                for _ in 0..element.count {
+                    read_row(element, &mut body)?;
+                }
+            }
+        }
+    }
+
+    // NOTE: faces can refer to vertices by any index, so check them once both are read
+    let vertex_count = mesh.positions.len();
+    if mesh.triangles.iter().flatten().any(|&i| i >= vertex_count) {
+        return None;
+    }
+    Some(mesh)
+}
+
+/// The scalar values of one row, lists are read past and left out.
+fn read_row(element: &Element, body: &mut Body) -> Option<Vec<f64>> {
+    let mut values = Vec::with_capacity(element.properties.len());
+    for property in &element.properties {
+        match property {
+            Property::Scalar(_, kind) => values.push(body.read(*kind)?),
+            Property::List(_, count, item) => {
+                for _ in 0..body.read_index(*count)? {
+                    body.read(*item)?;
+                }
+                values.push(0.0);
+            }
+        }
+    }
+    Some(values)
+}
+
+fn read_vertices(element: &Element, body: &mut Body, mesh: &mut MeshData) -> Option<()> {
+    // NOTE: exporters disagree on names, so each attribute goes by any of the common ones
+    let find = |names: &[&str]| {
+        element
+            .properties
+            .iter()
+            .position(|property| match property {
+                Property::Scalar(name, _) => names.contains(&name.as_str()),
+                Property::List(..) => false,
+            })
+    };
+    let scale = |index: usize| match &element.properties[index] {
+        Property::Scalar(_, kind) => kind.color_scale(),
+        Property::List(..) => 1.0,
+    };
+
+    let position = [find(&["x"])?, find(&["y"])?, find(&["z"])?];
+    let normal = [find(&["nx"]), find(&["ny"]), find(&["nz"])];
+    let uv = [
+        find(&["u", "s", "texture_u", "texture_s"]),
+        find(&["v", "t", "texture_v", "texture_t"]),
+    ];
+    let color = [
+        find(&["red", "r", "diffuse_red"]),
+        find(&["green", "g", "diffuse_green"]),
+        find(&["blue", "b", "diffuse_blue"]),
+    ];
+
+    for _ in 0..element.count {
+        let row = read_row(element, body)?;
+        mesh.positions.push(Point3::with_values(
+            row[position[0]],
+            row[position[1]],
+            row[position[2]],
+        ));
+        if let [Some(x), Some(y), Some(z)] = normal {
+            mesh.normals.push(Vec3::with_values(row[x], row[y], row[z]));
+        }
+        if let [Some(u), Some(v)] = uv {
+            mesh.uvs.push([row[u], row[v]]);
+        }
+        if let [Some(r), Some(g), Some(b)] = color {
+            mesh.colors.push(Color::with_values(
+                row[r] / scale(r),
+                row[g] / scale(g),
+                row[b] / scale(b),
+            ));
+        }
+    }
+    Some(())
+}
+
+fn read_faces(element: &Element, body: &mut Body, mesh: &mut MeshData) -> Option<()> {
+    for _ in 0..element.count {
+        for property in &element.properties {
+            match property {
+                Property::List(name, count, item)
+                    if name == "vertex_indices" || name == "vertex_index" =>
+                {
+                    let corners = (0..body.read_index(*count)?)
+                        .map(|_| body.read_index(*item))
+                        .collect::<Option<Vec<usize>>>()?;
+                    for i in 2..corners.len() {
+                        mesh.triangles
+                            .push([corners[0], corners[i - 1], corners[i]]);
+                    }
+                }
+                Property::List(_, count, item) => {
+                    for _ in 0..body.read_index(*count)? {
+                        body.read(*item)?;
+                    }
+                }
+                Property::Scalar(_, kind) => {
+                    body.read(*kind)?;
+                }
+            }
+        }
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CUBE: &str = "ply
+format ascii 1.0
+comment corners colored by position
+element vertex 8
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+element face 6
+property list uchar int vertex_indices
+end_header
+0 0 0 0 0 0
+1 0 0 255 0 0
+0 1 0 0 255 0
+1 1 0 255 255 0
+0 0 1 0 0 255
+1 0 1 255 0 255
+0 1 1 0 255 255
+1 1 1 255 255 255
+4 0 2 3 1
+4 4 5 7 6
+4 0 1 5 4
+4 2 6 7 3
+4 0 4 6 2
+4 1 3 7 5
+";
+
+    /// A square of four colored vertices as one quad face, followed by an element the loader
+    /// skips, written with the given byte order.
+    fn binary_square(big_endian: bool) -> Vec<u8> {
+        let format = if big_endian {
+            "binary_big_endian"
+        } else {
+            "binary_little_endian"
+        };
+        let mut bytes = format!(
+            "ply\nformat {format} 1.0\nelement vertex 4\nproperty float x\nproperty float y\n\
+             property float z\nproperty uchar red\nproperty uchar green\nproperty uchar blue\n\
+             element face 1\nproperty list uchar int vertex_indices\n\
+             element edge 1\nproperty int vertex1\nproperty int vertex2\nend_header\n"
+        )
+        .into_bytes();
+
+        let f32_bytes = |x: f32| {
+            if big_endian {
+                x.to_be_bytes()
+            } else {
+                x.to_le_bytes()
+            }
+        };
+        let i32_bytes = |x: i32| {
+            if big_endian {
+                x.to_be_bytes()
+            } else {
+                x.to_le_bytes()
+            }
+        };
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)] {
+            for c in [x, y, 2.0] {
+                bytes.extend(f32_bytes(c));
+            }
+            bytes.extend([255, 51, 0]);
+        }
+        bytes.push(4);
+        for i in 0..4 {
+            bytes.extend(i32_bytes(i));
+        }
+        bytes.extend(i32_bytes(0));
+        bytes.extend(i32_bytes(1));
+        bytes
+    }
+
+    #[test]
+    fn reads_ascii_with_colors() {
+        let mesh = parse(CUBE.as_bytes()).unwrap();
+        assert_eq!(mesh.positions.len(), 8);
+        assert_eq!(mesh.triangles.len(), 12);
+        assert_eq!(mesh.colors.len(), 8);
+        assert!(mesh.normals.is_empty() && mesh.uvs.is_empty());
+        assert_eq!(mesh.triangles[0], [0, 2, 3]);
+        assert_eq!(mesh.triangles[1], [0, 3, 1]);
+
+        let white = mesh.colors[7];
+        assert_eq!((white.x(), white.y(), white.z()), (1.0, 1.0, 1.0));
+        let red = mesh.colors[1];
+        assert_eq!((red.x(), red.y(), red.z()), (1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn reads_both_binary_byte_orders() {
+        for big_endian in [false, true] {
+            let mesh = parse(&binary_square(big_endian)).unwrap();
+            assert_eq!(mesh.positions.len(), 4);
+            assert_eq!(mesh.triangles, vec![[0, 1, 2], [0, 2, 3]]);
+            assert_eq!(mesh.colors.len(), 4);
+
+            let corner = mesh.positions[2];
+            assert_eq!((corner.x(), corner.y(), corner.z()), (1.0, 1.0, 2.0));
+            let color = mesh.colors[0];
+            assert_eq!((color.x(), color.y(), color.z()), (1.0, 0.2, 0.0));
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        let bytes = binary_square(false);
+        assert!(parse(&bytes[..bytes.len() - 10]).is_none());
+        assert!(parse(&CUBE.as_bytes()[..CUBE.len() - 12]).is_none());
+        assert!(parse(b"ply\nformat ascii 1.0\nelement vertex 1\n").is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_faces() {
+        let negative = CUBE.replace("4 1 3 7 5", "4 1 3 7 -1");
+        assert!(parse(negative.as_bytes()).is_none());
+        let fractional = CUBE.replace("4 1 3 7 5", "4 1 3 7 4.5");
+        assert!(parse(fractional.as_bytes()).is_none());
+        let out_of_range = CUBE.replace("4 1 3 7 5", "4 1 3 7 8");
+        assert!(parse(out_of_range.as_bytes()).is_none());
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(parse(b"obj\nformat ascii 1.0\nend_header\n").is_none());
+        let unknown = CUBE.replace("format ascii", "format text");
+        assert!(parse(unknown.as_bytes()).is_none());
+    }
+}
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use rand::Rng;
+
+use super::hittable::{is_cutout, HitRecord, Hittable, SurfaceSample};
+use crate::{
+    aabb::AABB,
+    materials::material::Material,
+    ray::Ray,
+    vec3::{cross, dot, Point3, Vec3},
+};
+
+/// A parallelogram with a corner at `q` and edges `u` and `v` in any orientation. Its normal
+/// is along `u × v`, and the texture coordinates run from `0` to `1` along each edge.
+pub struct Quad {
+    q: Point3,
+    u: Vec3,
+    v: Vec3,
+    normal: Vec3,
+    /// Offset of the plane along `normal`, so points on it satisfy `normal · p = d`.
+    d: f64,
+    /// `u × v` over its squared length, to find the coordinates of points along the edges.
+    w: Vec3,
+    mat_ptr: Arc<Box<dyn Material>>,
+}
+
+impl Quad {
+    pub fn new(q: Point3, u: Vec3, v: Vec3, mat_ptr: &Arc<Box<dyn Material>>) -> Self {
+        let n = cross(&u, &v);
+        let normal = n.unit_vector();
+        Self {
+            q,
+            u,
+            v,
+            normal,
+            d: dot(&normal, &q),
+            w: n / n.length_squared(),
+            mat_ptr: Arc::clone(mat_ptr),
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let denom = dot(&self.normal, &ray.direction());
+        if denom.abs() < 1e-8 {
+            return false;
+        }
+
+        let t = (self.d - dot(&self.normal, &ray.origin())) / denom;
+        if t < t_min || t > t_max {
+            return false;
+        }
+
+        let p = ray.at(t);
+        let planar = p - self.q;
+        let alpha = dot(&self.w, &cross(&planar, &self.v));
+        let beta = dot(&self.w, &cross(&self.u, &planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return false;
+        }
+        if is_cutout(&self.mat_ptr, alpha, beta, &p) {
+            return false;
+        }
+
+        rec.u = alpha;
+        rec.v = beta;
+        rec.t = t;
+        rec.p = p;
+        rec.set_face_normal(ray, &self.normal);
+        rec.dpdu = self.u;
+        rec.dpdv = self.v;
+        rec.mat_ptr = Arc::clone(&self.mat_ptr);
+        true
+    }
+
+    fn bounding_box(&self, _: f64, _: f64, output_box: &mut AABB) -> bool {
+        let corners = [self.q + self.u, self.q + self.v, self.q + self.u + self.v];
+        let mut min = self.q;
+        let mut max = self.q;
+        for corner in corners {
+            for a in 0..3 {
+                min[a] = min[a].min(corner[a]);
+                max[a] = max[a].max(corner[a]);
+            }
+        }
+
+        // NOTE: pad so quads lying in an axis plane don't get a flat box
+        *output_box = AABB::new(
+            min - Vec3::with_value(0.0001),
+            max + Vec3::with_value(0.0001),
+        );
+        true
+    }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let mut rng = rand::thread_rng();
+        Some(SurfaceSample {
+            p: self.q + rng.gen::<f64>() * self.u + rng.gen::<f64>() * self.v,
+            normal: self.normal,
+            area: cross(&self.u, &self.v).length(),
+        })
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_quad {
+    ( $q:expr, $u:expr, $v:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(Quad::new($q, $u, $v, $mat_ptr)))
+    };
+}
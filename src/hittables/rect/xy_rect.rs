@@ -1,6 +1,8 @@
 use std::sync::Arc;
 
-use super::super::hittable::{HitRecord, Hittable};
+use rand::Rng;
+
+use super::super::hittable::{is_cutout, HitRecord, Hittable, SurfaceSample};
 use crate::{
     aabb::AABB,
     materials::material::Material,
@@ -43,14 +45,23 @@ impl Hittable for XYRect {
             return false;
         }
 
-        rec.u = (x - self.x0) / (self.x1 - self.x0);
-        rec.v = (y - self.y0) / (self.y1 - self.y0);
+        let u = (x - self.x0) / (self.x1 - self.x0);
+        let v = (y - self.y0) / (self.y1 - self.y0);
+        let p = ray.at(t);
+        if is_cutout(&self.mat_ptr, u, v, &p) {
+            return false;
+        }
+
+        rec.u = u;
+        rec.v = v;
         rec.t = t;
 
         let outward_normal = Vec3::with_values(0.0, 0.0, 1.0);
         rec.set_face_normal(ray, &outward_normal);
+        rec.dpdu = Vec3::with_values(self.x1 - self.x0, 0.0, 0.0);
+        rec.dpdv = Vec3::with_values(0.0, self.y1 - self.y0, 0.0);
         rec.mat_ptr = Arc::clone(&self.mat_ptr);
-        rec.p = ray.at(t);
+        rec.p = p;
         true
     }
 
@@ -60,6 +71,19 @@ impl Hittable for XYRect {
         *output_box = AABB::new(min, max);
         true
     }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let mut rng = rand::thread_rng();
+        Some(SurfaceSample {
+            p: Point3::with_values(
+                rng.gen_range(self.x0..self.x1),
+                rng.gen_range(self.y0..self.y1),
+                self.k,
+            ),
+            normal: Vec3::with_values(0.0, 0.0, 1.0),
+            area: (self.x1 - self.x0) * (self.y1 - self.y0),
+        })
+    }
 }
 
 #[macro_export]
@@ -1,8 +1,10 @@
 use std::sync::Arc;
 
+use rand::Rng;
+
 use crate::{
     aabb::AABB,
-    hittables::hittable::{HitRecord, Hittable},
+    hittables::hittable::{is_cutout, HitRecord, Hittable, SurfaceSample},
     materials::material::Material,
     ray::Ray,
     vec3::{Point3, Vec3},
@@ -43,14 +45,23 @@ impl Hittable for YZRect {
             return false;
         }
 
-        rec.u = (y - self.y0) / (self.y1 - self.y0);
-        rec.v = (z - self.z0) / (self.z1 - self.z0);
+        let u = (y - self.y0) / (self.y1 - self.y0);
+        let v = (z - self.z0) / (self.z1 - self.z0);
+        let p = ray.at(t);
+        if is_cutout(&self.mat_ptr, u, v, &p) {
+            return false;
+        }
+
+        rec.u = u;
+        rec.v = v;
         rec.t = t;
 
         let outward_normal = Vec3::with_values(1.0, 0.0, 0.0);
         rec.set_face_normal(ray, &outward_normal);
+        rec.dpdu = Vec3::with_values(0.0, self.y1 - self.y0, 0.0);
+        rec.dpdv = Vec3::with_values(0.0, 0.0, self.z1 - self.z0);
         rec.mat_ptr = Arc::clone(&self.mat_ptr);
-        rec.p = ray.at(t);
+        rec.p = p;
         true
     }
 
@@ -60,6 +71,19 @@ impl Hittable for YZRect {
         *output_box = AABB::new(min, max);
         true
     }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let mut rng = rand::thread_rng();
+        Some(SurfaceSample {
+            p: Point3::with_values(
+                self.k,
+                rng.gen_range(self.y0..self.y1),
+                rng.gen_range(self.z0..self.z1),
+            ),
+            normal: Vec3::with_values(1.0, 0.0, 0.0),
+            area: (self.y1 - self.y0) * (self.z1 - self.z0),
+        })
+    }
 }
 
 #[macro_export]
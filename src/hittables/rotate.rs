@@ -6,7 +6,7 @@ use crate::{
     vec3::{Point3, Vec3},
 };
 
-use super::hittable::{HitRecord, Hittable};
+use super::hittable::{HitRecord, Hittable, SurfaceSample};
 
 pub struct RotateY {
     ptr: Arc<Box<dyn Hittable>>,
@@ -62,6 +62,14 @@ impl RotateY {
             bbox,
         }
     }
+
+    fn rotate_back(&self, v: &Vec3) -> Vec3 {
+        Vec3::with_values(
+            self.cos_theta * v[0] + self.sin_theta * v[2],
+            v[1],
+            -self.sin_theta * v[0] + self.cos_theta * v[2],
+        )
+    }
 }
 
 impl Hittable for RotateY {
@@ -90,8 +98,11 @@ impl Hittable for RotateY {
         normal[0] = self.cos_theta * rec.normal[0] + self.sin_theta * rec.normal[2];
         normal[2] = -self.sin_theta * rec.normal[0] + self.cos_theta * rec.normal[2];
 
+        // NOTE: normal already faces the ray, so rotating it keeps front_face valid
         rec.p = p;
-        rec.set_face_normal(&rotated_ray, &normal);
+        rec.normal = normal;
+        rec.dpdu = self.rotate_back(&rec.dpdu);
+        rec.dpdv = self.rotate_back(&rec.dpdv);
 
         true
     }
@@ -100,6 +111,15 @@ impl Hittable for RotateY {
         *output_box = self.bbox;
         self.has_box
     }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let sample = self.ptr.sample_surface()?;
+        Some(SurfaceSample {
+            p: self.rotate_back(&sample.p),
+            normal: self.rotate_back(&sample.normal),
+            area: sample.area,
+        })
+    }
 }
 
 #[macro_export]
@@ -0,0 +1,301 @@
+use std::sync::Arc;
+
+use super::{
+    hittable::{is_cutout, HitRecord, Hittable},
+    sphere::Sphere,
+};
+use crate::{
+    aabb::AABB,
+    materials::material::Material,
+    ray::Ray,
+    vec3::{dot, Point3, Vec3},
+};
+
+/// Marching gives up after this many steps and counts the ray as a miss.
+const MAX_STEPS: usize = 512;
+/// Bisection of a crossing stops after this many halvings, even short of `epsilon`.
+const MAX_BISECTIONS: usize = 64;
+
+/// A signed distance function, negative inside the shape. It should never be more than the
+/// distance to the surface, or marching can step through it.
+pub trait Sdf: Send + Sync {
+    fn distance(&self, p: &Point3) -> f64;
+}
+
+impl<F> Sdf for F
+where
+    F: Fn(&Point3) -> f64 + Send + Sync,
+{
+    fn distance(&self, p: &Point3) -> f64 {
+        self(p)
+    }
+}
+
+/// A box around `center` reaching `half_extents` out on each axis, with its edges rounded
+/// off by `radius`.
+pub struct SdfRoundBox {
+    center: Point3,
+    half_extents: Vec3,
+    radius: f64,
+}
+
+impl SdfRoundBox {
+    pub fn new(center: Point3, half_extents: Vec3, radius: f64) -> Self {
+        Self {
+            center,
+            half_extents,
+            radius,
+        }
+    }
+}
+
+impl Sdf for SdfRoundBox {
+    fn distance(&self, p: &Point3) -> f64 {
+        let mut outside = Vec3::new();
+        let mut inside = f64::NEG_INFINITY;
+        for a in 0..3 {
+            let q = (p[a] - self.center[a]).abs() - self.half_extents[a] + self.radius;
+            outside[a] = q.max(0.0);
+            inside = inside.max(q);
+        }
+
+        outside.length() + inside.min(0.0) - self.radius
+    }
+}
+
+/// A ring in the xz plane, like `Torus`.
+pub struct SdfTorus {
+    center: Point3,
+    major_radius: f64,
+    minor_radius: f64,
+}
+
+impl SdfTorus {
+    pub fn new(center: Point3, major_radius: f64, minor_radius: f64) -> Self {
+        Self {
+            center,
+            major_radius,
+            minor_radius,
+        }
+    }
+}
+
+impl Sdf for SdfTorus {
+    fn distance(&self, p: &Point3) -> f64 {
+        let local = *p - self.center;
+        let rho = (local.x() * local.x() + local.z() * local.z()).sqrt();
+        (rho - self.major_radius).hypot(local.y()) - self.minor_radius
+    }
+}
+
+/// The points within `radius` of the segment from `a` to `b`.
+pub struct SdfCapsule {
+    a: Point3,
+    b: Point3,
+    radius: f64,
+}
+
+impl SdfCapsule {
+    pub fn new(a: Point3, b: Point3, radius: f64) -> Self {
+        Self { a, b, radius }
+    }
+}
+
+impl Sdf for SdfCapsule {
+    fn distance(&self, p: &Point3) -> f64 {
+        let pa = *p - self.a;
+        let ba = self.b - self.a;
+        let h = (dot(&pa, &ba) / ba.length_squared()).clamp(0.0, 1.0);
+        (pa - h * ba).length() - self.radius
+    }
+}
+
+/// Union of two shapes blended over a distance of about `k` where they meet.
+pub struct SmoothUnion {
+    left: Arc<Box<dyn Sdf>>,
+    right: Arc<Box<dyn Sdf>>,
+    k: f64,
+}
+
+impl SmoothUnion {
+    pub fn new(left: &Arc<Box<dyn Sdf>>, right: &Arc<Box<dyn Sdf>>, k: f64) -> Self {
+        Self {
+            left: Arc::clone(left),
+            right: Arc::clone(right),
+            k,
+        }
+    }
+}
+
+impl Sdf for SmoothUnion {
+    fn distance(&self, p: &Point3) -> f64 {
+        let d1 = self.left.distance(p);
+        let d2 = self.right.distance(p);
+        let h = (0.5 + 0.5 * (d2 - d1) / self.k).clamp(0.0, 1.0);
+        d2 + (d1 - d2) * h - self.k * h * (1.0 - h)
+    }
+}
+
+/// The left shape with the right one carved out of it, blended over about `k`.
+pub struct SmoothSubtraction {
+    left: Arc<Box<dyn Sdf>>,
+    right: Arc<Box<dyn Sdf>>,
+    k: f64,
+}
+
+impl SmoothSubtraction {
+    pub fn new(left: &Arc<Box<dyn Sdf>>, right: &Arc<Box<dyn Sdf>>, k: f64) -> Self {
+        Self {
+            left: Arc::clone(left),
+            right: Arc::clone(right),
+            k,
+        }
+    }
+}
+
+impl Sdf for SmoothSubtraction {
+    fn distance(&self, p: &Point3) -> f64 {
+        let d1 = self.left.distance(p);
+        let d2 = self.right.distance(p);
+        let h = (0.5 - 0.5 * (d1 + d2) / self.k).clamp(0.0, 1.0);
+        d1 + (-d2 - d1) * h + self.k * h * (1.0 - h)
+    }
+}
+
+/// Copies of a shape every `period` along each axis, for axes with a nonzero period. The
+/// shape should fit in the cell around the origin.
+pub struct Repeat {
+    sdf: Arc<Box<dyn Sdf>>,
+    period: Vec3,
+}
+
+impl Repeat {
+    pub fn new(sdf: &Arc<Box<dyn Sdf>>, period: Vec3) -> Self {
+        Self {
+            sdf: Arc::clone(sdf),
+            period,
+        }
+    }
+}
+
+impl Sdf for Repeat {
+    fn distance(&self, p: &Point3) -> f64 {
+        let mut cell = *p;
+        for a in 0..3 {
+            if self.period[a] != 0.0 {
+                cell[a] -= self.period[a] * (p[a] / self.period[a]).round();
+            }
+        }
+
+        self.sdf.distance(&cell)
+    }
+}
+
+/// Surface where a signed distance function is zero, found by sphere tracing within
+/// `bbox`. Normals come from the gradient of the distance and uvs from the normal, as on
+/// a `Sphere`.
+pub struct SdfHittable {
+    sdf: Arc<Box<dyn Sdf>>,
+    bbox: AABB,
+    mat_ptr: Arc<Box<dyn Material>>,
+    epsilon: f64,
+}
+
+impl SdfHittable {
+    pub fn new(sdf: &Arc<Box<dyn Sdf>>, bbox: AABB, mat_ptr: &Arc<Box<dyn Material>>) -> Self {
+        Self {
+            sdf: Arc::clone(sdf),
+            bbox,
+            mat_ptr: Arc::clone(mat_ptr),
+            epsilon: 1e-5 * (bbox.max() - bbox.min()).length(),
+        }
+    }
+
+    fn gradient(&self, p: &Point3) -> Vec3 {
+        let mut gradient = Vec3::new();
+        for a in 0..3 {
+            let mut offset = Vec3::new();
+            offset[a] = self.epsilon;
+            gradient[a] = self.sdf.distance(&(*p + offset)) - self.sdf.distance(&(*p - offset));
+        }
+        gradient
+    }
+}
+
+impl Hittable for SdfHittable {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let (mut t, t_end) = match self.bbox.clip(ray, t_min, t_max) {
+            Some(range) => range,
+            None => return false,
+        };
+
+        // NOTE: distances are in world units, `t` in lengths of the ray's direction
+        let length = ray.direction().length();
+        let mut distance = self.sdf.distance(&ray.at(t));
+        let mut inside = distance < 0.0;
+
+        // NOTE: each step overshoots by epsilon so the surface is crossed rather than only
+        // approached, the crossing is then narrowed down by bisection
+        for _ in 0..MAX_STEPS {
+            let next = t + (distance.abs() + self.epsilon) / length;
+            if next > t_end {
+                return false;
+            }
+
+            let next_distance = self.sdf.distance(&ray.at(next));
+            if (next_distance < 0.0) == inside {
+                t = next;
+                distance = next_distance;
+                continue;
+            }
+
+            let (mut before, mut after) = (t, next);
+            for _ in 0..MAX_BISECTIONS {
+                if (after - before) * length <= 0.1 * self.epsilon {
+                    break;
+                }
+                let middle = 0.5 * (before + after);
+                if (self.sdf.distance(&ray.at(middle)) < 0.0) == inside {
+                    before = middle;
+                } else {
+                    after = middle;
+                }
+            }
+
+            let p = ray.at(after);
+            let outward_normal = self.gradient(&p).unit_vector();
+            let (mut u, mut v) = (0.0, 0.0);
+            Sphere::get_sphere_uv(&outward_normal, &mut u, &mut v);
+            // NOTE: the surface behind a cut-out texel can still be hit, march on from the far
+            // side of the crossing
+            if is_cutout(&self.mat_ptr, u, v, &p) {
+                inside = !inside;
+                t = after;
+                distance = self.sdf.distance(&p);
+                continue;
+            }
+
+            rec.t = after;
+            rec.p = p;
+            rec.u = u;
+            rec.v = v;
+            rec.set_face_normal(ray, &outward_normal);
+            Sphere::get_sphere_tangents(&outward_normal, 1.0, &mut rec.dpdu, &mut rec.dpdv);
+            rec.mat_ptr = Arc::clone(&self.mat_ptr);
+            return true;
+        }
+        false
+    }
+
+    fn bounding_box(&self, _: f64, _: f64, output_box: &mut AABB) -> bool {
+        *output_box = self.bbox;
+        true
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_sdf {
+    ( $sdf:expr, $bbox:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(SdfHittable::new($sdf, $bbox, $mat_ptr)))
+    };
+}
@@ -1,12 +1,12 @@
 use std::f64::consts::PI;
 use std::sync::Arc;
 
-use super::hittable::{HitRecord, Hittable};
+use super::hittable::{is_cutout, HitRecord, Hittable, SurfaceSample};
 use crate::{
     aabb::AABB,
     materials::material::Material,
     ray::Ray,
-    vec3::{dot, Point3, Vec3},
+    vec3::{dot, random_unit_vector, Point3, Vec3},
 };
 
 pub struct Sphere {
@@ -24,13 +24,49 @@ impl Sphere {
         }
     }
 
-    fn get_sphere_uv(p: &Point3, u: &mut f64, v: &mut f64) {
+    pub fn get_sphere_uv(p: &Point3, u: &mut f64, v: &mut f64) {
         let theta = (-p.y()).acos();
         let phi = (-p.z()).atan2(p.x()) + PI;
 
         *u = phi / (2.0 * PI);
         *v = theta / PI;
     }
+
+    /// Fills `rec` for the hit at the ray distance `root`, unless the material cuts it out.
+    fn hit_at(&self, ray: &Ray, root: f64, rec: &mut HitRecord) -> bool {
+        let p = ray.at(root);
+        let outward_normal = (p - self.center) / self.radius;
+        let (mut u, mut v) = (0.0, 0.0);
+        Sphere::get_sphere_uv(&outward_normal, &mut u, &mut v);
+        if is_cutout(&self.mat_ptr, u, v, &p) {
+            return false;
+        }
+
+        rec.t = root;
+        rec.p = p;
+        rec.u = u;
+        rec.v = v;
+        rec.set_face_normal(ray, &outward_normal);
+        Sphere::get_sphere_tangents(&outward_normal, self.radius, &mut rec.dpdu, &mut rec.dpdv);
+        rec.mat_ptr = Arc::clone(&self.mat_ptr);
+        true
+    }
+
+    /// Partial derivatives of the surface point with respect to the `(u, v)` from
+    /// `get_sphere_uv`, given the unit outward normal `p`.
+    pub fn get_sphere_tangents(p: &Point3, radius: f64, dpdu: &mut Vec3, dpdv: &mut Vec3) {
+        // NOTE: clamp sin(theta) so the poles don't divide by zero
+        let sin_theta = (1.0 - p.y() * p.y()).sqrt().max(1e-8);
+
+        *dpdu = 2.0 * PI * radius * Vec3::with_values(p.z(), 0.0, -p.x());
+        *dpdv = PI
+            * radius
+            * Vec3::with_values(
+                -p.x() * p.y() / sin_theta,
+                sin_theta,
+                -p.y() * p.z() / sin_theta,
+            );
+    }
 }
 
 impl Hittable for Sphere {
@@ -48,23 +84,42 @@ impl Hittable for Sphere {
         }
         let sqrtd = discriminant.sqrt();
 
-        // NOTE: Find the nearest root that lies in the acceptable range.
-        let mut root = (-half_b - sqrtd) / a;
-        if root < t_min || t_max < root {
-            root = (-half_b + sqrtd) / a;
+        // NOTE: Find the nearest root that lies in the acceptable range and isn't cut out.
+        for root in [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a] {
             if root < t_min || t_max < root {
-                return false;
+                continue;
+            }
+
+            if self.hit_at(ray, root, rec) {
+                return true;
             }
         }
 
-        rec.t = root;
-        rec.p = ray.at(rec.t);
-        let outward_normal = (rec.p - self.center) / self.radius;
-        rec.set_face_normal(ray, &outward_normal);
-        Sphere::get_sphere_uv(&outward_normal, &mut rec.u, &mut rec.v);
-        rec.mat_ptr = Arc::clone(&self.mat_ptr);
+        false
+    }
 
-        true
+    fn hit_all(&self, ray: &Ray, t_min: f64, t_max: f64) -> Vec<HitRecord> {
+        let oc = ray.origin() - self.center;
+        let dir = ray.direction();
+
+        let a = dir.length_squared();
+        let half_b = dot(&oc, &dir);
+        let c = oc.length_squared() - self.radius * self.radius;
+
+        let discriminant = half_b * half_b - a * c;
+        if discriminant <= 0.0 {
+            return Vec::new();
+        }
+        let sqrtd = discriminant.sqrt();
+
+        let mut hits = Vec::with_capacity(2);
+        for root in [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a] {
+            let mut rec = HitRecord::default();
+            if t_min <= root && root <= t_max && self.hit_at(ray, root, &mut rec) {
+                hits.push(rec);
+            }
+        }
+        hits
     }
 
     fn bounding_box(&self, _: f64, _: f64, output_box: &mut AABB) -> bool {
@@ -75,6 +130,15 @@ impl Hittable for Sphere {
 
         true
     }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let normal = random_unit_vector();
+        Some(SurfaceSample {
+            p: self.center + self.radius * normal,
+            normal,
+            area: 4.0 * PI * self.radius * self.radius,
+        })
+    }
 }
 
 #[macro_export]
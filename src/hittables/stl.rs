@@ -0,0 +1,150 @@
+use super::mesh::MeshData;
+use crate::vec3::Point3;
+
+/// Parses an ASCII or binary STL file into a mesh, each facet a triangle of its own. The
+/// facet normals are left out, the winding of the corners gives the same direction.
+pub fn parse(bytes: &[u8]) -> Option<MeshData> {
+    // NOTE: some binary files start with "solid" too, their size gives them away unless they
+    // are padded, so anything that doesn't read as ASCII facets is tried as binary
+    let binary_size = bytes
+        .get(80..84)
+        .map(|count| 84 + 50 * u32::from_le_bytes(count.try_into().unwrap()) as usize);
+    if bytes.starts_with(b"solid") && binary_size != Some(bytes.len()) {
+        std::str::from_utf8(bytes)
+            .ok()
+            .and_then(parse_ascii)
+            .filter(|mesh| !mesh.triangles.is_empty())
+            .or_else(|| parse_binary(bytes))
+    } else {
+        parse_binary(bytes)
+    }
+}
+
+fn parse_binary(bytes: &[u8]) -> Option<MeshData> {
+    let count = u32::from_le_bytes(bytes.get(80..84)?.try_into().ok()?) as usize;
+    let read_f32 = |offset: usize| -> Option<f64> {
+        Some(f32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?) as f64)
+    };
+
+    let mut mesh = MeshData::default();
+    for facet in 0..count {
+        // NOTE: each facet is a normal, three corners and a two byte attribute count
+        let start = 84 + 50 * facet + 12;
+        for corner in 0..3 {
+            let offset = start + 12 * corner;
+            mesh.positions.push(Point3::with_values(
+                read_f32(offset)?,
+                read_f32(offset + 4)?,
+                read_f32(offset + 8)?,
+            ));
+        }
+        mesh.triangles
+            .push([3 * facet, 3 * facet + 1, 3 * facet + 2]);
+    }
+    Some(mesh)
+}
+
+fn parse_ascii(text: &str) -> Option<MeshData> {
+    let mut mesh = MeshData::default();
+    let mut corners = Vec::with_capacity(3);
+    let mut words = text.split_ascii_whitespace();
+    while let Some(word) = words.next() {
+        match word {
+            "vertex" => {
+                let mut next = || words.next().and_then(|w| w.parse::<f64>().ok());
+                corners.push(mesh.positions.len());
+                mesh.positions
+                    .push(Point3::with_values(next()?, next()?, next()?));
+            }
+            "endloop" => {
+                // NOTE: loops should have three corners, fan out any that have more
+                for i in 2..corners.len() {
+                    mesh.triangles
+                        .push([corners[0], corners[i - 1], corners[i]]);
+                }
+                corners.clear();
+            }
+            _ => {}
+        }
+    }
+    Some(mesh)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWO_FACETS: &str = "solid square
+facet normal 0 0 1
+outer loop
+vertex 0 0 0
+vertex 1 0 0
+vertex 1 1 0
+endloop
+endfacet
+facet normal 0 0 1
+outer loop
+vertex 0 0 0
+vertex 1 1 0
+vertex 0 1 0
+endloop
+endfacet
+endsolid square
+";
+
+    /// The same two facets in binary, with an 80 byte `header`.
+    fn binary_square(header: &[u8]) -> Vec<u8> {
+        let mut bytes = header.to_vec();
+        bytes.resize(80, b' ');
+        bytes.extend(2u32.to_le_bytes());
+        for facet in [
+            [[0.0f32, 0.0], [1.0, 0.0], [1.0, 1.0]],
+            [[0.0, 0.0], [1.0, 1.0], [0.0, 1.0]],
+        ] {
+            for c in [0.0f32, 0.0, 1.0] {
+                bytes.extend(c.to_le_bytes());
+            }
+            for [x, y] in facet {
+                for c in [x, y, 0.0] {
+                    bytes.extend(c.to_le_bytes());
+                }
+            }
+            bytes.extend(0u16.to_le_bytes());
+        }
+        bytes
+    }
+
+    fn check_square(mesh: &MeshData) {
+        assert_eq!(mesh.positions.len(), 6);
+        assert_eq!(mesh.triangles, vec![[0, 1, 2], [3, 4, 5]]);
+        let corner = mesh.positions[5];
+        assert_eq!((corner.x(), corner.y(), corner.z()), (0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn reads_ascii() {
+        check_square(&parse(TWO_FACETS.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn reads_binary() {
+        check_square(&parse(&binary_square(b"exported part")).unwrap());
+    }
+
+    #[test]
+    fn reads_padded_binary_starting_with_solid() {
+        let mut bytes = binary_square(b"solid part");
+        bytes.extend([0; 16]);
+        check_square(&parse(&bytes).unwrap());
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        let bytes = binary_square(b"exported part");
+        assert!(parse(&bytes[..bytes.len() - 20]).is_none());
+        assert!(parse(&bytes[..60]).is_none());
+
+        let cut = &TWO_FACETS[..TWO_FACETS.find("vertex 0 1 0").unwrap() + 10];
+        assert!(parse(cut.as_bytes()).is_none());
+    }
+}
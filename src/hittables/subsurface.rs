@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use crate::{
+    aabb::AABB,
+    materials::{chromatic_phase::ChromaticPhase, dielectric::Dielectric, material::Material},
+    ray::Ray,
+    vec3::Color,
+};
+
+use super::{
+    constant_medium::ConstantMedium,
+    hittable::{HitRecord, Hittable},
+};
+
+/// A closed object that light refracts into through a dielectric surface and then random
+/// walks through, for skin, wax, marble and milk. The walk is a `ConstantMedium` filling the
+/// boundary, with coefficients in units of one over world space distance.
+pub struct Subsurface {
+    boundary: Arc<Box<dyn Hittable>>,
+    surface: Arc<Box<dyn Material>>,
+    interior: ConstantMedium,
+}
+
+impl Subsurface {
+    pub fn new(b: &Arc<Box<dyn Hittable>>, ir: f64, sigma_s: Color, sigma_a: Color) -> Self {
+        let phase = ChromaticPhase::with_color(sigma_a, sigma_s, Color::new());
+
+        Self {
+            boundary: Arc::clone(b),
+            surface: Arc::new(Box::new(Dielectric::new(ir))),
+            interior: ConstantMedium::chromatic(b, phase),
+        }
+    }
+
+    /// `albedo` is the chance of scattering rather than absorbing at each interaction and
+    /// `mean_free_path` the average distance light travels between interactions.
+    pub fn with_mean_free_path(
+        b: &Arc<Box<dyn Hittable>>,
+        ir: f64,
+        albedo: Color,
+        mean_free_path: Color,
+    ) -> Self {
+        let sigma_t = 1.0 / mean_free_path;
+        Self::new(b, ir, albedo * sigma_t, (1.0 - albedo) * sigma_t)
+    }
+}
+
+impl Hittable for Subsurface {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let mut hit_anything = false;
+        let mut closest_so_far = t_max;
+
+        if self.boundary.hit(ray, t_min, closest_so_far, rec) {
+            hit_anything = true;
+            closest_so_far = rec.t;
+            rec.mat_ptr = Arc::clone(&self.surface);
+        }
+
+        if self.interior.hit(ray, t_min, closest_so_far, rec) {
+            hit_anything = true;
+        }
+
+        hit_anything
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64, output_box: &mut AABB) -> bool {
+        self.boundary.bounding_box(time0, time1, output_box)
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_subsurface {
+    ( $b:expr, $ir:expr, $albedo:expr, $mean_free_path:expr, MeanFreePath ) => {
+        Arc::new(Box::new(Subsurface::with_mean_free_path(
+            $b,
+            $ir,
+            $albedo,
+            $mean_free_path,
+        )))
+    };
+    ( $b:expr, $ir:expr, $sigma_s:expr, $sigma_a:expr ) => {
+        Arc::new(Box::new(Subsurface::new($b, $ir, $sigma_s, $sigma_a)))
+    };
+}
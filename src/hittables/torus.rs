@@ -0,0 +1,234 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use super::{
+    cylinder::around_y,
+    hittable::{is_cutout, HitRecord, Hittable},
+};
+use crate::{
+    aabb::AABB,
+    materials::material::Material,
+    ray::Ray,
+    vec3::{dot, Point3, Vec3},
+};
+
+/// A ring around `center` in the xz plane. `major_radius` is the distance from the center to
+/// the middle of the tube, `minor_radius` the radius of the tube. `u` goes around the ring
+/// like on a `Sphere` and `v` around the tube, starting on the outside.
+pub struct Torus {
+    center: Point3,
+    major_radius: f64,
+    minor_radius: f64,
+    mat_ptr: Arc<Box<dyn Material>>,
+}
+
+impl Torus {
+    pub fn new(
+        center: Point3,
+        major_radius: f64,
+        minor_radius: f64,
+        mat_ptr: &Arc<Box<dyn Material>>,
+    ) -> Self {
+        Self {
+            center,
+            major_radius,
+            minor_radius,
+            mat_ptr: Arc::clone(mat_ptr),
+        }
+    }
+
+    /// Ray distances, along a unit `dir` from `origin` relative to the center, where
+    /// (|p|² + R² - r²)² = 4R²(x² + z²).
+    fn intersections(&self, origin: &Point3, dir: &Vec3) -> Vec<f64> {
+        let r2 = self.major_radius * self.major_radius;
+        let b = 2.0 * dot(origin, dir);
+        let c = origin.length_squared() + r2 - self.minor_radius * self.minor_radius;
+        let e = dir.x() * dir.x() + dir.z() * dir.z();
+        let f = origin.x() * dir.x() + origin.z() * dir.z();
+        let g = origin.x() * origin.x() + origin.z() * origin.z();
+
+        let coefficients = [
+            c * c - 4.0 * r2 * g,
+            2.0 * b * c - 8.0 * r2 * f,
+            b * b + 2.0 * c - 4.0 * r2 * e,
+            2.0 * b,
+        ];
+        let polynomial = |t: f64| {
+            (((t + coefficients[3]) * t + coefficients[2]) * t + coefficients[1]) * t
+                + coefficients[0]
+        };
+        let derivative = |t: f64| {
+            ((4.0 * t + 3.0 * coefficients[3]) * t + 2.0 * coefficients[2]) * t + coefficients[1]
+        };
+
+        // NOTE: the closed form loses precision, a couple of Newton steps win it back
+        solve_quartic(&coefficients)
+            .into_iter()
+            .map(|mut t| {
+                for _ in 0..2 {
+                    let slope = derivative(t);
+                    if slope.abs() > 1e-12 {
+                        t -= polynomial(t) / slope;
+                    }
+                }
+                t
+            })
+            .collect()
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        let length = ray.direction().length();
+        let dir = ray.direction() / length;
+        let origin = ray.origin() - self.center;
+
+        // NOTE: start from the bounding sphere so the coefficients stay small for far rays
+        let bound = self.major_radius + self.minor_radius;
+        let half_b = dot(&origin, &dir);
+        let discriminant = half_b * half_b - (origin.length_squared() - bound * bound);
+        if discriminant < 0.0 {
+            return false;
+        }
+        let skip = (-half_b - discriminant.sqrt()).max(0.0);
+        let start = origin + skip * dir;
+
+        let mut roots: Vec<f64> = self
+            .intersections(&start, &dir)
+            .into_iter()
+            .map(|s| (s + skip) / length)
+            .collect();
+        roots.sort_by(f64::total_cmp);
+
+        for t in roots {
+            if t < t_min || t_max < t {
+                continue;
+            }
+
+            let local = origin + (t * length) * dir;
+            let rho = (local.x() * local.x() + local.z() * local.z())
+                .sqrt()
+                .max(1e-12);
+            let ring = Vec3::with_values(local.x(), 0.0, local.z()) * (self.major_radius / rho);
+            let outward_normal = (local - ring).unit_vector();
+
+            let (u, dpdu) = around_y(&local);
+            let theta = local.y().atan2(rho - self.major_radius);
+            let v = theta.rem_euclid(2.0 * PI) / (2.0 * PI);
+
+            let p = local + self.center;
+            if is_cutout(&self.mat_ptr, u, v, &p) {
+                continue;
+            }
+
+            rec.t = t;
+            rec.p = p;
+            rec.u = u;
+            rec.v = v;
+            rec.set_face_normal(ray, &outward_normal);
+            rec.dpdu = dpdu;
+            rec.dpdv = 2.0
+                * PI
+                * self.minor_radius
+                * Vec3::with_values(
+                    -theta.sin() * local.x() / rho,
+                    theta.cos(),
+                    -theta.sin() * local.z() / rho,
+                );
+            rec.mat_ptr = Arc::clone(&self.mat_ptr);
+            return true;
+        }
+
+        false
+    }
+
+    fn bounding_box(&self, _: f64, _: f64, output_box: &mut AABB) -> bool {
+        let outer = self.major_radius + self.minor_radius;
+        let extent = Vec3::with_values(outer, self.minor_radius, outer);
+        *output_box = AABB::new(self.center - extent, self.center + extent);
+        true
+    }
+}
+
+/// Real roots of t⁴ + c₃t³ + c₂t² + c₁t + c₀, with `coefficients` from `c₀` to `c₃`, by
+/// Ferrari's method.
+fn solve_quartic(coefficients: &[f64; 4]) -> Vec<f64> {
+    let [c0, c1, c2, c3] = *coefficients;
+
+    // NOTE: substituting t = y - c₃/4 leaves y⁴ + py² + qy + r
+    let shift = c3 / 4.0;
+    let p = c2 - 6.0 * shift * shift;
+    let q = c1 - 2.0 * c2 * shift + 8.0 * shift.powi(3);
+    let r = c0 - c1 * shift + c2 * shift * shift - 3.0 * shift.powi(4);
+
+    let mut roots = Vec::with_capacity(4);
+    if q.abs() < 1e-12 {
+        for z in solve_quadratic(p, r) {
+            if z >= 0.0 {
+                roots.push(z.sqrt());
+                roots.push(-z.sqrt());
+            }
+        }
+    } else {
+        // NOTE: any positive root m of the resolvent splits it into two quadratics
+        let m = solve_cubic(p, p * p / 4.0 - r, -q * q / 8.0)
+            .into_iter()
+            .fold(f64::NEG_INFINITY, f64::max);
+        if m <= 0.0 {
+            return roots;
+        }
+
+        let s = (2.0 * m).sqrt();
+        roots.extend(solve_quadratic(-s, p / 2.0 + m + q / (2.0 * s)));
+        roots.extend(solve_quadratic(s, p / 2.0 + m - q / (2.0 * s)));
+    }
+
+    roots.into_iter().map(|y| y - shift).collect()
+}
+
+/// Real roots of x³ + ax² + bx + c.
+fn solve_cubic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    let shift = a / 3.0;
+    let p = b - a * a / 3.0;
+    let q = 2.0 * a.powi(3) / 27.0 - a * b / 3.0 + c;
+    let discriminant = q * q / 4.0 + p.powi(3) / 27.0;
+
+    let roots = if discriminant > 0.0 {
+        let sqrtd = discriminant.sqrt();
+        vec![(-q / 2.0 + sqrtd).cbrt() + (-q / 2.0 - sqrtd).cbrt()]
+    } else if p == 0.0 {
+        vec![0.0]
+    } else {
+        // NOTE: three real roots, found with the trigonometric form
+        let radius = 2.0 * (-p / 3.0).sqrt();
+        let angle = (3.0 * q / (p * radius)).clamp(-1.0, 1.0).acos() / 3.0;
+        (0..3)
+            .map(|k| radius * (angle - 2.0 * PI * k as f64 / 3.0).cos())
+            .collect()
+    };
+
+    roots.into_iter().map(|x| x - shift).collect()
+}
+
+/// Real roots of x² + bx + c.
+fn solve_quadratic(b: f64, c: f64) -> Vec<f64> {
+    let discriminant = b * b - 4.0 * c;
+    if discriminant < 0.0 {
+        return vec![];
+    }
+
+    let sqrtd = discriminant.sqrt();
+    vec![(-b - sqrtd) / 2.0, (-b + sqrtd) / 2.0]
+}
+
+#[macro_export]
+macro_rules! rc_box_torus {
+    ( $center:expr, $major_radius:expr, $minor_radius:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(Torus::new(
+            $center,
+            $major_radius,
+            $minor_radius,
+            $mat_ptr,
+        )))
+    };
+}
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use crate::{aabb::AABB, ray::Ray, vec3::Vec3};
 
-use super::hittable::{HitRecord, Hittable};
+use super::hittable::{HitRecord, Hittable, SurfaceSample};
 
 pub struct Translate {
     ptr: Arc<Box<dyn Hittable>>,
@@ -25,9 +25,8 @@ impl Hittable for Translate {
             return false;
         }
 
+        // NOTE: the normal and front_face are unchanged by a translation
         rec.p += self.offset;
-        let outward_normal = rec.normal;
-        rec.set_face_normal(&moved_r, &outward_normal);
 
         true
     }
@@ -43,6 +42,14 @@ impl Hittable for Translate {
         );
         true
     }
+
+    fn sample_surface(&self) -> Option<SurfaceSample> {
+        let sample = self.ptr.sample_surface()?;
+        Some(SurfaceSample {
+            p: sample.p + self.offset,
+            ..sample
+        })
+    }
 }
 
 #[macro_export]
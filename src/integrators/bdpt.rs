@@ -0,0 +1,769 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::{
+    camera::Camera,
+    hittables::hittable::{HitRecord, Hittable, HittableList},
+    lights::light::{Light, LightList},
+    ray::Ray,
+    vec3::{dot, Color, Point3, Vec3},
+};
+
+use super::{area_emission, random_cosine_direction, LightSource};
+
+/// Light reaching a pixel other than the one being sampled, from light subpaths connected
+/// straight to the camera.
+pub struct Splat {
+    pub i: usize,
+    pub j: usize,
+    pub color: Color,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum VertexKind {
+    Camera,
+    /// A point on the light source with the given index.
+    Light(usize),
+    Surface,
+    Medium,
+}
+
+#[derive(Clone)]
+struct Vertex {
+    kind: VertexKind,
+    p: Point3,
+    /// Geometric normal, zero for vertices that aren't on a surface.
+    n: Vec3,
+    rec: Option<HitRecord>,
+    /// Direction the path arrived in.
+    incoming: Vec3,
+    time: f64,
+    beta: Color,
+    /// Light emitted back along the path, for camera subpaths hitting emissive objects.
+    le: Color,
+    /// Index of the light source a camera subpath vertex lies on, if it is a listed emitter.
+    emitter: Option<usize>,
+    connectible: bool,
+    delta: bool,
+    /// Densities of sampling this vertex from the previous and the next one, per unit area.
+    pdf_fwd: f64,
+    pdf_rev: f64,
+}
+
+impl Vertex {
+    fn endpoint(kind: VertexKind, p: Point3, n: Vec3, beta: Color, time: f64) -> Self {
+        Self {
+            kind,
+            p,
+            n,
+            rec: None,
+            incoming: Vec3::new(),
+            time,
+            beta,
+            le: Color::with_value(0.0),
+            emitter: None,
+            connectible: true,
+            delta: false,
+            pdf_fwd: 0.0,
+            pdf_rev: 0.0,
+        }
+    }
+
+    fn is_on_surface(&self) -> bool {
+        self.n.length_squared() > 0.0
+    }
+
+    fn incoming_ray(&self) -> Ray {
+        Ray::new(self.p - self.incoming, self.incoming, self.time)
+    }
+
+    /// Scattering towards `q` times the cosine term, for surface and medium vertices.
+    fn eval(&self, q: &Point3) -> Color {
+        match &self.rec {
+            Some(rec) => {
+                let wi = (*q - self.p).unit_vector();
+                rec.mat_ptr.eval(&self.incoming_ray(), rec, &wi)
+            }
+            None => Color::with_value(0.0),
+        }
+    }
+
+    /// Turns a density per steradian of leaving this vertex towards `next` into one per unit
+    /// area at `next`.
+    fn convert_density(&self, pdf: f64, next: &Vertex) -> f64 {
+        let w = next.p - self.p;
+        let dist_squared = w.length_squared();
+        if dist_squared == 0.0 {
+            return 0.0;
+        }
+
+        let mut pdf = pdf / dist_squared;
+        if next.is_on_surface() {
+            pdf *= dot(&next.n, &(w / dist_squared.sqrt())).abs();
+        }
+        pdf
+    }
+}
+
+/// Bidirectional path tracer. Every sample traces a subpath from the camera and one from a
+/// light, then connects every prefix of one to every prefix of the other, weighting each
+/// strategy with multiple importance sampling (balance heuristic).
+///
+/// Materials that can't report a `scattering_pdf` are treated as specular, so only the
+/// strategies that reach them by sampling count. Media attached with `interior_medium` are
+/// ignored, `ConstantMedium` and `HeterogeneousMedium` work.
+pub struct Bdpt<'a> {
+    world: &'a HittableList,
+    camera: &'a Camera,
+    background: Color,
+    max_depth: usize,
+    width: usize,
+    height: usize,
+    /// Viewport area at unit distance, including the overhang of the last pixel.
+    film_area: f64,
+    sources: Vec<LightSource>,
+    /// Lights that can't start paths, like directional ones, only reached with shadow rays.
+    direct_only: Vec<Arc<Box<dyn Light>>>,
+}
+
+impl<'a> Bdpt<'a> {
+    pub fn new(
+        world: &'a HittableList,
+        lights: &LightList,
+        camera: &'a Camera,
+        background: Color,
+        max_depth: u64,
+        width: u64,
+        height: u64,
+    ) -> Self {
+        let (sources, direct_only) = LightSource::from_lights(lights);
+
+        let (w, h) = (width as f64, height as f64);
+        Self {
+            world,
+            camera,
+            background,
+            max_depth: max_depth as usize,
+            width: width as usize,
+            height: height as usize,
+            film_area: camera.viewport_area() * (w / (w - 1.0)) * (h / (h - 1.0)),
+            sources,
+            direct_only,
+        }
+    }
+
+    /// Radiance along the camera ray through `(s, t)`, and the light the sample's light
+    /// subpath adds to other pixels.
+    pub fn sample(&self, s: f64, t: f64) -> (Color, Vec<Splat>) {
+        let ray = self.camera.get_ray(s, t);
+        let time = ray.time();
+
+        let mut camera_path = Vec::with_capacity(self.max_depth + 1);
+        let mut light_path = Vec::with_capacity(self.max_depth + 1);
+
+        let mut radiance = self.camera_subpath(ray, &mut camera_path);
+        self.light_subpath(time, &mut light_path);
+
+        let mut splats = Vec::new();
+        for t in 1..=camera_path.len() {
+            for s in 0..=light_path.len() {
+                if (s == 1 && t == 1) || s + t < 2 || s + t - 2 > self.max_depth {
+                    continue;
+                }
+
+                let (contribution, pixel) = self.connect(&mut light_path, &mut camera_path, s, t);
+                if t == 1 {
+                    if let Some((i, j)) = pixel {
+                        splats.push(Splat {
+                            i,
+                            j,
+                            color: contribution,
+                        });
+                    }
+                } else {
+                    radiance += contribution;
+                }
+            }
+        }
+
+        for pt in camera_path.iter().skip(1).filter(|v| v.connectible) {
+            radiance += self.direct_only_light(pt);
+        }
+
+        (radiance, splats)
+    }
+
+    fn camera_subpath(&self, ray: Ray, path: &mut Vec<Vertex>) -> Color {
+        let direction = ray.direction().unit_vector();
+        let pdf_dir = self.camera_pdf(&direction);
+        path.push(Vertex::endpoint(
+            VertexKind::Camera,
+            ray.origin(),
+            Vec3::new(),
+            Color::with_value(1.0),
+            ray.time(),
+        ));
+
+        self.random_walk(ray, Color::with_value(1.0), pdf_dir, true, path)
+    }
+
+    fn light_subpath(&self, time: f64, path: &mut Vec<Vertex>) {
+        if self.sources.is_empty() {
+            return;
+        }
+
+        let mut rng = rand::thread_rng();
+        let index = rng.gen_range(0..self.sources.len());
+        let pick_pdf = 1.0 / self.sources.len() as f64;
+
+        match &self.sources[index] {
+            LightSource::Area(emitter, area) => {
+                let Some(sample) = emitter.sample_surface() else {
+                    return;
+                };
+
+                // NOTE: either side may emit, one-sided lights just waste half the samples
+                let side = if rng.gen::<bool>() { 1.0 } else { -1.0 };
+                let direction = random_cosine_direction(&(side * sample.normal));
+                let cos_theta = dot(&direction, &sample.normal).abs();
+                let pdf_dir = 0.5 * cos_theta / PI;
+                let pdf_pos = 1.0 / area;
+
+                let le = area_emission(emitter, &sample.p, &direction, time);
+                let mut vertex =
+                    Vertex::endpoint(VertexKind::Light(index), sample.p, sample.normal, le, time);
+                vertex.pdf_fwd = pdf_pos * pick_pdf;
+                path.push(vertex);
+
+                // NOTE: the endpoint stays even when dark, connections with `s == 1` replace it
+                if le.near_zero() || pdf_dir <= 0.0 {
+                    return;
+                }
+
+                let beta = le * cos_theta / (pick_pdf * pdf_pos * pdf_dir);
+                let ray = Ray::new(sample.p, direction, time);
+                self.random_walk(ray, beta, pdf_dir, false, path);
+            }
+            LightSource::Delta(light) => {
+                let Some(sample) = light.sample_le() else {
+                    return;
+                };
+
+                let mut vertex = Vertex::endpoint(
+                    VertexKind::Light(index),
+                    sample.origin,
+                    Vec3::new(),
+                    sample.intensity,
+                    time,
+                );
+                vertex.pdf_fwd = pick_pdf;
+                path.push(vertex);
+
+                if sample.intensity.near_zero() || sample.pdf <= 0.0 {
+                    return;
+                }
+
+                let beta = sample.intensity / (pick_pdf * sample.pdf);
+                let ray = Ray::new(sample.origin, sample.direction, time);
+                self.random_walk(ray, beta, sample.pdf, false, path);
+            }
+        }
+    }
+
+    /// Extends `path` by following `ray` until it's absorbed, leaves the scene or reaches
+    /// the depth limit. Returns the background seen by camera subpaths that escape.
+    fn random_walk(
+        &self,
+        mut ray: Ray,
+        mut beta: Color,
+        pdf_dir: f64,
+        from_camera: bool,
+        path: &mut Vec<Vertex>,
+    ) -> Color {
+        let mut rng = rand::thread_rng();
+        let mut pdf_fwd = pdf_dir;
+
+        for bounce in 0..self.max_depth {
+            let mut rec = HitRecord::default();
+            if !self.world.hit(&ray, 0.001, f64::INFINITY, &mut rec) {
+                return if from_camera {
+                    beta * self.background
+                } else {
+                    Color::with_value(0.0)
+                };
+            }
+
+            let incoming = ray.direction().unit_vector();
+            let is_medium = rec.mat_ptr.is_phase_function();
+            let connectible = rec.mat_ptr.scattering_pdf(&ray, &rec, &-incoming).is_some();
+
+            let mut vertex = Vertex {
+                kind: if is_medium {
+                    VertexKind::Medium
+                } else {
+                    VertexKind::Surface
+                },
+                p: rec.p,
+                n: if is_medium { Vec3::new() } else { rec.normal },
+                rec: Some(rec.clone()),
+                incoming,
+                time: ray.time(),
+                beta,
+                le: Color::with_value(0.0),
+                emitter: None,
+                connectible,
+                delta: !connectible,
+                pdf_fwd: 0.0,
+                pdf_rev: 0.0,
+            };
+            vertex.pdf_fwd = path.last().unwrap().convert_density(pdf_fwd, &vertex);
+            if from_camera {
+                vertex.le = rec.mat_ptr.emitted(&ray, &rec);
+                if !vertex.le.near_zero() {
+                    vertex.emitter = self.emitter_at(&ray, &rec);
+                }
+            }
+            path.push(vertex);
+
+            if bounce + 1 >= self.max_depth {
+                break;
+            }
+
+            let mut scattered = Ray::new(Vec3::new(), Vec3::new(), 0.0);
+            let mut attenuation = Color::new();
+            if !rec
+                .mat_ptr
+                .scatter(&ray, &rec, &mut attenuation, &mut scattered)
+            {
+                break;
+            }
+
+            let outgoing = scattered.direction().unit_vector();
+            let mut pdf_rev = 0.0;
+            pdf_fwd = 0.0;
+            match rec.mat_ptr.scattering_pdf(&ray, &rec, &outgoing) {
+                Some(pdf) if pdf > 0.0 => {
+                    pdf_fwd = pdf;
+                    let reversed = Ray::new(rec.p + outgoing, -outgoing, ray.time());
+                    pdf_rev = rec
+                        .mat_ptr
+                        .scattering_pdf(&reversed, &rec, &-incoming)
+                        .unwrap_or(0.0);
+                }
+                _ => path.last_mut().unwrap().delta = true,
+            }
+
+            let n = path.len();
+            path[n - 2].pdf_rev = path[n - 1].convert_density(pdf_rev, &path[n - 2]);
+            beta *= attenuation;
+
+            // NOTE: roulette doesn't change the densities, the weights stay a partition of one
+            if bounce >= 3 {
+                let survival = beta.x().max(beta.y()).max(beta.z()).clamp(0.05, 0.95);
+                if rng.gen::<f64>() > survival {
+                    break;
+                }
+                beta /= survival;
+            }
+
+            ray = scattered;
+        }
+
+        Color::with_value(0.0)
+    }
+
+    /// Contribution of the path made from the first `s` light and `t` camera vertices, with
+    /// the pixel it lands on for `t == 1`.
+    fn connect(
+        &self,
+        light_path: &mut [Vertex],
+        camera_path: &mut [Vertex],
+        s: usize,
+        t: usize,
+    ) -> (Color, Option<(usize, usize)>) {
+        let black = Color::with_value(0.0);
+        let mut sampled: Option<Vertex> = None;
+        let mut pixel = None;
+
+        let contribution = if s == 0 {
+            let pt = &camera_path[t - 1];
+            pt.le * pt.beta
+        } else if t == 1 {
+            let qs = &light_path[s - 1];
+            if !qs.connectible {
+                return (black, None);
+            }
+
+            let lens = self.camera.sample_lens();
+            let Some(film) = self.pixel_at(&lens, &qs.p) else {
+                return (black, None);
+            };
+            pixel = Some(film);
+
+            let to_lens = lens - qs.p;
+            let dist_squared = to_lens.length_squared();
+            let cos_camera = dot(&(-to_lens / dist_squared.sqrt()), &self.camera.forward());
+            // NOTE: importance times the cosine at the lens over the density of the lens point
+            let importance = 1.0 / (self.film_area * cos_camera.powi(3) * dist_squared);
+
+            let camera_vertex = Vertex::endpoint(
+                VertexKind::Camera,
+                lens,
+                Vec3::new(),
+                Color::with_value(importance),
+                qs.time,
+            );
+            let l = qs.beta * qs.eval(&lens) * camera_vertex.beta;
+            sampled = Some(camera_vertex);
+
+            if l.near_zero() || !self.visible(&qs.p, &lens, qs.time) {
+                return (black, None);
+            }
+            l
+        } else if s == 1 {
+            let pt = &camera_path[t - 1];
+            if !pt.connectible {
+                return (black, None);
+            }
+
+            let Some(light_vertex) = self.sample_light_vertex(pt) else {
+                return (black, None);
+            };
+            let l = pt.beta * pt.eval(&light_vertex.p) * light_vertex.beta;
+            let p = light_vertex.p;
+            sampled = Some(light_vertex);
+
+            if l.near_zero() || !self.visible(&pt.p, &p, pt.time) {
+                return (black, None);
+            }
+            l
+        } else {
+            let qs = &light_path[s - 1];
+            let pt = &camera_path[t - 1];
+            if !qs.connectible || !pt.connectible {
+                return (black, None);
+            }
+
+            let l = qs.beta * qs.eval(&pt.p) * pt.eval(&qs.p) * pt.beta;
+            if l.near_zero() || !self.visible(&qs.p, &pt.p, pt.time) {
+                return (black, None);
+            }
+            l / (qs.p - pt.p).length_squared()
+        };
+
+        if contribution.near_zero() {
+            return (black, None);
+        }
+
+        let weight = self.mis_weight(light_path, camera_path, sampled, s, t);
+        (weight * contribution, pixel)
+    }
+
+    /// Picks a light and a point on it as seen from `pt`, as the end of a path with one light
+    /// vertex. Its `beta` is the light arriving at `pt` over the density of picking it.
+    fn sample_light_vertex(&self, pt: &Vertex) -> Option<Vertex> {
+        if self.sources.is_empty() {
+            return None;
+        }
+
+        let mut rng = rand::thread_rng();
+        let index = rng.gen_range(0..self.sources.len());
+        let pick_pdf = 1.0 / self.sources.len() as f64;
+
+        match &self.sources[index] {
+            LightSource::Area(emitter, area) => {
+                let sample = emitter.sample_surface()?;
+                let to_pt = pt.p - sample.p;
+                let dist_squared = to_pt.length_squared();
+                let direction = to_pt / dist_squared.sqrt();
+
+                let le = area_emission(emitter, &sample.p, &direction, pt.time);
+                let cos_light = dot(&direction, &sample.normal).abs();
+                let beta = le * cos_light * *area / (dist_squared * pick_pdf);
+
+                let mut vertex = Vertex::endpoint(
+                    VertexKind::Light(index),
+                    sample.p,
+                    sample.normal,
+                    beta,
+                    pt.time,
+                );
+                vertex.pdf_fwd = pick_pdf / area;
+                Some(vertex)
+            }
+            LightSource::Delta(light) => {
+                let sample = light.sample_li(&pt.p)?;
+                let p = pt.p + sample.distance * sample.direction;
+
+                let mut vertex = Vertex::endpoint(
+                    VertexKind::Light(index),
+                    p,
+                    Vec3::new(),
+                    sample.radiance / pick_pdf,
+                    pt.time,
+                );
+                vertex.pdf_fwd = pick_pdf;
+                Some(vertex)
+            }
+        }
+    }
+
+    /// Light from sources that can't start subpaths, which no other strategy can find.
+    fn direct_only_light(&self, pt: &Vertex) -> Color {
+        let mut radiance = Color::with_value(0.0);
+        for light in self.direct_only.iter() {
+            let Some(sample) = light.sample_li(&pt.p) else {
+                continue;
+            };
+
+            let towards = pt.p + sample.direction;
+            let f = pt.eval(&towards);
+            if f.near_zero() {
+                continue;
+            }
+
+            let shadow_ray = Ray::new(pt.p, sample.direction, pt.time);
+            let mut rec = HitRecord::default();
+            if !self
+                .world
+                .hit(&shadow_ray, 0.001, sample.distance, &mut rec)
+            {
+                radiance += pt.beta * f * sample.radiance;
+            }
+        }
+        radiance
+    }
+
+    /// Balance heuristic weight of the strategy that made the path from `s` light and `t`
+    /// camera vertices, compared to every other way of sampling the same path.
+    fn mis_weight(
+        &self,
+        light_path: &mut [Vertex],
+        camera_path: &mut [Vertex],
+        sampled: Option<Vertex>,
+        s: usize,
+        t: usize,
+    ) -> f64 {
+        if s + t == 2 {
+            return 1.0;
+        }
+
+        // NOTE: a camera subpath hitting an emitter nobody listed can't be made any other way
+        if s == 0 && camera_path[t - 1].emitter.is_none() {
+            return 1.0;
+        }
+
+        // NOTE: the path is changed in place for the weight, then put back as it was
+        let replaced = sampled.map(|v| {
+            if s == 1 {
+                std::mem::replace(&mut light_path[0], v)
+            } else {
+                std::mem::replace(&mut camera_path[0], v)
+            }
+        });
+        let saved_camera: Vec<(f64, bool)> = camera_path[t.saturating_sub(2)..t]
+            .iter()
+            .map(|v| (v.pdf_rev, v.delta))
+            .collect();
+        let saved_light: Vec<(f64, bool)> = light_path[s.saturating_sub(2)..s]
+            .iter()
+            .map(|v| (v.pdf_rev, v.delta))
+            .collect();
+
+        {
+            let pt = &camera_path[t - 1];
+            let pt_minus = (t > 1).then(|| &camera_path[t - 2]);
+            let qs = (s > 0).then(|| &light_path[s - 1]);
+            let qs_minus = (s > 1).then(|| &light_path[s - 2]);
+
+            let pt_rev = match qs {
+                Some(qs) => self.pdf(qs, qs_minus, pt),
+                None => self.pdf_light_origin(pt),
+            };
+            let pt_minus_rev = pt_minus.map(|pt_minus| match qs {
+                Some(qs) => self.pdf(pt, Some(qs), pt_minus),
+                None => self.pdf_light(pt, pt_minus),
+            });
+            let qs_rev = qs.map(|qs| self.pdf(pt, pt_minus, qs));
+            let qs_minus_rev = qs_minus.map(|qs_minus| self.pdf(qs.unwrap(), Some(pt), qs_minus));
+
+            camera_path[t - 1].pdf_rev = pt_rev;
+            camera_path[t - 1].delta = false;
+            if let Some(pdf) = pt_minus_rev {
+                camera_path[t - 2].pdf_rev = pdf;
+            }
+            if let Some(pdf) = qs_rev {
+                light_path[s - 1].pdf_rev = pdf;
+                light_path[s - 1].delta = false;
+            }
+            if let Some(pdf) = qs_minus_rev {
+                light_path[s - 2].pdf_rev = pdf;
+            }
+        }
+
+        let remap = |pdf: f64| if pdf != 0.0 { pdf } else { 1.0 };
+        let mut sum_ri = 0.0;
+
+        let mut ri = 1.0;
+        for i in (1..t).rev() {
+            ri *= remap(camera_path[i].pdf_rev) / remap(camera_path[i].pdf_fwd);
+            if !camera_path[i].delta && !camera_path[i - 1].delta {
+                sum_ri += ri;
+            }
+        }
+
+        let mut ri = 1.0;
+        for i in (0..s).rev() {
+            ri *= remap(light_path[i].pdf_rev) / remap(light_path[i].pdf_fwd);
+            let delta_light = if i > 0 {
+                light_path[i - 1].delta
+            } else {
+                self.is_delta_light(&light_path[0])
+            };
+            if !light_path[i].delta && !delta_light {
+                sum_ri += ri;
+            }
+        }
+
+        for (v, (pdf_rev, delta)) in camera_path[t.saturating_sub(2)..t]
+            .iter_mut()
+            .zip(saved_camera)
+        {
+            v.pdf_rev = pdf_rev;
+            v.delta = delta;
+        }
+        for (v, (pdf_rev, delta)) in light_path[s.saturating_sub(2)..s]
+            .iter_mut()
+            .zip(saved_light)
+        {
+            v.pdf_rev = pdf_rev;
+            v.delta = delta;
+        }
+        if let Some(v) = replaced {
+            if s == 1 {
+                light_path[0] = v;
+            } else {
+                camera_path[0] = v;
+            }
+        }
+
+        1.0 / (1.0 + sum_ri)
+    }
+
+    /// Density per unit area at `next` of sampling it from `v`, which was reached from
+    /// `prev`.
+    fn pdf(&self, v: &Vertex, prev: Option<&Vertex>, next: &Vertex) -> f64 {
+        let to_next = next.p - v.p;
+        if to_next.length_squared() == 0.0 {
+            return 0.0;
+        }
+        let wn = to_next.unit_vector();
+
+        let pdf = match v.kind {
+            VertexKind::Light(_) => return self.pdf_light(v, next),
+            VertexKind::Camera => self.camera_pdf(&wn),
+            VertexKind::Surface | VertexKind::Medium => {
+                let (Some(rec), Some(prev)) = (&v.rec, prev) else {
+                    return 0.0;
+                };
+                let arriving = Ray::new(prev.p, v.p - prev.p, v.time);
+                rec.mat_ptr
+                    .scattering_pdf(&arriving, rec, &wn)
+                    .unwrap_or(0.0)
+            }
+        };
+
+        v.convert_density(pdf, next)
+    }
+
+    /// Density per unit area at `next` of light leaving the light vertex `v` towards it.
+    fn pdf_light(&self, v: &Vertex, next: &Vertex) -> f64 {
+        let Some(source) = self.source_of(v) else {
+            return 0.0;
+        };
+
+        let w = next.p - v.p;
+        let dist_squared = w.length_squared();
+        if dist_squared == 0.0 {
+            return 0.0;
+        }
+        let w = w / dist_squared.sqrt();
+
+        let pdf_dir = match source {
+            LightSource::Area(..) => 0.5 * dot(&v.n, &w).abs() / PI,
+            LightSource::Delta(light) => light.pdf_le(&w),
+        };
+
+        let mut pdf = pdf_dir / dist_squared;
+        if next.is_on_surface() {
+            pdf *= dot(&next.n, &w).abs();
+        }
+        pdf
+    }
+
+    /// Density per unit area of starting a light subpath at `v`.
+    fn pdf_light_origin(&self, v: &Vertex) -> f64 {
+        let pick_pdf = 1.0 / self.sources.len() as f64;
+        match self.source_of(v) {
+            Some(LightSource::Area(_, area)) => pick_pdf / area,
+            Some(LightSource::Delta(_)) => pick_pdf,
+            None => 0.0,
+        }
+    }
+
+    fn source_of(&self, v: &Vertex) -> Option<&LightSource> {
+        match v.kind {
+            VertexKind::Light(index) => self.sources.get(index),
+            _ => v.emitter.and_then(|index| self.sources.get(index)),
+        }
+    }
+
+    fn is_delta_light(&self, v: &Vertex) -> bool {
+        matches!(self.source_of(v), Some(LightSource::Delta(_)))
+    }
+
+    /// Density per steradian of the camera sending a ray along the unit `direction`.
+    fn camera_pdf(&self, direction: &Vec3) -> f64 {
+        let cos_theta = dot(direction, &self.camera.forward());
+        if cos_theta <= 0.0 {
+            return 0.0;
+        }
+
+        1.0 / (self.film_area * cos_theta.powi(3))
+    }
+
+    /// Pixel the ray from `lens` through `p` lands on, matching how `main` picks `(s, t)`.
+    fn pixel_at(&self, lens: &Point3, p: &Point3) -> Option<(usize, usize)> {
+        let (s, t) = self.camera.viewport_coords(lens, p)?;
+        if s < 0.0 || t < 0.0 {
+            return None;
+        }
+
+        let i = (s * (self.width - 1) as f64) as usize;
+        let j = (t * (self.height - 1) as f64) as usize;
+        (i < self.width && j < self.height).then_some((i, j))
+    }
+
+    /// Index of the listed emitter `ray` hit at `rec`, if any.
+    fn emitter_at(&self, ray: &Ray, rec: &HitRecord) -> Option<usize> {
+        let tolerance = 1e-6 * rec.t.max(1.0);
+        self.sources.iter().position(|source| match source {
+            LightSource::Area(emitter, _) => {
+                let mut emitter_rec = HitRecord::default();
+                emitter.hit(ray, 0.001, rec.t + tolerance, &mut emitter_rec)
+                    && (emitter_rec.t - rec.t).abs() <= tolerance
+            }
+            LightSource::Delta(_) => false,
+        })
+    }
+
+    fn visible(&self, a: &Point3, b: &Point3, time: f64) -> bool {
+        let w = *b - *a;
+        let distance = w.length();
+        let ray = Ray::new(*a, w / distance, time);
+        let mut rec = HitRecord::default();
+        !self.world.hit(&ray, 0.001, distance - 0.001, &mut rec)
+    }
+}
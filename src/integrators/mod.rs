@@ -0,0 +1,81 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::{
+    hittables::hittable::{HitRecord, Hittable},
+    lights::light::{Light, LightList},
+    onb::Onb,
+    ray::Ray,
+    vec3::{Color, Point3, Vec3},
+};
+
+pub mod bdpt;
+pub mod path;
+pub mod photon;
+pub mod photon_map;
+
+/// Where paths traced from the lights can start.
+pub enum LightSource {
+    /// An object with an emissive material and its surface area.
+    Area(Arc<Box<dyn Hittable>>, f64),
+    Delta(Arc<Box<dyn Light>>),
+}
+
+impl LightSource {
+    /// Sources for every listed emitter and light, along with the lights that can't start
+    /// paths, like directional ones.
+    pub fn from_lights(lights: &LightList) -> (Vec<LightSource>, Vec<Arc<Box<dyn Light>>>) {
+        let mut sources = Vec::new();
+        for emitter in lights.emitters.iter() {
+            match emitter.sample_surface() {
+                Some(sample) => sources.push(LightSource::Area(Arc::clone(emitter), sample.area)),
+                None => eprintln!("Emitter can't be sampled, it is only found by chance"),
+            }
+        }
+
+        let mut direct_only = Vec::new();
+        for light in lights.lights.iter() {
+            if light.sample_le().is_some() {
+                sources.push(LightSource::Delta(Arc::clone(light)));
+            } else {
+                direct_only.push(Arc::clone(light));
+            }
+        }
+
+        (sources, direct_only)
+    }
+}
+
+/// Light an emitter gives off at its surface point `p` along the unit `direction`, found by
+/// hitting it from that side so one-sided and textured emission come out right.
+pub fn area_emission(
+    emitter: &Arc<Box<dyn Hittable>>,
+    p: &Point3,
+    direction: &Vec3,
+    time: f64,
+) -> Color {
+    let probe = Ray::new(*p + 1e-3 * *direction, -*direction, time);
+    let mut rec = HitRecord::default();
+    if !emitter.hit(&probe, 0.0, 2e-3, &mut rec) {
+        return Color::with_value(0.0);
+    }
+
+    rec.mat_ptr.emitted(&probe, &rec)
+}
+
+/// Cosine weighted direction in the hemisphere around `n`.
+pub fn random_cosine_direction(n: &Vec3) -> Vec3 {
+    let mut rng = rand::thread_rng();
+    let r1: f64 = rng.gen();
+    let r2: f64 = rng.gen();
+
+    let phi = 2.0 * PI * r1;
+    let uvw = Onb::build_from_w(n);
+    uvw.local(
+        phi.cos() * r2.sqrt(),
+        phi.sin() * r2.sqrt(),
+        (1.0 - r2).sqrt(),
+    )
+}
@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use crate::{
+    hittables::hittable::{HitRecord, Hittable, HittableList},
+    lights::light::LightList,
+    medium::Medium,
+    ray::Ray,
+    spectrum,
+    vec3::{dot, Color, Vec3},
+};
+
+pub fn ray_color(
+    ray: Ray,
+    background: &Color,
+    world: &HittableList,
+    lights: &LightList,
+    depth: u64,
+) -> Color {
+    let (emitted_attenuation, final_ray_color) = trace_path(ray, background, world, lights, depth);
+
+    emitted_attenuation
+        .iter()
+        .rev()
+        .fold(final_ray_color, |mut acc, &(emitted, attenutation)| {
+            acc = emitted + attenutation * acc;
+            acc
+        })
+}
+
+/// Like `ray_color` but for a single random wavelength, each colour along the path is turned
+/// into its spectral value before they're multiplied together.
+pub fn spectral_ray_color(
+    mut ray: Ray,
+    background: &Color,
+    world: &HittableList,
+    lights: &LightList,
+    depth: u64,
+) -> Color {
+    let lambda = spectrum::sample_wavelength();
+    ray.set_wavelength(Some(lambda));
+
+    let (emitted_attenuation, final_ray_color) = trace_path(ray, background, world, lights, depth);
+
+    let radiance = emitted_attenuation.iter().rev().fold(
+        spectrum::rgb_to_spectrum(&final_ray_color, lambda),
+        |acc, (emitted, attenutation)| {
+            spectrum::rgb_to_spectrum(emitted, lambda)
+                + spectrum::rgb_to_spectrum(attenutation, lambda) * acc
+        },
+    );
+    spectrum::spectral_to_rgb(radiance, lambda)
+}
+
+/// Follows `ray` through the scene, returning the emitted light and attenuation at each
+/// bounce along with the colour the path ended on. Light from `lights` is gathered with
+/// shadow rays at every bounce and counted as emitted.
+fn trace_path(
+    mut ray: Ray,
+    background: &Color,
+    world: &HittableList,
+    lights: &LightList,
+    depth: u64,
+) -> (Vec<(Color, Color)>, Color) {
+    let mut emitted_attenuation: Vec<(Color, Color)> = Vec::with_capacity(depth as usize);
+    // NOTE: media of the closed surfaces the ray is currently inside, innermost last
+    let mut media: Vec<Arc<Box<dyn Medium>>> = Vec::new();
+
+    let mut final_ray_color: Color = Color::with_value(0.0);
+    for _ in (0..depth).rev() {
+        let mut rec = HitRecord::default();
+
+        let hit_surface = world.hit(&ray, 0.001, f64::INFINITY, &mut rec);
+        let t_max = if hit_surface { rec.t } else { f64::INFINITY };
+        let hit_medium = media
+            .last()
+            .is_some_and(|medium| medium.sample(&ray, t_max, &mut rec));
+
+        if !hit_surface && !hit_medium {
+            final_ray_color = *background;
+            break;
+        }
+
+        let mut scattered: Ray = Ray::new(Vec3::new(), Vec3::new(), 0.0);
+        let mut attenutation: Color = Color::new();
+        // NOTE: medium collisions are hits too, so glowing volumes add their emission here.
+        // Light is gathered before scattering, as absorbing collisions still score it
+        let emitted: Color =
+            rec.mat_ptr.emitted(&ray, &rec) + direct_light(&ray, &rec, world, lights, media.last());
+
+        if !rec
+            .mat_ptr
+            .scatter(&ray, &rec, &mut attenutation, &mut scattered)
+        {
+            final_ray_color = emitted;
+            break;
+        }
+
+        let transmitted = !hit_medium && dot(&scattered.direction(), &rec.normal) < 0.0;
+        if let Some(medium) = rec.mat_ptr.interior_medium().filter(|_| transmitted) {
+            if rec.front_face {
+                media.push(medium);
+            } else if let Some(i) = media.iter().rposition(|m| Arc::ptr_eq(m, &medium)) {
+                media.remove(i);
+            }
+        }
+
+        emitted_attenuation.push((emitted, attenutation));
+        scattered.set_wavelength(ray.wavelength());
+        ray = scattered;
+    }
+
+    (emitted_attenuation, final_ray_color)
+}
+
+/// Light from `lights` reaching the hit in `rec` and scattered back along `ray`. Shadow rays
+/// are blocked by any surface and by collisions in `medium`, the medium around the hit.
+pub fn direct_light(
+    ray: &Ray,
+    rec: &HitRecord,
+    world: &HittableList,
+    lights: &LightList,
+    medium: Option<&Arc<Box<dyn Medium>>>,
+) -> Color {
+    let mut radiance = Color::with_value(0.0);
+    for light in lights.lights.iter() {
+        let Some(sample) = light.sample_li(&rec.p) else {
+            continue;
+        };
+
+        let f = rec.mat_ptr.eval(ray, rec, &sample.direction);
+        if f.near_zero() {
+            continue;
+        }
+
+        let shadow_ray = Ray::new(rec.p, sample.direction, ray.time());
+        let mut shadow_rec = HitRecord::default();
+        if world.hit(&shadow_ray, 0.001, sample.distance, &mut shadow_rec)
+            || medium.is_some_and(|m| m.sample(&shadow_ray, sample.distance, &mut shadow_rec))
+        {
+            continue;
+        }
+
+        radiance += f * sample.radiance;
+    }
+
+    radiance
+}
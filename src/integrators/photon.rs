@@ -0,0 +1,295 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use rand::Rng;
+use rayon::prelude::*;
+
+use crate::{
+    hittables::hittable::{HitRecord, Hittable, HittableList},
+    lights::light::LightList,
+    medium::Medium,
+    ray::Ray,
+    vec3::{dot, Color, Vec3},
+};
+
+use super::{
+    area_emission,
+    path::direct_light,
+    photon_map::{Photon, PhotonMap},
+    random_cosine_direction, LightSource,
+};
+
+/// Photons gathered for each density estimate.
+const GATHER_COUNT: usize = 50;
+
+/// Path tracer that takes caustics from a photon map. Photons are shot from the listed
+/// emitters and the lights that can start paths, and stored where they first land on a
+/// diffuse surface after at least one specular bounce. Camera paths add a density estimate
+/// of them at every diffuse hit instead of finding the same light through specular bounces.
+///
+/// Photons ignore media and directional lights shoot none, so their caustics come from path
+/// tracing alone.
+pub struct PhotonMapper<'a> {
+    world: &'a HittableList,
+    lights: &'a LightList,
+    background: Color,
+    max_depth: u64,
+    sources: Vec<LightSource>,
+    caustics: PhotonMap,
+    /// Farthest a photon can be from a hit and still count towards it.
+    max_radius: f64,
+}
+
+impl<'a> PhotonMapper<'a> {
+    /// Shoots `photon_count` photons, only the ones that make caustics are kept.
+    pub fn new(
+        world: &'a HittableList,
+        lights: &'a LightList,
+        background: Color,
+        max_depth: u64,
+        photon_count: usize,
+    ) -> Self {
+        let (sources, _) = LightSource::from_lights(lights);
+        let mut photon_mapper = Self {
+            world,
+            lights,
+            background,
+            max_depth,
+            sources,
+            caustics: PhotonMap::new(Vec::new()),
+            max_radius: 0.0,
+        };
+
+        if !photon_mapper.sources.is_empty() {
+            let scale = 1.0 / photon_count as f64;
+            let photons: Vec<Photon> = (0..photon_count)
+                .into_par_iter()
+                .filter_map(|_| photon_mapper.trace_photon(scale))
+                .collect();
+            photon_mapper.caustics = PhotonMap::new(photons);
+            photon_mapper.max_radius = typical_radius(&photon_mapper.caustics);
+        }
+
+        photon_mapper
+    }
+
+    /// Follows one photon from a random light through specular bounces, returning it if it
+    /// lands on a diffuse surface after at least one of them.
+    fn trace_photon(&self, scale: f64) -> Option<Photon> {
+        let mut rng = rand::thread_rng();
+        let pick_pdf = 1.0 / self.sources.len() as f64;
+        // NOTE: every scene opens the shutter from 0 to 1
+        let time = rng.gen::<f64>();
+
+        let (mut ray, mut power) = match &self.sources[rng.gen_range(0..self.sources.len())] {
+            LightSource::Area(emitter, area) => {
+                let sample = emitter.sample_surface()?;
+                let side = if rng.gen::<bool>() { 1.0 } else { -1.0 };
+                let direction = random_cosine_direction(&(side * sample.normal));
+                let cos_theta = dot(&direction, &sample.normal).abs();
+                let pdf_dir = 0.5 * cos_theta / PI;
+                if pdf_dir <= 0.0 {
+                    return None;
+                }
+
+                let le = area_emission(emitter, &sample.p, &direction, time);
+                let power = le * cos_theta * *area / (pick_pdf * pdf_dir);
+                (Ray::new(sample.p, direction, time), power)
+            }
+            LightSource::Delta(light) => {
+                let sample = light.sample_le()?;
+                if sample.pdf <= 0.0 {
+                    return None;
+                }
+
+                let power = sample.intensity / (pick_pdf * sample.pdf);
+                (Ray::new(sample.origin, sample.direction, time), power)
+            }
+        };
+        power *= scale;
+
+        let mut specular = false;
+        for _ in 0..self.max_depth {
+            if power.near_zero() {
+                return None;
+            }
+
+            let mut rec = HitRecord::default();
+            if !self.world.hit(&ray, 0.001, f64::INFINITY, &mut rec)
+                || rec.mat_ptr.is_phase_function()
+            {
+                return None;
+            }
+
+            let incoming = ray.direction().unit_vector();
+            if rec.mat_ptr.scattering_pdf(&ray, &rec, &-incoming).is_some() {
+                return specular.then_some(Photon {
+                    p: rec.p,
+                    incoming,
+                    normal: rec.normal,
+                    power,
+                });
+            }
+
+            let mut scattered = Ray::new(Vec3::new(), Vec3::new(), 0.0);
+            let mut attenuation = Color::new();
+            if !rec
+                .mat_ptr
+                .scatter(&ray, &rec, &mut attenuation, &mut scattered)
+            {
+                return None;
+            }
+
+            power *= attenuation;
+            specular = true;
+            ray = scattered;
+        }
+
+        None
+    }
+
+    /// Radiance along `ray`, path traced like `ray_color` apart from the caustics.
+    pub fn ray_color(&self, mut ray: Ray) -> Color {
+        let mut radiance = Color::with_value(0.0);
+        let mut throughput = Color::with_value(1.0);
+        // NOTE: media of the closed surfaces the ray is currently inside, innermost last
+        let mut media: Vec<Arc<Box<dyn Medium>>> = Vec::new();
+
+        // NOTE: a path that left a diffuse hit through specular bounces alone is a caustic
+        // path, the photons already brought its light back to that hit
+        let mut after_estimate = false;
+        let mut caustic = false;
+
+        for _ in 0..self.max_depth {
+            let mut rec = HitRecord::default();
+
+            let hit_surface = self.world.hit(&ray, 0.001, f64::INFINITY, &mut rec);
+            let t_max = if hit_surface { rec.t } else { f64::INFINITY };
+            let hit_medium = media
+                .last()
+                .is_some_and(|medium| medium.sample(&ray, t_max, &mut rec));
+
+            if !hit_surface && !hit_medium {
+                radiance += throughput * self.background;
+                break;
+            }
+
+            let emitted = rec.mat_ptr.emitted(&ray, &rec);
+            let in_photon_map = caustic && !emitted.near_zero() && self.shoots_photons(&ray, &rec);
+            if !in_photon_map {
+                radiance += throughput * emitted;
+            }
+
+            let mut scattered = Ray::new(Vec3::new(), Vec3::new(), 0.0);
+            let mut attenuation = Color::new();
+            if !rec
+                .mat_ptr
+                .scatter(&ray, &rec, &mut attenuation, &mut scattered)
+            {
+                break;
+            }
+
+            radiance +=
+                throughput * direct_light(&ray, &rec, self.world, self.lights, media.last());
+
+            let outgoing = scattered.direction().unit_vector();
+            if rec.mat_ptr.is_phase_function() {
+                after_estimate = false;
+                caustic = false;
+            } else if rec.mat_ptr.scattering_pdf(&ray, &rec, &outgoing).is_some() {
+                radiance += throughput * self.caustic_radiance(&ray, &rec);
+                after_estimate = true;
+                caustic = false;
+            } else {
+                caustic = after_estimate;
+            }
+
+            let transmitted = !hit_medium && dot(&scattered.direction(), &rec.normal) < 0.0;
+            if let Some(medium) = rec.mat_ptr.interior_medium().filter(|_| transmitted) {
+                if rec.front_face {
+                    media.push(medium);
+                } else if let Some(i) = media.iter().rposition(|m| Arc::ptr_eq(m, &medium)) {
+                    media.remove(i);
+                }
+            }
+
+            throughput *= attenuation;
+            scattered.set_wavelength(ray.wavelength());
+            ray = scattered;
+        }
+
+        radiance
+    }
+
+    /// Light the caustic photons around the hit in `rec` scatter back along `ray`.
+    fn caustic_radiance(&self, ray: &Ray, rec: &HitRecord) -> Color {
+        let mut radiance = Color::with_value(0.0);
+        if self.caustics.is_empty() {
+            return radiance;
+        }
+
+        let neighbours = self.caustics.nearest(&rec.p, GATHER_COUNT, self.max_radius);
+        let radius_squared = if neighbours.len() == GATHER_COUNT {
+            neighbours.iter().map(|(d, _)| *d).fold(0.0, f64::max)
+        } else {
+            self.max_radius * self.max_radius
+        };
+        if radius_squared <= 0.0 {
+            return radiance;
+        }
+
+        for (_, photon) in neighbours {
+            if dot(&photon.normal, &rec.normal) < 0.9 {
+                continue;
+            }
+
+            // NOTE: `eval` includes the cosine term the photon's power already accounts for
+            let wi = -photon.incoming;
+            let cos_theta = dot(&wi, &rec.normal);
+            if cos_theta <= 1e-4 {
+                continue;
+            }
+            radiance += rec.mat_ptr.eval(ray, rec, &wi) / cos_theta * photon.power;
+        }
+
+        radiance / (PI * radius_squared)
+    }
+
+    /// Whether the emitter `ray` hit at `rec` is one photons are shot from.
+    fn shoots_photons(&self, ray: &Ray, rec: &HitRecord) -> bool {
+        let tolerance = 1e-6 * rec.t.max(1.0);
+        self.sources.iter().any(|source| match source {
+            LightSource::Area(emitter, _) => {
+                let mut emitter_rec = HitRecord::default();
+                emitter.hit(ray, 0.001, rec.t + tolerance, &mut emitter_rec)
+                    && (emitter_rec.t - rec.t).abs() <= tolerance
+            }
+            LightSource::Delta(_) => false,
+        })
+    }
+}
+
+/// Twice the median distance to the `GATHER_COUNT`th nearest photon around a spread of the
+/// photons in `map`, so estimates reach about as far as the photons are dense.
+fn typical_radius(map: &PhotonMap) -> f64 {
+    if map.is_empty() {
+        return 0.0;
+    }
+
+    let step = (map.len() / 256).max(1);
+    let mut radii: Vec<f64> = map
+        .photons()
+        .iter()
+        .step_by(step)
+        .map(|photon| {
+            map.nearest(&photon.p, GATHER_COUNT, f64::INFINITY)
+                .iter()
+                .map(|(d, _)| *d)
+                .fold(0.0, f64::max)
+                .sqrt()
+        })
+        .collect();
+    radii.sort_by(f64::total_cmp);
+
+    2.0 * radii[radii.len() / 2]
+}
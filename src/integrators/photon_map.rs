@@ -0,0 +1,158 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::vec3::{Color, Point3, Vec3};
+
+/// Light a photon carried to a diffuse surface.
+#[derive(Clone, Copy)]
+pub struct Photon {
+    pub p: Point3,
+    /// Unit direction the photon arrived in.
+    pub incoming: Vec3,
+    /// Normal of the surface it landed on, to tell apart the two sides of thin walls.
+    pub normal: Vec3,
+    pub power: Color,
+}
+
+/// Photons in a balanced kd-tree. The tree is implicit: the node splitting a range of
+/// `photons` is the one in its middle, with the smaller ones to its left.
+pub struct PhotonMap {
+    photons: Vec<Photon>,
+    /// Axis each node splits along, at the same index as its photon.
+    axes: Vec<usize>,
+}
+
+impl PhotonMap {
+    pub fn new(mut photons: Vec<Photon>) -> Self {
+        let mut axes = vec![0; photons.len()];
+        Self::build(&mut photons, &mut axes);
+        Self { photons, axes }
+    }
+
+    fn build(photons: &mut [Photon], axes: &mut [usize]) {
+        if photons.len() < 2 {
+            return;
+        }
+
+        let mut min = photons[0].p;
+        let mut max = photons[0].p;
+        for photon in photons.iter() {
+            for a in 0..3 {
+                min[a] = min[a].min(photon.p[a]);
+                max[a] = max[a].max(photon.p[a]);
+            }
+        }
+        let extent = max - min;
+        let axis = (0..3)
+            .max_by(|&a, &b| extent[a].total_cmp(&extent[b]))
+            .unwrap();
+
+        let mid = photons.len() / 2;
+        photons.select_nth_unstable_by(mid, |a, b| a.p[axis].total_cmp(&b.p[axis]));
+        axes[mid] = axis;
+
+        let (left, right) = photons.split_at_mut(mid);
+        let (left_axes, right_axes) = axes.split_at_mut(mid);
+        Self::build(left, left_axes);
+        Self::build(&mut right[1..], &mut right_axes[1..]);
+    }
+
+    pub fn len(&self) -> usize {
+        self.photons.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.photons.is_empty()
+    }
+
+    pub fn photons(&self) -> &[Photon] {
+        &self.photons
+    }
+
+    /// Up to `k` photons closest to `p` that are within `max_distance`, paired with their
+    /// squared distances, in no particular order.
+    pub fn nearest(&self, p: &Point3, k: usize, max_distance: f64) -> Vec<(f64, &Photon)> {
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search(0, self.photons.len(), p, k, max_distance.powi(2), &mut heap);
+        }
+
+        heap.into_iter()
+            .map(|n| (n.dist_squared, &self.photons[n.index]))
+            .collect()
+    }
+
+    fn search(
+        &self,
+        start: usize,
+        end: usize,
+        p: &Point3,
+        k: usize,
+        max_dist_squared: f64,
+        heap: &mut BinaryHeap<Neighbour>,
+    ) {
+        if start >= end {
+            return;
+        }
+
+        let mid = start + (end - start) / 2;
+        let node = &self.photons[mid];
+        let axis = self.axes[mid];
+        let offset = p[axis] - node.p[axis];
+
+        let (near, far) = if offset < 0.0 {
+            ((start, mid), (mid + 1, end))
+        } else {
+            ((mid + 1, end), (start, mid))
+        };
+        self.search(near.0, near.1, p, k, max_dist_squared, heap);
+
+        let dist_squared = (node.p - *p).length_squared();
+        if dist_squared <= max_dist_squared {
+            heap.push(Neighbour {
+                dist_squared,
+                index: mid,
+            });
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        // NOTE: once `k` are found, only photons closer than the farthest of them matter
+        let reach = if heap.len() == k {
+            heap.peek().unwrap().dist_squared
+        } else {
+            max_dist_squared
+        };
+        if offset * offset < reach {
+            self.search(far.0, far.1, p, k, max_dist_squared, heap);
+        }
+    }
+}
+
+/// A photon found by `PhotonMap::nearest`, ordered by distance so the farthest is on top of
+/// the heap.
+struct Neighbour {
+    dist_squared: f64,
+    index: usize,
+}
+
+impl PartialEq for Neighbour {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Neighbour {}
+
+impl PartialOrd for Neighbour {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Neighbour {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.dist_squared.total_cmp(&other.dist_squared)
+    }
+}
@@ -0,0 +1,35 @@
+use crate::vec3::{Color, Point3, Vec3};
+
+use super::light::{Light, LightSample};
+
+/// Parallel light from infinitely far away travelling along `direction`, like the sun.
+pub struct DirectionalLight {
+    to_light: Vec3,
+    radiance: Color,
+}
+
+impl DirectionalLight {
+    pub fn new(direction: Vec3, radiance: Color) -> Self {
+        Self {
+            to_light: -direction.unit_vector(),
+            radiance,
+        }
+    }
+}
+
+impl Light for DirectionalLight {
+    fn sample_li(&self, _: &Point3) -> Option<LightSample> {
+        Some(LightSample {
+            direction: self.to_light,
+            distance: f64::INFINITY,
+            radiance: self.radiance,
+        })
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_directional_light {
+    ( $direction:expr, $radiance:expr ) => {
+        Arc::new(Box::new(DirectionalLight::new($direction, $radiance)))
+    };
+}
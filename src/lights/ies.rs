@@ -0,0 +1,283 @@
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+
+use crate::{
+    onb::Onb,
+    vec3::{dot, Vec3},
+};
+
+/// Angular intensity distribution of a real luminaire, read from an IES LM-63 photometric
+/// file. Only type C photometry is supported, which is what nearly every fixture uses:
+/// vertical angles run from the nadir (straight down, `0`) to the zenith (`180`) and
+/// horizontal angles go around the nadir axis.
+pub struct IesProfile {
+    vertical_angles: Vec<f64>,
+    horizontal_angles: Vec<f64>,
+    /// Candela for every horizontal angle, each with a value per vertical angle.
+    candela: Vec<Vec<f64>>,
+    max_candela: f64,
+}
+
+impl IesProfile {
+    /// Loads a profile from `file`, falling back to an even distribution if that fails.
+    pub fn from_file<T: AsRef<Path>>(file: T) -> Self {
+        match fs::read_to_string(&file)
+            .ok()
+            .and_then(|text| Self::parse(&text))
+        {
+            Some(profile) => profile,
+            None => {
+                eprintln!("Unable to load IES profile {}", file.as_ref().display());
+                Self::isotropic()
+            }
+        }
+    }
+
+    /// Parses the contents of an LM-63 file (1986, 1991, 1995 and 2002 revisions).
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut lines = text.lines();
+        let tilt = lines.find(|line| line.trim_start().starts_with("TILT="))?;
+
+        let rest: Vec<&str> = lines.collect();
+        let mut values = rest
+            .iter()
+            .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ','))
+            .filter(|token| !token.is_empty())
+            .map(|token| token.parse::<f64>().ok());
+        let mut next = || values.next().flatten();
+
+        // NOTE: lamp tilt only matters for lamps mounted at an angle, so it's skipped
+        if tilt.trim() == "TILT=INCLUDE" {
+            let _lamp_to_luminaire_geometry = next()?;
+            let tilt_count = next()? as usize;
+            for _ in 0..2 * tilt_count {
+                next()?;
+            }
+        }
+
+        let _lamp_count = next()?;
+        let _lumens_per_lamp = next()?;
+        let multiplier = next()?;
+        let vertical_count = next()? as usize;
+        let horizontal_count = next()? as usize;
+        let photometric_type = next()?;
+        let _units = next()?;
+        let (_width, _length, _height) = (next()?, next()?, next()?);
+        let ballast_factor = next()?;
+        let _ballast_lamp_factor = next()?;
+        let _input_watts = next()?;
+
+        if photometric_type != 1.0 || vertical_count == 0 || horizontal_count == 0 {
+            return None;
+        }
+
+        let vertical_angles: Vec<f64> =
+            (0..vertical_count).map(|_| next()).collect::<Option<_>>()?;
+        let horizontal_angles: Vec<f64> = (0..horizontal_count)
+            .map(|_| next())
+            .collect::<Option<_>>()?;
+        let candela: Vec<Vec<f64>> = (0..horizontal_count)
+            .map(|_| {
+                (0..vertical_count)
+                    .map(|_| next().map(|cd| cd * multiplier * ballast_factor))
+                    .collect::<Option<_>>()
+            })
+            .collect::<Option<_>>()?;
+
+        let max_candela = candela.iter().flatten().fold(0.0f64, |a, &b| a.max(b));
+        if max_candela <= 0.0 {
+            return None;
+        }
+
+        Some(Self {
+            vertical_angles,
+            horizontal_angles,
+            candela,
+            max_candela,
+        })
+    }
+
+    /// The same intensity in every direction.
+    pub fn isotropic() -> Self {
+        Self {
+            vertical_angles: vec![0.0, 180.0],
+            horizontal_angles: vec![0.0],
+            candela: vec![vec![1.0, 1.0]],
+            max_candela: 1.0,
+        }
+    }
+
+    pub fn max_candela(&self) -> f64 {
+        self.max_candela
+    }
+
+    /// Intensity in candela at `vertical` degrees from the nadir and `horizontal` degrees
+    /// around it, interpolated between the measured angles.
+    pub fn candela(&self, vertical: f64, horizontal: f64) -> f64 {
+        let horizontal = self.fold_horizontal(horizontal);
+
+        let (h0, h1, th) = bracket(&self.horizontal_angles, horizontal);
+        let (v0, v1, tv) = match bracket_strict(&self.vertical_angles, vertical) {
+            Some(bracket) => bracket,
+            None => return 0.0,
+        };
+
+        let lerp = |row: &Vec<f64>| row[v0] + tv * (row[v1] - row[v0]);
+        let a = lerp(&self.candela[h0]);
+        let b = lerp(&self.candela[h1]);
+        a + th * (b - a)
+    }
+
+    /// Intensity towards `direction` relative to the brightest direction, with the nadir
+    /// along `axis.w()` and horizontal angles measured from `axis.u()`.
+    pub fn relative_intensity(&self, axis: &Onb, direction: &Vec3) -> f64 {
+        let direction = direction.unit_vector();
+        let vertical = dot(&direction, &axis.w()).clamp(-1.0, 1.0).acos();
+        let horizontal = dot(&direction, &axis.v()).atan2(dot(&direction, &axis.u()));
+        let horizontal = if horizontal < 0.0 {
+            horizontal + 2.0 * PI
+        } else {
+            horizontal
+        };
+
+        self.candela(vertical.to_degrees(), horizontal.to_degrees()) / self.max_candela
+    }
+
+    /// Maps `horizontal` in `[0, 360)` onto the measured range using the symmetry the last
+    /// horizontal angle implies.
+    fn fold_horizontal(&self, horizontal: f64) -> f64 {
+        let last = *self.horizontal_angles.last().unwrap();
+        if self.horizontal_angles.len() == 1 {
+            return self.horizontal_angles[0];
+        }
+
+        if last <= 90.0 {
+            // NOTE: symmetric in each quadrant
+            let h = horizontal % 180.0;
+            if h > 90.0 {
+                180.0 - h
+            } else {
+                h
+            }
+        } else if last <= 180.0 && horizontal > 180.0 {
+            // NOTE: symmetric about the 0-180 plane
+            360.0 - horizontal
+        } else {
+            horizontal
+        }
+    }
+}
+
+/// Indices around `x` in the sorted `angles` and how far between them it is, clamping to the
+/// ends.
+fn bracket(angles: &[f64], x: f64) -> (usize, usize, f64) {
+    if angles.len() == 1 || x <= angles[0] {
+        return (0, 0, 0.0);
+    }
+
+    match angles.iter().position(|&a| a >= x) {
+        Some(i) => {
+            let t = (x - angles[i - 1]) / (angles[i] - angles[i - 1]);
+            (i - 1, i, t)
+        }
+        None => (angles.len() - 1, angles.len() - 1, 0.0),
+    }
+}
+
+/// Like `bracket`, but `None` outside the measured range since no light is given off there.
+fn bracket_strict(angles: &[f64], x: f64) -> Option<(usize, usize, f64)> {
+    let first = angles[0];
+    let last = *angles.last().unwrap();
+    if x < first - 1e-6 || x > last + 1e-6 {
+        return None;
+    }
+
+    Some(bracket(angles, x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Quadrant symmetric profile with a tilt block, commas between values, a multiplier of 2
+    /// and a ballast factor of 0.5.
+    const QUADRANT: &str = "IESNA:LM-63-2002
+[TEST] quadrant
+TILT=INCLUDE
+1
+3
+0, 45, 90
+1.0, 0.9, 0.8
+1 1000 2.0 3 2 1 2 0 0 0
+0.5 1.0 100
+0 45 90
+0 90
+100, 80, 10
+60, 40, 5
+";
+
+    /// Profile symmetric about the 0-180 plane.
+    const HALF: &str = "IESNA:LM-63-1995
+TILT=NONE
+1 1000 1.0 2 3 1 2 0 0 0
+1.0 1.0 100
+0 90
+0 90 180
+10 0
+20 0
+30 0
+";
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{a} != {b}");
+    }
+
+    #[test]
+    fn skips_tilt_and_scales_candela() {
+        let profile = IesProfile::parse(QUADRANT).unwrap();
+        assert_eq!(profile.vertical_angles, vec![0.0, 45.0, 90.0]);
+        assert_eq!(profile.horizontal_angles, vec![0.0, 90.0]);
+        assert_close(profile.max_candela(), 100.0);
+        assert_close(profile.candela(0.0, 0.0), 100.0);
+        assert_close(profile.candela(45.0, 0.0), 80.0);
+        assert_close(profile.candela(22.5, 90.0), 50.0);
+    }
+
+    #[test]
+    fn nothing_outside_the_vertical_range() {
+        let profile = IesProfile::parse(QUADRANT).unwrap();
+        assert_close(profile.candela(120.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn folds_quadrant_symmetry() {
+        let profile = IesProfile::parse(QUADRANT).unwrap();
+        assert_close(profile.fold_horizontal(135.0), 45.0);
+        assert_close(profile.fold_horizontal(180.0), 0.0);
+        assert_close(profile.fold_horizontal(270.0), 90.0);
+        assert_close(profile.candela(0.0, 135.0), 80.0);
+        assert_close(profile.candela(0.0, 270.0), 60.0);
+    }
+
+    #[test]
+    fn folds_half_symmetry() {
+        let profile = IesProfile::parse(HALF).unwrap();
+        assert_close(profile.fold_horizontal(135.0), 135.0);
+        assert_close(profile.fold_horizontal(270.0), 90.0);
+        assert_close(profile.candela(0.0, 270.0), 20.0);
+        assert_close(profile.candela(0.0, 225.0), 25.0);
+    }
+
+    #[test]
+    fn rejects_other_photometry() {
+        let type_b = HALF.replace("1 1000 1.0 2 3 1 2", "1 1000 1.0 2 3 2 2");
+        assert!(IesProfile::parse(&type_b).is_none());
+    }
+
+    #[test]
+    fn rejects_truncated_files() {
+        assert!(IesProfile::parse("IESNA:LM-63-2002\n[TEST] no tilt\n").is_none());
+        assert!(IesProfile::parse(&HALF[..HALF.len() - 10]).is_none());
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use crate::{
+    hittables::hittable::Hittable,
+    vec3::{Color, Point3, Vec3},
+};
+
+/// Light arriving at a point straight from a light source.
+pub struct LightSample {
+    /// Unit direction from the point towards the light.
+    pub direction: Vec3,
+    /// Distance to the light, infinite for directional lights.
+    pub distance: f64,
+    pub radiance: Color,
+}
+
+/// Light leaving a source at a single point.
+pub struct EmissionSample {
+    pub origin: Point3,
+    /// Unit direction the light leaves in.
+    pub direction: Vec3,
+    /// Radiant intensity towards `direction`.
+    pub intensity: Color,
+    /// Density per steradian of picking `direction`.
+    pub pdf: f64,
+}
+
+/// A light source that isn't part of the geometry and can only be reached with shadow rays,
+/// like point, spot and directional lights.
+pub trait Light: Send + Sync {
+    /// Light reaching `p` from this source if it isn't occluded, `None` if `p` is out of its
+    /// reach.
+    fn sample_li(&self, p: &Point3) -> Option<LightSample>;
+
+    /// Samples light leaving the source, for integrators that also trace paths from the
+    /// lights. These paths always fall off with the inverse square of distance, so `None` for
+    /// lights that can't start paths or fall off some other way.
+    fn sample_le(&self) -> Option<EmissionSample> {
+        None
+    }
+
+    /// Density per steradian with which `sample_le` leaves along the unit `direction`.
+    fn pdf_le(&self, _: &Vec3) -> f64 {
+        0.0
+    }
+}
+
+/// How the intensity of a positional light drops with distance.
+#[derive(Clone, Copy, Debug)]
+pub enum Falloff {
+    Constant,
+    Linear,
+    InverseSquare,
+}
+
+impl Falloff {
+    pub fn attenuation(&self, distance: f64) -> f64 {
+        match self {
+            Falloff::Constant => 1.0,
+            Falloff::Linear => 1.0 / distance,
+            Falloff::InverseSquare => 1.0 / (distance * distance),
+        }
+    }
+}
+
+pub struct LightList {
+    pub lights: Vec<Arc<Box<dyn Light>>>,
+    /// Objects in the world with emissive materials. The path tracer finds these by chance,
+    /// integrators that start paths at the lights need them listed here too.
+    pub emitters: Vec<Arc<Box<dyn Hittable>>>,
+}
+
+impl LightList {
+    pub fn new() -> Self {
+        Self {
+            lights: Vec::new(),
+            emitters: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, light: Arc<Box<dyn Light>>) {
+        self.lights.push(light);
+    }
+
+    pub fn add_emitter(&mut self, emitter: Arc<Box<dyn Hittable>>) {
+        self.emitters.push(emitter);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lights.is_empty() && self.emitters.is_empty()
+    }
+}
@@ -0,0 +1,116 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use crate::{
+    onb::Onb,
+    vec3::{random_unit_vector, Color, Point3, Vec3},
+};
+
+use super::{
+    ies::IesProfile,
+    light::{EmissionSample, Falloff, Light, LightSample},
+};
+
+/// Emits `intensity` equally in every direction from a single point, or shaped by an IES
+/// profile whose nadir points along `axis.w()`.
+pub struct PointLight {
+    position: Point3,
+    intensity: Color,
+    falloff: Falloff,
+    profile: Option<Arc<IesProfile>>,
+    axis: Onb,
+}
+
+impl PointLight {
+    pub fn new(position: Point3, intensity: Color) -> Self {
+        Self::with_falloff(position, intensity, Falloff::InverseSquare)
+    }
+
+    pub fn with_falloff(position: Point3, intensity: Color, falloff: Falloff) -> Self {
+        Self {
+            position,
+            intensity,
+            falloff,
+            profile: None,
+            axis: Onb::build_from_w(&Vec3::with_values(0.0, -1.0, 0.0)),
+        }
+    }
+
+    /// Point light shaped like a real fixture, with `intensity` in its brightest direction.
+    pub fn with_profile(
+        position: Point3,
+        intensity: Color,
+        profile: &Arc<IesProfile>,
+        down: Vec3,
+    ) -> Self {
+        Self {
+            position,
+            intensity,
+            falloff: Falloff::InverseSquare,
+            profile: Some(Arc::clone(profile)),
+            axis: Onb::build_from_w(&down),
+        }
+    }
+
+    /// Intensity towards the unit `direction` leaving the light.
+    fn intensity_towards(&self, direction: &Vec3) -> Color {
+        match &self.profile {
+            Some(profile) => profile.relative_intensity(&self.axis, direction) * self.intensity,
+            None => self.intensity,
+        }
+    }
+}
+
+impl Light for PointLight {
+    fn sample_li(&self, p: &Point3) -> Option<LightSample> {
+        let to_light = self.position - *p;
+        let distance = to_light.length();
+        let direction = to_light / distance;
+
+        let intensity = self.intensity_towards(&-direction);
+        if intensity.near_zero() {
+            return None;
+        }
+
+        Some(LightSample {
+            direction,
+            distance,
+            radiance: intensity * self.falloff.attenuation(distance),
+        })
+    }
+
+    fn sample_le(&self) -> Option<EmissionSample> {
+        if !matches!(self.falloff, Falloff::InverseSquare) {
+            return None;
+        }
+
+        let direction = random_unit_vector();
+        Some(EmissionSample {
+            origin: self.position,
+            direction,
+            intensity: self.intensity_towards(&direction),
+            pdf: self.pdf_le(&direction),
+        })
+    }
+
+    fn pdf_le(&self, _: &Vec3) -> f64 {
+        1.0 / (4.0 * PI)
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_point_light {
+    ( $position:expr, $intensity:expr, $profile:expr, $down:expr, IesProfile ) => {
+        Arc::new(Box::new(PointLight::with_profile(
+            $position, $intensity, $profile, $down,
+        )))
+    };
+    ( $position:expr, $intensity:expr, $falloff:expr ) => {
+        Arc::new(Box::new(PointLight::with_falloff(
+            $position, $intensity, $falloff,
+        )))
+    };
+    ( $position:expr, $intensity:expr ) => {
+        Arc::new(Box::new(PointLight::new($position, $intensity)))
+    };
+}
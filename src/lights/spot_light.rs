@@ -0,0 +1,176 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::{
+    onb::Onb,
+    vec3::{dot, Color, Point3, Vec3},
+};
+
+use super::{
+    ies::IesProfile,
+    light::{EmissionSample, Falloff, Light, LightSample},
+};
+
+/// A point light restricted to a cone. `cone_angle` is the half angle of the cone in degrees,
+/// and the last `penumbra` degrees of it fade out smoothly. An IES profile can shape the
+/// light within the cone, its nadir points along the spot's direction.
+pub struct SpotLight {
+    position: Point3,
+    direction: Vec3,
+    intensity: Color,
+    cos_inner: f64,
+    cos_outer: f64,
+    falloff: Falloff,
+    profile: Option<Arc<IesProfile>>,
+    axis: Onb,
+}
+
+impl SpotLight {
+    pub fn new(
+        position: Point3,
+        look_at: Point3,
+        intensity: Color,
+        cone_angle: f64,
+        penumbra: f64,
+    ) -> Self {
+        Self::with_falloff(
+            position,
+            look_at,
+            intensity,
+            cone_angle,
+            penumbra,
+            Falloff::InverseSquare,
+        )
+    }
+
+    pub fn with_falloff(
+        position: Point3,
+        look_at: Point3,
+        intensity: Color,
+        cone_angle: f64,
+        penumbra: f64,
+        falloff: Falloff,
+    ) -> Self {
+        let inner_angle = (cone_angle - penumbra).max(0.0);
+        let direction = (look_at - position).unit_vector();
+        Self {
+            position,
+            direction,
+            intensity,
+            cos_inner: inner_angle.to_radians().cos(),
+            cos_outer: cone_angle.to_radians().cos(),
+            falloff,
+            profile: None,
+            axis: Onb::build_from_w(&direction),
+        }
+    }
+
+    pub fn with_profile(
+        position: Point3,
+        look_at: Point3,
+        intensity: Color,
+        cone_angle: f64,
+        penumbra: f64,
+        profile: &Arc<IesProfile>,
+    ) -> Self {
+        Self {
+            profile: Some(Arc::clone(profile)),
+            ..Self::new(position, look_at, intensity, cone_angle, penumbra)
+        }
+    }
+
+    fn cone_falloff(&self, cos_theta: f64) -> f64 {
+        if cos_theta >= self.cos_inner {
+            return 1.0;
+        }
+        if cos_theta <= self.cos_outer {
+            return 0.0;
+        }
+
+        let t = (cos_theta - self.cos_outer) / (self.cos_inner - self.cos_outer);
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    /// Intensity towards the unit `direction` leaving the light.
+    fn intensity_towards(&self, direction: &Vec3) -> Color {
+        let mut cone = self.cone_falloff(dot(direction, &self.direction));
+        if let Some(profile) = &self.profile {
+            cone *= profile.relative_intensity(&self.axis, direction);
+        }
+        cone * self.intensity
+    }
+}
+
+impl Light for SpotLight {
+    fn sample_li(&self, p: &Point3) -> Option<LightSample> {
+        let to_light = self.position - *p;
+        let distance = to_light.length();
+        let direction = to_light / distance;
+
+        let intensity = self.intensity_towards(&-direction);
+        if intensity.near_zero() {
+            return None;
+        }
+
+        Some(LightSample {
+            direction,
+            distance,
+            radiance: intensity * self.falloff.attenuation(distance),
+        })
+    }
+
+    // NOTE: uniform over the cone, the penumbra and profile only scale the intensity
+    fn sample_le(&self) -> Option<EmissionSample> {
+        if !matches!(self.falloff, Falloff::InverseSquare) {
+            return None;
+        }
+
+        let mut rng = rand::thread_rng();
+        let cos_theta = 1.0 - rng.gen::<f64>() * (1.0 - self.cos_outer);
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * rng.gen::<f64>();
+        let direction = self
+            .axis
+            .local(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+
+        Some(EmissionSample {
+            origin: self.position,
+            direction,
+            intensity: self.intensity_towards(&direction),
+            pdf: self.pdf_le(&direction),
+        })
+    }
+
+    fn pdf_le(&self, direction: &Vec3) -> f64 {
+        if dot(direction, &self.direction) < self.cos_outer {
+            return 0.0;
+        }
+
+        1.0 / (2.0 * PI * (1.0 - self.cos_outer))
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_spot_light {
+    ( $position:expr, $look_at:expr, $intensity:expr, $cone_angle:expr, $penumbra:expr, $profile:expr ) => {
+        Arc::new(Box::new(SpotLight::with_profile(
+            $position,
+            $look_at,
+            $intensity,
+            $cone_angle,
+            $penumbra,
+            $profile,
+        )))
+    };
+    ( $position:expr, $look_at:expr, $intensity:expr, $cone_angle:expr, $penumbra:expr ) => {
+        Arc::new(Box::new(SpotLight::new(
+            $position,
+            $look_at,
+            $intensity,
+            $cone_angle,
+            $penumbra,
+        )))
+    };
+}
@@ -1,28 +1,48 @@
 #![allow(dead_code, unused)]
 mod scene;
+mod scene_graph;
 mod vec3;
 
 mod hittables;
+mod integrators;
+mod lights;
 mod materials;
 mod textures;
 
 mod aabb;
 mod camera;
+mod medium;
+mod onb;
 mod ray;
+mod spectrum;
+mod transform;
 
 use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::sync::{Arc, Mutex};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use indicatif::{ProgressBar, ProgressStyle};
 use rand::prelude::*;
 use rayon::prelude::*;
 
-use hittables::hittable::{HitRecord, Hittable, HittableList};
-use ray::Ray;
+use integrators::{
+    bdpt::Bdpt,
+    path::{ray_color, spectral_ray_color},
+    photon::PhotonMapper,
+};
 use scene::Scene;
-use vec3::{Color, Vec3};
+use vec3::Color;
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Integrator {
+    // Unidirectional path tracing
+    Path,
+    // Bidirectional path tracing
+    Bdpt,
+    // Path tracing with caustics from a photon map
+    Photon,
+}
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -34,14 +54,50 @@ struct Args {
     // Name of the file to output
     #[arg(short, long, value_name = "FILE", default_value_t = String::from("image"))]
     filename: String,
+
+    // Trace a wavelength per sample instead of RGB, needed for dispersion
+    #[arg(long, default_value_t = false)]
+    spectral: bool,
+
+    // Light transport algorithm
+    #[arg(long, value_enum, default_value_t = Integrator::Path)]
+    integrator: Integrator,
+
+    // Photons shot by the photon integrator
+    #[arg(long, value_name = "NUM", default_value_t = 1_000_000)]
+    photons: usize,
 }
 
 fn main() {
     let args = Args::parse();
 
-    let (world, camera, background, settings) = Scene::world_select(args.scene_number);
+    let (world, lights, camera, background, settings) = Scene::world_select(args.scene_number);
     let world = Arc::new(world);
 
+    if args.spectral && args.integrator != Integrator::Path {
+        eprintln!("--spectral is only supported by the path integrator, rendering RGB");
+    }
+    let bdpt = (args.integrator == Integrator::Bdpt).then(|| {
+        Bdpt::new(
+            &world,
+            &lights,
+            &camera,
+            background,
+            settings.max_depth,
+            settings.image_width,
+            settings.image_height,
+        )
+    });
+    let photon_mapper = (args.integrator == Integrator::Photon).then(|| {
+        PhotonMapper::new(
+            &world,
+            &lights,
+            background,
+            settings.max_depth,
+            args.photons,
+        )
+    });
+
     let filename = format!("{}.ppm", args.filename);
     let file = File::create(filename).expect("Unable to create file");
     let mut file = BufWriter::new(file);
@@ -62,45 +118,62 @@ fn main() {
     progress_bar.set_message("WORK");
     let progress_bar = Mutex::new(progress_bar);
 
-    let img: Arc<Mutex<Vec<Vec<[u8; 12]>>>> =
-        Arc::new(Mutex::new(vec![
-            vec![
-                [48u8; 12];
-                settings.image_width as usize
-            ];
-            settings.image_height as usize
-        ]));
+    // NOTE: sums of the samples, bidirectional paths can add light to any pixel
+    let row = vec![Color::new(); settings.image_width as usize];
+    let img: Arc<Mutex<Vec<Vec<Color>>>> =
+        Arc::new(Mutex::new(vec![row; settings.image_height as usize]));
 
     (0..settings.image_height).into_par_iter().for_each(|j| {
         let buf_j = (settings.image_height - 1 - j) as usize;
 
         (0..settings.image_width).into_par_iter().for_each(|i| {
-            let pixel_color = (0..settings.samples_per_pixel)
+            let (pixel_color, splats) = (0..settings.samples_per_pixel)
                 .into_par_iter()
                 .map(|_| {
                     let mut rng = rand::thread_rng();
                     let u = (i as f64 + rng.gen::<f64>()) / (settings.image_width - 1) as f64;
                     let v = (j as f64 + rng.gen::<f64>()) / (settings.image_height - 1) as f64;
+                    if let Some(bdpt) = &bdpt {
+                        return bdpt.sample(u, v);
+                    }
+
                     let ray = camera.get_ray(u, v);
-                    ray_color(ray, &background, &world, settings.max_depth)
+                    let color = if let Some(photon_mapper) = &photon_mapper {
+                        photon_mapper.ray_color(ray)
+                    } else if args.spectral {
+                        spectral_ray_color(ray, &background, &world, &lights, settings.max_depth)
+                    } else {
+                        ray_color(ray, &background, &world, &lights, settings.max_depth)
+                    };
+                    (color, Vec::new())
                 })
-                .sum();
+                .reduce(
+                    || (Color::new(), Vec::new()),
+                    |(a, mut splats), (b, more)| {
+                        splats.extend(more);
+                        (a + b, splats)
+                    },
+                );
 
             let mut img = img.lock().unwrap();
-            write_to_buf(
-                &mut img,
-                buf_j,
-                i as usize,
-                pixel_color,
-                settings.samples_per_pixel,
-            );
+            img[buf_j][i as usize] += pixel_color;
+            for splat in splats {
+                let splat_j = settings.image_height as usize - 1 - splat.j;
+                img[splat_j][splat.i] += splat.color;
+            }
         });
 
         progress_bar.lock().unwrap().inc(1);
     });
 
-    let img = img.lock().unwrap().concat().concat();
-    write_to_file(&mut file, &img);
+    let img = img.lock().unwrap();
+    let mut buf = vec![[48u8; 12]; settings.image_width as usize];
+    for row in img.iter() {
+        for (i, pixel_color) in row.iter().enumerate() {
+            write_to_buf(&mut buf, i, *pixel_color, settings.samples_per_pixel);
+        }
+        write_to_file(&mut file, &buf.concat());
+    }
 
     if let Err(e) = file.flush() {
         eprintln!("Write Failed because: {}", e);
@@ -110,13 +183,7 @@ fn main() {
     progress_bar.lock().unwrap().finish_with_message("DONE");
 }
 
-fn write_to_buf(
-    buf: &mut [Vec<[u8; 12]>],
-    j: usize,
-    i: usize,
-    pixel_color: Color,
-    samples_per_pixel: u64,
-) {
+fn write_to_buf(buf: &mut [[u8; 12]], i: usize, pixel_color: Color, samples_per_pixel: u64) {
     let mut r = pixel_color.x();
     let mut g = pixel_color.y();
     let mut b = pixel_color.z();
@@ -133,7 +200,7 @@ fn write_to_buf(
         (256.0 * b.clamp(0.0, 0.999)) as u64
     );
 
-    buf[j][i].copy_from_slice(point.as_bytes());
+    buf[i].copy_from_slice(point.as_bytes());
 }
 
 fn write_to_file(file: &mut BufWriter<File>, data_as_bytes: &[u8]) {
@@ -142,40 +209,3 @@ fn write_to_file(file: &mut BufWriter<File>, data_as_bytes: &[u8]) {
         std::process::exit(1);
     }
 }
-
-fn ray_color(mut ray: Ray, background: &Color, world: &HittableList, depth: u64) -> Color {
-    let mut emitted_attenuation: Vec<(Color, Color)> = Vec::with_capacity(depth as usize);
-
-    let mut final_ray_color: Color = Color::with_value(0.0);
-    for _ in (0..depth).rev() {
-        let mut rec = HitRecord::default();
-
-        if !world.hit(&ray, 0.001, f64::INFINITY, &mut rec) {
-            final_ray_color = *background;
-            break;
-        }
-
-        let mut scattered: Ray = Ray::new(Vec3::new(), Vec3::new(), 0.0);
-        let mut attenutation: Color = Color::new();
-        let emitted: Color = rec.mat_ptr.emitted(rec.u, rec.v, &rec.p);
-
-        if !rec
-            .mat_ptr
-            .scatter(&ray, &rec, &mut attenutation, &mut scattered)
-        {
-            final_ray_color = emitted;
-            break;
-        }
-
-        emitted_attenuation.push((emitted, attenutation));
-        ray = scattered;
-    }
-
-    emitted_attenuation
-        .iter()
-        .rev()
-        .fold(final_ray_color, |mut acc, &(emitted, attenutation)| {
-            acc = emitted + attenutation * acc;
-            acc
-        })
-}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use crate::{
+    hittables::hittable::HitRecord,
+    medium::Medium,
+    ray::Ray,
+    textures::texture::Texture,
+    vec3::{Color, Point3, Vec3},
+};
+
+use super::material::Material;
+
+/// Wraps a material with an opacity texture, the mean of the mask's channels is the alpha.
+/// Rays that land on a transparent texel carry on as if the surface wasn't there.
+pub struct AlphaMask {
+    base: Arc<Box<dyn Material>>,
+    mask: Arc<Box<dyn Texture>>,
+}
+
+impl AlphaMask {
+    pub fn new(base: &Arc<Box<dyn Material>>, mask: &Arc<Box<dyn Texture>>) -> Self {
+        Self {
+            base: Arc::clone(base),
+            mask: Arc::clone(mask),
+        }
+    }
+}
+
+impl Material for AlphaMask {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        self.base.scatter(r_in, rec, attenuation, scattered)
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, wi: &Vec3) -> Color {
+        self.base.eval(r_in, rec, wi)
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, wi: &Vec3) -> Option<f64> {
+        self.base.scattering_pdf(r_in, rec, wi)
+    }
+
+    fn is_phase_function(&self) -> bool {
+        self.base.is_phase_function()
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        self.base.emitted(r_in, rec)
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        let opacity = self.mask.value(u, v, p);
+        self.base.alpha(u, v, p) * (opacity.x() + opacity.y() + opacity.z()) / 3.0
+    }
+
+    fn interior_medium(&self) -> Option<Arc<Box<dyn Medium>>> {
+        self.base.interior_medium()
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_alpha_mask {
+    ( $base:expr, $mask:expr ) => {
+        Arc::new(Box::new(AlphaMask::new($base, $mask)))
+    };
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use crate::{
+    hittables::hittable::HitRecord,
+    medium::Medium,
+    ray::Ray,
+    textures::texture::Texture,
+    vec3::{cross, dot, Color, Point3, Vec3},
+};
+
+use super::material::Material;
+
+/// Wraps a material and bends its shading normal as if the surface were displaced along
+/// its normal by `scale` times the height texture. The height is the mean of the texture's
+/// channels, so grayscale images and `NoiseTexture` both work.
+pub struct BumpMap {
+    base: Arc<Box<dyn Material>>,
+    height: Arc<Box<dyn Texture>>,
+    scale: f64,
+}
+
+impl BumpMap {
+    // NOTE: world space distance used for the finite differences
+    const DELTA: f64 = 0.001;
+
+    pub fn new(base: &Arc<Box<dyn Material>>, height: &Arc<Box<dyn Texture>>, scale: f64) -> Self {
+        Self {
+            base: Arc::clone(base),
+            height: Arc::clone(height),
+            scale,
+        }
+    }
+
+    fn height_at(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        let h = self.height.value(u, v, p);
+        self.scale * (h.x() + h.y() + h.z()) / 3.0
+    }
+
+    fn perturbed_normal(&self, rec: &HitRecord) -> Vec3 {
+        let dpdu_len = rec.dpdu.length();
+        let dpdv_len = rec.dpdv.length();
+        if dpdu_len == 0.0 || dpdv_len == 0.0 {
+            return rec.normal;
+        }
+
+        let du = Self::DELTA / dpdu_len;
+        let dv = Self::DELTA / dpdv_len;
+
+        let displace = self.height_at(rec.u, rec.v, &rec.p);
+        let u_displace = self.height_at(rec.u + du, rec.v, &(rec.p + du * rec.dpdu));
+        let v_displace = self.height_at(rec.u, rec.v + dv, &(rec.p + dv * rec.dpdv));
+
+        let dpdu = rec.dpdu + (u_displace - displace) / du * rec.normal;
+        let dpdv = rec.dpdv + (v_displace - displace) / dv * rec.normal;
+
+        let perturbed = cross(&dpdu, &dpdv);
+        if perturbed.near_zero() {
+            return rec.normal;
+        }
+
+        let perturbed = perturbed.unit_vector();
+        if dot(&perturbed, &rec.normal) < 0.0 {
+            -perturbed
+        } else {
+            perturbed
+        }
+    }
+}
+
+impl Material for BumpMap {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        let mut shading_rec = rec.clone();
+        shading_rec.normal = self.perturbed_normal(rec);
+        self.base
+            .scatter(r_in, &shading_rec, attenuation, scattered)
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, wi: &Vec3) -> Color {
+        let mut shading_rec = rec.clone();
+        shading_rec.normal = self.perturbed_normal(rec);
+        self.base.eval(r_in, &shading_rec, wi)
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, wi: &Vec3) -> Option<f64> {
+        let mut shading_rec = rec.clone();
+        shading_rec.normal = self.perturbed_normal(rec);
+        self.base.scattering_pdf(r_in, &shading_rec, wi)
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        self.base.emitted(r_in, rec)
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        self.base.alpha(u, v, p)
+    }
+
+    fn interior_medium(&self) -> Option<Arc<Box<dyn Medium>>> {
+        self.base.interior_medium()
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_bump_map {
+    ( $base:expr, $height:expr, $scale:expr ) => {
+        Arc::new(Box::new(BumpMap::new($base, $height, $scale)))
+    };
+}
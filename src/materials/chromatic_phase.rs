@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::{
+    hittables::hittable::HitRecord,
+    ray::Ray,
+    textures::texture::{SolidColor, Texture},
+    vec3::{dot, Color, Vec3},
+};
+
+use super::{
+    henyey_greenstein::{henyey_greenstein, sample_henyey_greenstein},
+    material::Material,
+};
+
+/// Phase function for media with separate per channel absorption and scattering
+/// coefficients, which can also glow. Collisions are sampled with the largest extinction
+/// coefficient (the majorant), so the medium using this must have a density of `majorant()`.
+/// Every collision either scatters, carries on as a null collision, or is absorbed, and the
+/// attenuation corrects for the channels that were sampled too often.
+pub struct ChromaticPhase {
+    sigma_a: Color,
+    sigma_s: Color,
+    sigma_t: Color,
+    majorant: f64,
+    emission: Arc<Box<dyn Texture>>,
+    g: f64,
+}
+
+impl ChromaticPhase {
+    pub fn new(sigma_a: Color, sigma_s: Color, emission: &Arc<Box<dyn Texture>>, g: f64) -> Self {
+        let sigma_t = sigma_s + sigma_a;
+        let majorant = sigma_t.x().max(sigma_t.y()).max(sigma_t.z());
+        // NOTE: collisions are weighted by the majorant, a medium with none can't be sampled
+        assert!(
+            majorant > 0.0 && majorant.is_finite(),
+            "ChromaticPhase needs a positive, finite extinction coefficient"
+        );
+        Self {
+            sigma_a,
+            sigma_s,
+            sigma_t,
+            majorant,
+            emission: Arc::clone(emission),
+            g,
+        }
+    }
+
+    pub fn with_color(sigma_a: Color, sigma_s: Color, emission: Color) -> Self {
+        let emission: Arc<Box<dyn Texture>> = Arc::new(Box::new(SolidColor::new(emission)));
+        Self::new(sigma_a, sigma_s, &emission, 0.0)
+    }
+
+    pub fn majorant(&self) -> f64 {
+        self.majorant
+    }
+}
+
+impl Material for ChromaticPhase {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        let scatter_weight = self.sigma_s / self.majorant;
+        let null_weight = (self.majorant - self.sigma_t) / self.majorant;
+
+        let p_scatter = (scatter_weight.x() + scatter_weight.y() + scatter_weight.z()) / 3.0;
+        let p_null = (null_weight.x() + null_weight.y() + null_weight.z()) / 3.0;
+
+        let mut rng = rand::thread_rng();
+        let xi = rng.gen::<f64>();
+        if xi < p_scatter {
+            let direction = sample_henyey_greenstein(&r_in.direction(), self.g);
+            *scattered = Ray::new(rec.p, direction, r_in.time());
+            *attenuation = scatter_weight / p_scatter;
+            true
+        } else if xi < p_scatter + p_null {
+            *scattered = Ray::new(rec.p, r_in.direction(), r_in.time());
+            *attenuation = null_weight / p_null;
+            true
+        } else {
+            false
+        }
+    }
+
+    // NOTE: every collision, null ones included, scores the scattered share of the light
+    fn eval(&self, r_in: &Ray, _: &HitRecord, wi: &Vec3) -> Color {
+        let cos_theta = dot(&r_in.direction().unit_vector(), wi);
+        self.sigma_s / self.majorant * henyey_greenstein(cos_theta, self.g)
+    }
+
+    fn is_phase_function(&self) -> bool {
+        true
+    }
+
+    // NOTE: collision estimate of the emission absorbed-weighted over the sampled segment
+    fn emitted(&self, _: &Ray, rec: &HitRecord) -> Color {
+        self.sigma_a * self.emission.value_at(rec) / self.majorant
+    }
+}
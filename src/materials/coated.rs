@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::{
+    hittables::hittable::HitRecord,
+    medium::Medium,
+    ray::Ray,
+    vec3::{dot, reflect, Color, Point3, Vec3},
+};
+
+use super::{dielectric::Dielectric, material::Material};
+
+/// A clear dielectric layer over any base material, like varnish or car paint. Rays reflect
+/// off the coat with its Fresnel reflectance and otherwise reach the base.
+pub struct Coated {
+    base: Arc<Box<dyn Material>>,
+    ir: f64,
+}
+
+impl Coated {
+    pub fn new(base: &Arc<Box<dyn Material>>, index_of_refraction: f64) -> Self {
+        Self {
+            base: Arc::clone(base),
+            ir: index_of_refraction,
+        }
+    }
+}
+
+impl Material for Coated {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        // NOTE: the coat is only seen from outside, rays inside the object go to the base
+        if !rec.front_face {
+            return self.base.scatter(r_in, rec, attenuation, scattered);
+        }
+
+        let unit_direction = r_in.direction().unit_vector();
+        let cos_theta = 1f64.min(dot(&(-unit_direction), &rec.normal));
+
+        let mut rng = rand::thread_rng();
+        if Dielectric::reflectance(&cos_theta, &(1.0 / self.ir)) > rng.gen::<f64>() {
+            *attenuation = Color::with_value(1.0);
+            *scattered = Ray::new(rec.p, reflect(&unit_direction, &rec.normal), r_in.time());
+            return true;
+        }
+
+        self.base.scatter(r_in, rec, attenuation, scattered)
+    }
+
+    // NOTE: only the light that gets past the coat towards the viewer reaches the base
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, wi: &Vec3) -> Color {
+        if !rec.front_face {
+            return self.base.eval(r_in, rec, wi);
+        }
+
+        let cos_theta = 1f64.min(dot(&(-r_in.direction().unit_vector()), &rec.normal));
+        let transmittance = 1.0 - Dielectric::reflectance(&cos_theta, &(1.0 / self.ir));
+        transmittance * self.base.eval(r_in, rec, wi)
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        self.base.emitted(r_in, rec)
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        self.base.alpha(u, v, p)
+    }
+
+    fn interior_medium(&self) -> Option<Arc<Box<dyn Medium>>> {
+        self.base.interior_medium()
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_coated {
+    ( $base:expr, $ior:expr ) => {
+        Arc::new(Box::new(Coated::new($base, $ior)))
+    };
+}
@@ -1,26 +1,102 @@
+use std::sync::Arc;
+
 use rand::Rng;
 
 use crate::{
     hittables::hittable::HitRecord,
+    medium::Medium,
     ray::Ray,
     vec3::{dot, reflect, refract, Color},
 };
 
-use super::material::Material;
+use super::{
+    material::Material,
+    thin_film::{Substrate, ThinFilm},
+};
+
+/// How the index of refraction varies with wavelength, which spreads white light into its
+/// colours in spectral mode. Wavelengths are in micrometres.
+#[derive(Default, Clone, Copy)]
+pub enum Dispersion {
+    #[default]
+    None,
+    /// `n = a + b / lambda^2`
+    Cauchy { a: f64, b: f64 },
+    /// `n^2 = 1 + sum(b_i lambda^2 / (lambda^2 - c_i))`
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl Dispersion {
+    fn ior(&self, lambda_um: f64) -> Option<f64> {
+        let l2 = lambda_um * lambda_um;
+        match *self {
+            Dispersion::None => None,
+            Dispersion::Cauchy { a, b } => Some(a + b / l2),
+            Dispersion::Sellmeier { b, c } => {
+                Some((1.0 + (0..3).map(|i| b[i] * l2 / (l2 - c[i])).sum::<f64>()).sqrt())
+            }
+        }
+    }
+}
 
 #[derive(Default)]
 pub struct Dielectric {
     pub ir: f64,
+    medium: Option<Arc<Box<dyn Medium>>>,
+    dispersion: Dispersion,
+    film: Option<ThinFilm>,
 }
 
 impl Dielectric {
+    const D_LINE: f64 = 0.5876;
+
     pub fn new(index_of_refraction: f64) -> Self {
         Self {
             ir: index_of_refraction,
+            medium: None,
+            dispersion: Dispersion::None,
+            film: None,
         }
     }
 
-    fn reflectance(cosine: &f64, ref_idx: &f64) -> f64 {
+    /// Dispersive dielectric, `ir` is taken at the sodium d-line (587.6nm) for RGB renders.
+    pub fn with_dispersion(dispersion: Dispersion) -> Self {
+        Self {
+            ir: dispersion.ior(Self::D_LINE).unwrap_or(1.0),
+            medium: None,
+            dispersion,
+            film: None,
+        }
+    }
+
+    /// Dielectric whose inside is filled with `medium`, like murky water or smoky glass.
+    pub fn with_medium(index_of_refraction: f64, medium: &Arc<Box<dyn Medium>>) -> Self {
+        Self {
+            ir: index_of_refraction,
+            medium: Some(Arc::clone(medium)),
+            dispersion: Dispersion::None,
+            film: None,
+        }
+    }
+
+    /// Dielectric with a thin film on its outside, with `ir` of `1.0` it's a soap bubble.
+    pub fn with_thin_film(index_of_refraction: f64, film: ThinFilm) -> Self {
+        Self {
+            ir: index_of_refraction,
+            medium: None,
+            dispersion: Dispersion::None,
+            film: Some(film),
+        }
+    }
+
+    /// Index of refraction for a ray carrying `lambda` nanometres in spectral mode.
+    pub fn ior(&self, lambda: Option<f64>) -> f64 {
+        lambda
+            .and_then(|lambda| self.dispersion.ior(lambda / 1000.0))
+            .unwrap_or(self.ir)
+    }
+
+    pub fn reflectance(cosine: &f64, ref_idx: &f64) -> f64 {
         let mut r0 = (1.0 - ref_idx) / (1.0 + ref_idx);
         r0 *= r0;
         r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
@@ -36,11 +112,8 @@ impl Material for Dielectric {
         scattered: &mut Ray,
     ) -> bool {
         *attenuation = Color::with_values(1.0, 1.0, 1.0);
-        let refraction_ratio = if rec.front_face {
-            1.0 / self.ir
-        } else {
-            self.ir
-        };
+        let ir = self.ior(r_in.wavelength());
+        let refraction_ratio = if rec.front_face { 1.0 / ir } else { ir };
 
         let unit_direction = r_in.direction().unit_vector();
         let cos_theta = 1f64.min(dot(&(-unit_direction), &rec.normal));
@@ -48,6 +121,35 @@ impl Material for Dielectric {
 
         let cannot_refract = refraction_ratio * sin_theta > 1.0;
         let mut rng = rand::thread_rng();
+
+        // NOTE: a film colours the reflected and transmitted light, choose between them by the
+        // average reflectance and reweight so each channel keeps its own share
+        if let Some(film) = self.film.as_ref().filter(|_| rec.front_face) {
+            let substrate = Substrate::Dielectric(ir);
+            let reflectance = film.reflectance(
+                rec.u,
+                rec.v,
+                &rec.p,
+                cos_theta,
+                substrate,
+                r_in.wavelength(),
+            );
+            let p_reflect = (reflectance.x() + reflectance.y() + reflectance.z()) / 3.0;
+
+            let direction = if cannot_refract || p_reflect > rng.gen::<f64>() {
+                if !cannot_refract {
+                    *attenuation = reflectance / p_reflect;
+                }
+                reflect(&unit_direction, &rec.normal)
+            } else {
+                *attenuation = (1.0 - reflectance) / (1.0 - p_reflect);
+                refract(&unit_direction, &rec.normal, refraction_ratio)
+            };
+
+            *scattered = Ray::new(rec.p, direction, r_in.time());
+            return true;
+        }
+
         let direction = if cannot_refract
             || Dielectric::reflectance(&cos_theta, &refraction_ratio) > rng.gen::<f64>()
         {
@@ -59,10 +161,23 @@ impl Material for Dielectric {
         *scattered = Ray::new(rec.p, direction, r_in.time());
         true
     }
+
+    fn interior_medium(&self) -> Option<Arc<Box<dyn Medium>>> {
+        self.medium.clone()
+    }
 }
 
 #[macro_export]
 macro_rules! rc_box_dielectric {
+    ( $ior:expr, $film:expr, ThinFilm ) => {
+        Arc::new(Box::new(Dielectric::with_thin_film($ior, $film)))
+    };
+    ( $dispersion:expr, Dispersion ) => {
+        Arc::new(Box::new(Dielectric::with_dispersion($dispersion)))
+    };
+    ( $ior:expr, $medium:expr ) => {
+        Arc::new(Box::new(Dielectric::with_medium($ior, $medium)))
+    };
     ( $ior:expr ) => {
         Arc::new(Box::new(Dielectric::new($ior)))
     };
@@ -1,27 +1,108 @@
+use std::f64::consts::PI;
 use std::sync::Arc;
 
 use super::material::Material;
 use crate::{
     hittables::hittable::HitRecord,
+    lights::ies::IesProfile,
+    onb::Onb,
     ray::Ray,
+    spectrum,
     textures::texture::{SolidColor, Texture},
-    vec3::{Color, Point3},
+    vec3::Color,
 };
 
+/// Luminous efficacy of 555nm light, the most a watt can give.
+const LUMENS_PER_WATT: f64 = 683.0;
+
+/// Total power given off by a light.
+#[derive(Clone, Copy, Debug)]
+pub enum Power {
+    Watts(f64),
+    Lumens(f64),
+}
+
 pub struct DiffuseLight {
     pub emit: Arc<Box<dyn Texture>>,
+    pub intensity: f64,
+    /// Only emit from the side the surface's outward normal points to.
+    pub one_sided: bool,
+    /// Shapes the emission like a real fixture, with the nadir along the surface normal.
+    pub profile: Option<Arc<IesProfile>>,
 }
 
 impl DiffuseLight {
     pub fn new(emit: &Arc<Box<dyn Texture>>) -> Self {
+        Self::with_intensity(emit, 1.0, false)
+    }
+
+    pub fn with_color(color: Color) -> Self {
+        Self {
+            emit: Arc::new(Box::new(SolidColor::new(color))),
+            intensity: 1.0,
+            one_sided: false,
+            profile: None,
+        }
+    }
+
+    pub fn with_intensity(emit: &Arc<Box<dyn Texture>>, intensity: f64, one_sided: bool) -> Self {
         Self {
             emit: Arc::clone(emit),
+            intensity,
+            one_sided,
+            profile: None,
         }
     }
 
-    pub fn with_color(color: Color) -> Self {
+    /// One-sided light whose radiance towards the viewer follows the relative intensity of
+    /// `profile`, `intensity` being the radiance along the normal.
+    pub fn with_profile(
+        emit: &Arc<Box<dyn Texture>>,
+        intensity: f64,
+        profile: &Arc<IesProfile>,
+    ) -> Self {
         Self {
-            emit: Arc::new(Box::new(SolidColor::new(color))),
+            emit: Arc::clone(emit),
+            intensity,
+            one_sided: true,
+            profile: Some(Arc::clone(profile)),
+        }
+    }
+
+    /// Light of a black body at `kelvin`, where `intensity` is the emitted luminance.
+    pub fn with_temperature(kelvin: f64, intensity: f64, one_sided: bool) -> Self {
+        assert!(
+            kelvin > 0.0,
+            "DiffuseLight temperature must be above absolute zero"
+        );
+        Self {
+            emit: Arc::new(Box::new(SolidColor::new(spectrum::blackbody_rgb(kelvin)))),
+            intensity,
+            one_sided,
+            profile: None,
+        }
+    }
+
+    /// Light giving off `power` in total from a surface of `area`, like a bulb's rating. The
+    /// colour only sets the tint, it's scaled to a luminance of one first.
+    pub fn with_power(color: Color, power: Power, area: f64, one_sided: bool) -> Self {
+        let watts = match power {
+            Power::Watts(watts) => watts,
+            Power::Lumens(lumens) => lumens / LUMENS_PER_WATT,
+        };
+        // NOTE: a lambertian emitter of radiance L gives off pi * L * area per side
+        let sides = if one_sided { 1.0 } else { 2.0 };
+        let luminance = spectrum::luminance(&color);
+        assert!(
+            luminance > 0.0,
+            "DiffuseLight::with_power needs a colour with some luminance to tint"
+        );
+
+        Self {
+            emit: Arc::new(Box::new(SolidColor::new(color / luminance))),
+            intensity: watts / (sides * PI * area),
+            one_sided,
+            profile: None,
         }
     }
 }
@@ -37,8 +118,19 @@ impl Material for DiffuseLight {
         false
     }
 
-    fn emitted(&self, u: f64, v: f64, p: &Point3) -> Color {
-        self.emit.value(u, v, p)
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        if self.one_sided && !rec.front_face {
+            return Color::with_value(0.0);
+        }
+
+        let shape = match &self.profile {
+            Some(profile) => {
+                profile.relative_intensity(&Onb::build_from_w(&rec.normal), &-r_in.direction())
+            }
+            None => 1.0,
+        };
+
+        shape * self.intensity * self.emit.value_at(rec)
     }
 }
 
@@ -55,6 +147,26 @@ macro_rules! rc_box_diffuse_light {
     ( Color, $color:expr ) => {
         Arc::new(Box::new(DiffuseLight::with_color($color)))
     };
+    ( $kelvin:expr, $intensity:expr, $one_sided:expr, Kelvin ) => {
+        Arc::new(Box::new(DiffuseLight::with_temperature(
+            $kelvin, $intensity, $one_sided,
+        )))
+    };
+    ( $color:expr, $power:expr, $area:expr, $one_sided:expr, Power ) => {
+        Arc::new(Box::new(DiffuseLight::with_power(
+            $color, $power, $area, $one_sided,
+        )))
+    };
+    ( $emit:expr, $intensity:expr, $profile:expr, IesProfile ) => {
+        Arc::new(Box::new(DiffuseLight::with_profile(
+            $emit, $intensity, $profile,
+        )))
+    };
+    ( $emit:expr, $intensity:expr, $one_sided:expr ) => {
+        Arc::new(Box::new(DiffuseLight::with_intensity(
+            $emit, $intensity, $one_sided,
+        )))
+    };
     ( $material:expr ) => {
         Arc::new(Box::new(DiffuseLight::new($material)))
     };
@@ -0,0 +1,389 @@
+use std::f64::consts::{LN_2, PI};
+
+use rand::Rng;
+
+use crate::{
+    hittables::hittable::HitRecord,
+    onb::Onb,
+    ray::Ray,
+    vec3::{cross, dot, Color, Vec3},
+};
+
+use super::material::Material;
+
+/// Scattering orders modelled separately, longer paths through the fiber are lumped together.
+const P_MAX: usize = 3;
+
+/// Hair fiber scattering after Chiang et al. 2016, "A Practical and Controllable Hair and Fur
+/// Model for Production Path Tracing". Light reflects off the cuticle (R), passes through the
+/// fiber (TT) or reflects once inside it (TRT), losing colour to `sigma_a` on the way.
+/// `beta_m` and `beta_n` in `(0, 1]` are the longitudinal and azimuthal roughness and `alpha`
+/// the tilt of the cuticle scales in degrees.
+///
+/// The fiber runs along `dpdu` of the hit and `v` says where across it the ray hit, as on a
+/// `Curve`.
+pub struct Hair {
+    sigma_a: Color,
+    eta: f64,
+    /// Variance of the longitudinal lobes, for R, TT, TRT and the rest.
+    variance: [f64; P_MAX + 1],
+    /// Scale of the azimuthal logistic distribution.
+    s: f64,
+    sin_2k_alpha: [f64; 3],
+    cos_2k_alpha: [f64; 3],
+}
+
+impl Hair {
+    pub fn new(sigma_a: Color, eta: f64, beta_m: f64, beta_n: f64, alpha: f64) -> Self {
+        let v0 = (0.726 * beta_m + 0.812 * beta_m.powi(2) + 3.7 * beta_m.powi(20)).powi(2);
+        let s =
+            (PI / 8.0).sqrt() * (0.265 * beta_n + 1.194 * beta_n.powi(2) + 5.372 * beta_n.powi(22));
+
+        let mut sin_2k_alpha = [alpha.to_radians().sin(), 0.0, 0.0];
+        let mut cos_2k_alpha = [(1.0 - sin_2k_alpha[0].powi(2)).max(0.0).sqrt(), 0.0, 0.0];
+        for i in 1..3 {
+            sin_2k_alpha[i] = 2.0 * cos_2k_alpha[i - 1] * sin_2k_alpha[i - 1];
+            cos_2k_alpha[i] = cos_2k_alpha[i - 1].powi(2) - sin_2k_alpha[i - 1].powi(2);
+        }
+
+        Self {
+            sigma_a,
+            eta,
+            variance: [v0, 0.25 * v0, 4.0 * v0, 4.0 * v0],
+            s,
+            sin_2k_alpha,
+            cos_2k_alpha,
+        }
+    }
+
+    /// Natural hair coloured by its pigments, the concentrations of dark brown `eumelanin`
+    /// and reddish `pheomelanin`. Around 8 eumelanin is black, 1.3 brown and 0.3 blonde.
+    pub fn with_melanin(eumelanin: f64, pheomelanin: f64, beta_m: f64, beta_n: f64) -> Self {
+        let sigma_a = eumelanin * Color::with_values(0.419, 0.697, 1.37)
+            + pheomelanin * Color::with_values(0.187, 0.4, 1.05);
+        Self::new(sigma_a, 1.55, beta_m, beta_n, 2.0)
+    }
+
+    /// Hair that looks roughly `color` after many bounces, for dyed hair and fur.
+    pub fn with_color(color: Color, beta_m: f64, beta_n: f64) -> Self {
+        let denom = 5.969 - 0.215 * beta_n + 2.532 * beta_n.powi(2) - 10.73 * beta_n.powi(3)
+            + 5.574 * beta_n.powi(4)
+            + 0.245 * beta_n.powi(5);
+        let absorption = |c: f64| (c.max(1e-4).ln() / denom).powi(2);
+        let sigma_a = Color::with_values(
+            absorption(color.x()),
+            absorption(color.y()),
+            absorption(color.z()),
+        );
+        Self::new(sigma_a, 1.55, beta_m, beta_n, 2.0)
+    }
+
+    /// Directions in the frame of the fiber, x along it and `wo` in the xz plane.
+    fn frame(r_in: &Ray, rec: &HitRecord) -> Onb {
+        let wo = -r_in.direction().unit_vector();
+        let tangent = if rec.dpdu.near_zero() {
+            Onb::build_from_w(&rec.normal).u()
+        } else {
+            rec.dpdu.unit_vector()
+        };
+
+        let mut towards = wo - dot(&wo, &tangent) * tangent;
+        if towards.near_zero() {
+            towards = rec.normal - dot(&rec.normal, &tangent) * tangent;
+        }
+        let z = towards.unit_vector();
+        Onb::from_axes(tangent, cross(&z, &tangent), z)
+    }
+
+    fn local(frame: &Onb, w: &Vec3) -> Vec3 {
+        Vec3::with_values(dot(w, &frame.u()), dot(w, &frame.v()), dot(w, &frame.w()))
+    }
+
+    /// Sine and cosine of the outgoing longitudinal angle, tilted by the scales for lobe `p`.
+    fn tilted(&self, p: usize, sin_theta_o: f64, cos_theta_o: f64) -> (f64, f64) {
+        let (sin_theta, cos_theta) = match p {
+            0 => (
+                sin_theta_o * self.cos_2k_alpha[1] - cos_theta_o * self.sin_2k_alpha[1],
+                cos_theta_o * self.cos_2k_alpha[1] + sin_theta_o * self.sin_2k_alpha[1],
+            ),
+            1 => (
+                sin_theta_o * self.cos_2k_alpha[0] + cos_theta_o * self.sin_2k_alpha[0],
+                cos_theta_o * self.cos_2k_alpha[0] - sin_theta_o * self.sin_2k_alpha[0],
+            ),
+            2 => (
+                sin_theta_o * self.cos_2k_alpha[2] + cos_theta_o * self.sin_2k_alpha[2],
+                cos_theta_o * self.cos_2k_alpha[2] - sin_theta_o * self.sin_2k_alpha[2],
+            ),
+            _ => (sin_theta_o, cos_theta_o),
+        };
+        (sin_theta, cos_theta.abs())
+    }
+
+    /// Attenuation of each lobe for light leaving at `cos_theta_o` from offset `h`, and the
+    /// angle `gamma_t` it refracts to inside the fiber.
+    fn attenuation(&self, sin_theta_o: f64, cos_theta_o: f64, h: f64) -> ([Color; P_MAX + 1], f64) {
+        let sin_theta_t = sin_theta_o / self.eta;
+        let cos_theta_t = safe_sqrt(1.0 - sin_theta_t * sin_theta_t);
+        let etap = (self.eta * self.eta - sin_theta_o * sin_theta_o).sqrt() / cos_theta_o;
+        let sin_gamma_t = (h / etap).clamp(-1.0, 1.0);
+        let cos_gamma_t = safe_sqrt(1.0 - sin_gamma_t * sin_gamma_t);
+
+        let path = 2.0 * cos_gamma_t / cos_theta_t;
+        let transmittance = Color::with_values(
+            (-self.sigma_a.x() * path).exp(),
+            (-self.sigma_a.y() * path).exp(),
+            (-self.sigma_a.z() * path).exp(),
+        );
+
+        let cos_gamma_o = safe_sqrt(1.0 - h * h);
+        let f = fresnel(cos_theta_o * cos_gamma_o, self.eta);
+        let mut ap = [Color::new(); P_MAX + 1];
+        ap[0] = Color::with_value(f);
+        ap[1] = (1.0 - f).powi(2) * transmittance;
+        for p in 2..P_MAX {
+            ap[p] = ap[p - 1] * transmittance * f;
+        }
+        let tf = transmittance * f;
+        ap[P_MAX] = ap[P_MAX - 1] * tf / (Color::with_value(1.0) - tf);
+
+        (ap, sin_gamma_t.asin())
+    }
+
+    /// Chance of picking each lobe when sampling, in proportion to its attenuation.
+    fn lobe_pdf(ap: &[Color; P_MAX + 1]) -> [f64; P_MAX + 1] {
+        let weights = ap.map(|a| (a.x() + a.y() + a.z()) / 3.0);
+        let total: f64 = weights.iter().sum();
+        weights.map(|w| w / total.max(1e-12))
+    }
+
+    /// The scattering function times the cosine term and its sampling density, for local
+    /// directions.
+    fn evaluate(&self, wo: &Vec3, wi: &Vec3, h: f64) -> (Color, f64) {
+        let sin_theta_o = wo.x();
+        let cos_theta_o = safe_sqrt(1.0 - sin_theta_o * sin_theta_o);
+        let phi_o = wo.z().atan2(wo.y());
+        let sin_theta_i = wi.x();
+        let cos_theta_i = safe_sqrt(1.0 - sin_theta_i * sin_theta_i);
+        let phi_i = wi.z().atan2(wi.y());
+
+        let gamma_o = h.clamp(-1.0, 1.0).asin();
+        let (ap, gamma_t) = self.attenuation(sin_theta_o, cos_theta_o, h);
+        let lobe_pdf = Self::lobe_pdf(&ap);
+        let phi = phi_i - phi_o;
+
+        let mut f = Color::new();
+        let mut pdf = 0.0;
+        for p in 0..P_MAX {
+            let (sin_theta_op, cos_theta_op) = self.tilted(p, sin_theta_o, cos_theta_o);
+            let mp = longitudinal(
+                cos_theta_i,
+                cos_theta_op,
+                sin_theta_i,
+                sin_theta_op,
+                self.variance[p],
+            );
+            let np = azimuthal(phi, p, self.s, gamma_o, gamma_t);
+            f += mp * np * ap[p];
+            pdf += mp * np * lobe_pdf[p];
+        }
+
+        let mp = longitudinal(
+            cos_theta_i,
+            cos_theta_o,
+            sin_theta_i,
+            sin_theta_o,
+            self.variance[P_MAX],
+        );
+        f += mp * ap[P_MAX] / (2.0 * PI);
+        pdf += mp * lobe_pdf[P_MAX] / (2.0 * PI);
+
+        (f, pdf)
+    }
+}
+
+impl Material for Hair {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        let frame = Self::frame(r_in, rec);
+        let wo = Self::local(&frame, &-r_in.direction().unit_vector());
+        let h = 2.0 * rec.v - 1.0;
+
+        let sin_theta_o = wo.x();
+        let cos_theta_o = safe_sqrt(1.0 - sin_theta_o * sin_theta_o);
+        let phi_o = wo.z().atan2(wo.y());
+        let (ap, gamma_t) = self.attenuation(sin_theta_o, cos_theta_o, h);
+        let lobe_pdf = Self::lobe_pdf(&ap);
+
+        let mut rng = rand::thread_rng();
+        let mut pick = rng.gen::<f64>();
+        let mut p = 0;
+        while p < P_MAX && pick >= lobe_pdf[p] {
+            pick -= lobe_pdf[p];
+            p += 1;
+        }
+
+        // NOTE: sample the longitudinal lobe around the tilted mirror direction
+        let (sin_theta_op, cos_theta_op) = self.tilted(p, sin_theta_o, cos_theta_o);
+        let v = self.variance[p];
+        let u = rng.gen::<f64>().max(1e-5);
+        let cos_theta = 1.0 + v * (u + (1.0 - u) * (-2.0 / v).exp()).ln();
+        let sin_theta = safe_sqrt(1.0 - cos_theta * cos_theta);
+        let cos_phi = (2.0 * PI * rng.gen::<f64>()).cos();
+        let sin_theta_i = -cos_theta * sin_theta_op + sin_theta * cos_phi * cos_theta_op;
+        let cos_theta_i = safe_sqrt(1.0 - sin_theta_i * sin_theta_i);
+
+        let gamma_o = h.clamp(-1.0, 1.0).asin();
+        let dphi = if p < P_MAX {
+            exit_azimuth(p, gamma_o, gamma_t) + sample_trimmed_logistic(rng.gen(), self.s)
+        } else {
+            2.0 * PI * rng.gen::<f64>()
+        };
+        let phi_i = phi_o + dphi;
+
+        let wi = Vec3::with_values(
+            sin_theta_i,
+            cos_theta_i * phi_i.cos(),
+            cos_theta_i * phi_i.sin(),
+        );
+        let (f, pdf) = self.evaluate(&wo, &wi, h);
+        if pdf <= 0.0 {
+            return false;
+        }
+
+        *attenuation = f / pdf;
+        *scattered = Ray::new(rec.p, frame.local(wi.x(), wi.y(), wi.z()), r_in.time());
+        true
+    }
+
+    // NOTE: the model is written with the cosine term already divided out
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, wi: &Vec3) -> Color {
+        let frame = Self::frame(r_in, rec);
+        let wo = Self::local(&frame, &-r_in.direction().unit_vector());
+        self.evaluate(&wo, &Self::local(&frame, wi), 2.0 * rec.v - 1.0)
+            .0
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, wi: &Vec3) -> Option<f64> {
+        let frame = Self::frame(r_in, rec);
+        let wo = Self::local(&frame, &-r_in.direction().unit_vector());
+        Some(
+            self.evaluate(&wo, &Self::local(&frame, wi), 2.0 * rec.v - 1.0)
+                .1,
+        )
+    }
+}
+
+fn safe_sqrt(x: f64) -> f64 {
+    x.max(0.0).sqrt()
+}
+
+/// Fresnel reflectance of a dielectric for unpolarized light arriving from outside.
+fn fresnel(cos_theta_i: f64, eta: f64) -> f64 {
+    let cos_theta_i = cos_theta_i.clamp(-1.0, 1.0);
+    let sin_theta_t = safe_sqrt(1.0 - cos_theta_i * cos_theta_i) / eta;
+    if sin_theta_t >= 1.0 {
+        return 1.0;
+    }
+
+    let cos_theta_t = safe_sqrt(1.0 - sin_theta_t * sin_theta_t);
+    let parallel = (eta * cos_theta_i - cos_theta_t) / (eta * cos_theta_i + cos_theta_t);
+    let perpendicular = (cos_theta_i - eta * cos_theta_t) / (cos_theta_i + eta * cos_theta_t);
+    0.5 * (parallel * parallel + perpendicular * perpendicular)
+}
+
+/// Modified Bessel function of the first kind of order zero.
+fn bessel_i0(x: f64) -> f64 {
+    let mut value = 0.0;
+    let mut x2i = 1.0;
+    let mut factorial: f64 = 1.0;
+    let mut four_i = 1.0;
+    for i in 0..10 {
+        if i > 1 {
+            factorial *= i as f64;
+        }
+        value += x2i / (four_i * factorial * factorial);
+        x2i *= x * x;
+        four_i *= 4.0;
+    }
+    value
+}
+
+fn log_bessel_i0(x: f64) -> f64 {
+    if x > 12.0 {
+        x + 0.5 * (-(2.0 * PI).ln() + (1.0 / x).ln() + 1.0 / (8.0 * x))
+    } else {
+        bessel_i0(x).ln()
+    }
+}
+
+/// Longitudinal scattering `M_p`.
+fn longitudinal(
+    cos_theta_i: f64,
+    cos_theta_o: f64,
+    sin_theta_i: f64,
+    sin_theta_o: f64,
+    v: f64,
+) -> f64 {
+    let a = cos_theta_i * cos_theta_o / v;
+    let b = sin_theta_i * sin_theta_o / v;
+
+    // NOTE: low roughness overflows the direct form, work in logarithms instead
+    if v <= 0.1 {
+        (log_bessel_i0(a) - b - 1.0 / v + LN_2 + (1.0 / (2.0 * v)).ln()).exp()
+    } else {
+        (-b).exp() * bessel_i0(a) / ((1.0 / v).sinh() * 2.0 * v)
+    }
+}
+
+/// Azimuth the light leaves at after `p` internal paths, relative to where it came from.
+fn exit_azimuth(p: usize, gamma_o: f64, gamma_t: f64) -> f64 {
+    let p = p as f64;
+    2.0 * p * gamma_t - 2.0 * gamma_o + p * PI
+}
+
+fn logistic(x: f64, s: f64) -> f64 {
+    let x = x.abs();
+    (-x / s).exp() / (s * (1.0 + (-x / s).exp()).powi(2))
+}
+
+fn logistic_cdf(x: f64, s: f64) -> f64 {
+    1.0 / (1.0 + (-x / s).exp())
+}
+
+/// Azimuthal scattering `N_p`, a logistic distribution around the exit azimuth trimmed to
+/// the circle.
+fn azimuthal(phi: f64, p: usize, s: f64, gamma_o: f64, gamma_t: f64) -> f64 {
+    let dphi = (phi - exit_azimuth(p, gamma_o, gamma_t) + PI).rem_euclid(2.0 * PI) - PI;
+    logistic(dphi, s) / (logistic_cdf(PI, s) - logistic_cdf(-PI, s))
+}
+
+fn sample_trimmed_logistic(u: f64, s: f64) -> f64 {
+    let k = logistic_cdf(PI, s) - logistic_cdf(-PI, s);
+    let x = -s * (1.0 / (u * k + logistic_cdf(-PI, s)) - 1.0).ln();
+    x.clamp(-PI, PI)
+}
+
+#[macro_export]
+macro_rules! rc_box_hair {
+    ( Melanin, $eumelanin:expr, $pheomelanin:expr, $beta_m:expr, $beta_n:expr ) => {
+        Arc::new(Box::new(Hair::with_melanin(
+            $eumelanin,
+            $pheomelanin,
+            $beta_m,
+            $beta_n,
+        )))
+    };
+    ( Color, $color:expr, $beta_m:expr, $beta_n:expr ) => {
+        Arc::new(Box::new(Hair::with_color($color, $beta_m, $beta_n)))
+    };
+    ( $sigma_a:expr, $eta:expr, $beta_m:expr, $beta_n:expr, $alpha:expr ) => {
+        Arc::new(Box::new(Hair::new(
+            $sigma_a, $eta, $beta_m, $beta_n, $alpha,
+        )))
+    };
+}
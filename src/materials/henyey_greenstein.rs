@@ -0,0 +1,174 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::{
+    hittables::hittable::HitRecord,
+    onb::Onb,
+    ray::Ray,
+    textures::texture::{SolidColor, Texture},
+    vec3::{dot, Color, Vec3},
+};
+
+use super::material::Material;
+
+/// Henyey-Greenstein phase function. The asymmetry `g` in `(-1, 1)` is the mean cosine of the
+/// scattering angle, positive values scatter forward, negative backward and `0.0` is isotropic.
+pub struct HenyeyGreenstein {
+    albedo: Arc<Box<dyn Texture>>,
+    g: f64,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(color: Color, g: f64) -> Self {
+        Self {
+            albedo: Arc::new(Box::new(SolidColor::new(color))),
+            g,
+        }
+    }
+
+    pub fn with_texture(a: &Arc<Box<dyn Texture>>, g: f64) -> Self {
+        Self {
+            albedo: Arc::clone(a),
+            g,
+        }
+    }
+}
+
+impl Material for HenyeyGreenstein {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        let direction = sample_henyey_greenstein(&r_in.direction(), self.g);
+        *scattered = Ray::new(rec.p, direction, r_in.time());
+        *attenuation = self.albedo.value_at(rec);
+        true
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, wi: &Vec3) -> Color {
+        let cos_theta = dot(&r_in.direction().unit_vector(), wi);
+        self.albedo.value_at(rec) * henyey_greenstein(cos_theta, self.g)
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, _: &HitRecord, wi: &Vec3) -> Option<f64> {
+        let cos_theta = dot(&r_in.direction().unit_vector(), wi);
+        Some(henyey_greenstein(cos_theta, self.g))
+    }
+
+    fn is_phase_function(&self) -> bool {
+        true
+    }
+}
+
+/// Blend of two Henyey-Greenstein lobes, usually a strong forward lobe and a weaker backward
+/// one as in clouds. `weight` is the share of the first lobe.
+pub struct DualLobeHenyeyGreenstein {
+    albedo: Arc<Box<dyn Texture>>,
+    g1: f64,
+    g2: f64,
+    weight: f64,
+}
+
+impl DualLobeHenyeyGreenstein {
+    pub fn new(color: Color, g1: f64, g2: f64, weight: f64) -> Self {
+        Self {
+            albedo: Arc::new(Box::new(SolidColor::new(color))),
+            g1,
+            g2,
+            weight,
+        }
+    }
+
+    pub fn with_texture(a: &Arc<Box<dyn Texture>>, g1: f64, g2: f64, weight: f64) -> Self {
+        Self {
+            albedo: Arc::clone(a),
+            g1,
+            g2,
+            weight,
+        }
+    }
+}
+
+impl Material for DualLobeHenyeyGreenstein {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        let mut rng = rand::thread_rng();
+        let g = if rng.gen::<f64>() < self.weight {
+            self.g1
+        } else {
+            self.g2
+        };
+
+        let direction = sample_henyey_greenstein(&r_in.direction(), g);
+        *scattered = Ray::new(rec.p, direction, r_in.time());
+        *attenuation = self.albedo.value_at(rec);
+        true
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, wi: &Vec3) -> Color {
+        let cos_theta = dot(&r_in.direction().unit_vector(), wi);
+        let phase = self.weight * henyey_greenstein(cos_theta, self.g1)
+            + (1.0 - self.weight) * henyey_greenstein(cos_theta, self.g2);
+        self.albedo.value_at(rec) * phase
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, _: &HitRecord, wi: &Vec3) -> Option<f64> {
+        let cos_theta = dot(&r_in.direction().unit_vector(), wi);
+        Some(
+            self.weight * henyey_greenstein(cos_theta, self.g1)
+                + (1.0 - self.weight) * henyey_greenstein(cos_theta, self.g2),
+        )
+    }
+
+    fn is_phase_function(&self) -> bool {
+        true
+    }
+}
+
+/// Density of scattering by `cos_theta` away from the direction of travel, per steradian.
+pub fn henyey_greenstein(cos_theta: f64, g: f64) -> f64 {
+    let denom = 1.0 + g * g - 2.0 * g * cos_theta;
+    (1.0 - g * g) / (4.0 * PI * denom * denom.sqrt())
+}
+
+/// Samples a scattered direction for light travelling along `direction`.
+pub fn sample_henyey_greenstein(direction: &Vec3, g: f64) -> Vec3 {
+    let mut rng = rand::thread_rng();
+    let xi: f64 = rng.gen();
+
+    let cos_theta = if g.abs() < 1e-3 {
+        1.0 - 2.0 * xi
+    } else {
+        let sqr_term = (1.0 - g * g) / (1.0 - g + 2.0 * g * xi);
+        (1.0 + g * g - sqr_term * sqr_term) / (2.0 * g)
+    }
+    .clamp(-1.0, 1.0);
+
+    let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+    let phi = 2.0 * PI * rng.gen::<f64>();
+
+    let uvw = Onb::build_from_w(direction);
+    uvw.local(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta)
+}
+
+#[macro_export]
+macro_rules! rc_box_henyey_greenstein {
+    ( $color:expr, $g1:expr, $g2:expr, $weight:expr, DualLobe ) => {
+        Arc::new(Box::new(DualLobeHenyeyGreenstein::new(
+            $color, $g1, $g2, $weight,
+        )))
+    };
+    ( $color:expr, $g:expr ) => {
+        Arc::new(Box::new(HenyeyGreenstein::new($color, $g)))
+    };
+}
@@ -1,10 +1,11 @@
+use std::f64::consts::PI;
 use std::sync::Arc;
 
 use crate::{
     hittables::hittable::HitRecord,
     ray::Ray,
     textures::texture::{SolidColor, Texture},
-    vec3::{random_in_unit_sphere, Color},
+    vec3::{random_in_unit_sphere, Color, Vec3},
 };
 
 use super::material::Material;
@@ -36,7 +37,19 @@ impl Material for Isotropic {
         scattered: &mut Ray,
     ) -> bool {
         *scattered = Ray::new(rec.p, random_in_unit_sphere(), r_in.time());
-        *attenuation = self.albedo.value(rec.u, rec.v, &rec.p);
+        *attenuation = self.albedo.value_at(rec);
+        true
+    }
+
+    fn eval(&self, _: &Ray, rec: &HitRecord, _: &Vec3) -> Color {
+        self.albedo.value_at(rec) / (4.0 * PI)
+    }
+
+    fn scattering_pdf(&self, _: &Ray, _: &HitRecord, _: &Vec3) -> Option<f64> {
+        Some(1.0 / (4.0 * PI))
+    }
+
+    fn is_phase_function(&self) -> bool {
         true
     }
 }
@@ -1,10 +1,11 @@
+use std::f64::consts::PI;
 use std::sync::Arc;
 
 use crate::{
     hittables::hittable::HitRecord,
     ray::Ray,
     textures::texture::{SolidColor, Texture},
-    vec3::{random_unit_vector, Color},
+    vec3::{dot, random_unit_vector, Color, Vec3},
 };
 
 use super::material::Material;
@@ -50,9 +51,18 @@ impl Material for Lambertian {
         }
 
         *scattered = Ray::new(rec.p, scatter_direction, r_in.time());
-        *attenuation = self.albedo.value(rec.u, rec.v, &rec.p);
+        *attenuation = self.albedo.value_at(rec);
         true
     }
+
+    fn eval(&self, _: &Ray, rec: &HitRecord, wi: &Vec3) -> Color {
+        let cos_theta = dot(&rec.normal, wi).max(0.0);
+        self.albedo.value_at(rec) * cos_theta / PI
+    }
+
+    fn scattering_pdf(&self, _: &Ray, rec: &HitRecord, wi: &Vec3) -> Option<f64> {
+        Some(dot(&rec.normal, wi).max(0.0) / PI)
+    }
 }
 
 #[macro_export]
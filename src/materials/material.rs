@@ -1,10 +1,13 @@
+use std::sync::Arc;
+
 use crate::{
     hittables::hittable::HitRecord,
+    medium::Medium,
     ray::Ray,
-    vec3::{Color, Point3},
+    vec3::{Color, Point3, Vec3},
 };
 
-pub trait Material {
+pub trait Material: Send + Sync {
     fn scatter(
         &self,
         r_in: &Ray,
@@ -13,7 +16,37 @@ pub trait Material {
         scattered: &mut Ray,
     ) -> bool;
 
-    fn emitted(&self, _: f64, _: f64, _: &Point3) -> Color {
+    /// Scattering function times the cosine term for light arriving from the unit direction
+    /// `wi`, used to weight light sampled with shadow rays. Purely specular materials can't
+    /// be lit that way and keep the default of black.
+    fn eval(&self, _: &Ray, _: &HitRecord, _: &Vec3) -> Color {
+        Color::with_value(0.0)
+    }
+
+    /// Density per steradian with which `scatter` picks the unit direction `wi`. `None` for
+    /// materials that can't report it, like specular or layered ones, which integrators then
+    /// treat as specular.
+    fn scattering_pdf(&self, _: &Ray, _: &HitRecord, _: &Vec3) -> Option<f64> {
+        None
+    }
+
+    /// Whether this is the phase function of a medium rather than a surface, so there's no
+    /// normal to account for at its hits.
+    fn is_phase_function(&self) -> bool {
+        false
+    }
+
+    fn emitted(&self, _: &Ray, _: &HitRecord) -> Color {
         Color::with_value(0.0)
     }
+
+    /// Opacity of the surface at the hit, `0.0` lets every ray through and `1.0` none.
+    fn alpha(&self, _: f64, _: f64, _: &Point3) -> f64 {
+        1.0
+    }
+
+    /// Medium filling the inside of closed surfaces made of this material, if any.
+    fn interior_medium(&self) -> Option<Arc<Box<dyn Medium>>> {
+        None
+    }
 }
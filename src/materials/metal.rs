@@ -4,12 +4,16 @@ use crate::{
     vec3::{dot, random_in_unit_sphere, reflect, Color},
 };
 
-use super::material::Material;
+use super::{
+    material::Material,
+    thin_film::{Substrate, ThinFilm},
+};
 
 #[derive(Default)]
 pub struct Metal {
     pub albedo: Color,
     pub fuzz: f64,
+    film: Option<ThinFilm>,
 }
 
 impl Metal {
@@ -17,6 +21,16 @@ impl Metal {
         Self {
             albedo,
             fuzz: if f < 1.0 { f } else { 1.0 },
+            film: None,
+        }
+    }
+
+    /// Metal under a thin film, like anodised titanium or a heat tinted pan.
+    pub fn with_thin_film(albedo: Color, f: f64, film: ThinFilm) -> Self {
+        Self {
+            albedo,
+            fuzz: if f < 1.0 { f } else { 1.0 },
+            film: Some(film),
         }
     }
 }
@@ -35,7 +49,21 @@ impl Material for Metal {
             reflected + self.fuzz * random_in_unit_sphere(),
             r_in.time(),
         );
-        *attenuation = self.albedo;
+        *attenuation = match &self.film {
+            Some(film) => {
+                let cos_theta = dot(&(-r_in.direction().unit_vector()), &rec.normal);
+                let substrate = Substrate::Conductor(self.albedo);
+                film.reflectance(
+                    rec.u,
+                    rec.v,
+                    &rec.p,
+                    cos_theta,
+                    substrate,
+                    r_in.wavelength(),
+                )
+            }
+            None => self.albedo,
+        };
 
         dot(&scattered.direction(), &rec.normal) > 0.0
     }
@@ -43,6 +71,9 @@ impl Material for Metal {
 
 #[macro_export]
 macro_rules! rc_box_metal {
+    ( $color:expr, $f:expr, $film:expr, ThinFilm ) => {
+        Arc::new(Box::new(Metal::with_thin_film($color, $f, $film)))
+    };
     ( $color:expr, $f:expr ) => {
         Arc::new(Box::new(Metal::new($color, $f)))
     };
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::{
+    hittables::hittable::HitRecord,
+    medium::Medium,
+    ray::Ray,
+    textures::texture::{SolidColor, Texture},
+    vec3::{Color, Point3, Vec3},
+};
+
+use super::material::Material;
+
+/// Picks between two materials per hit. The mean of the weight texture's channels is the
+/// probability of using `b`, so `0.0` is all `a` and `1.0` is all `b`.
+pub struct MixMaterial {
+    a: Arc<Box<dyn Material>>,
+    b: Arc<Box<dyn Material>>,
+    weight: Arc<Box<dyn Texture>>,
+}
+
+impl MixMaterial {
+    pub fn new(
+        a: &Arc<Box<dyn Material>>,
+        b: &Arc<Box<dyn Material>>,
+        weight: &Arc<Box<dyn Texture>>,
+    ) -> Self {
+        Self {
+            a: Arc::clone(a),
+            b: Arc::clone(b),
+            weight: Arc::clone(weight),
+        }
+    }
+
+    pub fn with_weight(a: &Arc<Box<dyn Material>>, b: &Arc<Box<dyn Material>>, w: f64) -> Self {
+        Self {
+            a: Arc::clone(a),
+            b: Arc::clone(b),
+            weight: Arc::new(Box::new(SolidColor::new(Color::with_value(w)))),
+        }
+    }
+
+    fn weight_at(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        let w = self.weight.value(u, v, p);
+        ((w.x() + w.y() + w.z()) / 3.0).clamp(0.0, 1.0)
+    }
+}
+
+impl Material for MixMaterial {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        let mut rng = rand::thread_rng();
+        if rng.gen::<f64>() < self.weight_at(rec.u, rec.v, &rec.p) {
+            self.b.scatter(r_in, rec, attenuation, scattered)
+        } else {
+            self.a.scatter(r_in, rec, attenuation, scattered)
+        }
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, wi: &Vec3) -> Color {
+        let w = self.weight_at(rec.u, rec.v, &rec.p);
+        (1.0 - w) * self.a.eval(r_in, rec, wi) + w * self.b.eval(r_in, rec, wi)
+    }
+
+    // NOTE: only known if both sides can tell, a specular side makes the mix specular
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, wi: &Vec3) -> Option<f64> {
+        let w = self.weight_at(rec.u, rec.v, &rec.p);
+        let a = self.a.scattering_pdf(r_in, rec, wi)?;
+        let b = self.b.scattering_pdf(r_in, rec, wi)?;
+        Some((1.0 - w) * a + w * b)
+    }
+
+    fn is_phase_function(&self) -> bool {
+        self.a.is_phase_function() && self.b.is_phase_function()
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        let w = self.weight_at(rec.u, rec.v, &rec.p);
+        (1.0 - w) * self.a.emitted(r_in, rec) + w * self.b.emitted(r_in, rec)
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        let w = self.weight_at(u, v, p);
+        (1.0 - w) * self.a.alpha(u, v, p) + w * self.b.alpha(u, v, p)
+    }
+
+    fn interior_medium(&self) -> Option<Arc<Box<dyn Medium>>> {
+        self.a
+            .interior_medium()
+            .or_else(|| self.b.interior_medium())
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_mix {
+    ( $a:expr, $b:expr, $w:literal ) => {
+        Arc::new(Box::new(MixMaterial::with_weight($a, $b, $w)))
+    };
+    ( $a:expr, $b:expr, $weight:expr ) => {
+        Arc::new(Box::new(MixMaterial::new($a, $b, $weight)))
+    };
+}
@@ -1,6 +1,15 @@
+pub mod alpha_mask;
+pub mod bump_map;
+pub mod chromatic_phase;
+pub mod coated;
 pub mod dielectric;
 pub mod diffuse_light;
+pub mod hair;
+pub mod henyey_greenstein;
 pub mod isotropic;
 pub mod lambertian;
 pub mod material;
 pub mod metal;
+pub mod mix;
+pub mod normal_map;
+pub mod thin_film;
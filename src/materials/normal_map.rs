@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use crate::{
+    hittables::hittable::HitRecord,
+    medium::Medium,
+    ray::Ray,
+    textures::texture::Texture,
+    vec3::{cross, dot, Color, Point3, Vec3},
+};
+
+use super::material::Material;
+
+/// Wraps a material and bends its shading normal with a tangent-space normal map, where
+/// the red, green and blue channels map to the `dpdu`, `dpdv` and normal directions.
+pub struct NormalMap {
+    base: Arc<Box<dyn Material>>,
+    map: Arc<Box<dyn Texture>>,
+}
+
+impl NormalMap {
+    pub fn new(base: &Arc<Box<dyn Material>>, map: &Arc<Box<dyn Texture>>) -> Self {
+        Self {
+            base: Arc::clone(base),
+            map: Arc::clone(map),
+        }
+    }
+
+    fn perturbed_normal(&self, rec: &HitRecord) -> Vec3 {
+        let n = rec.normal;
+
+        let tangent = rec.dpdu - dot(&n, &rec.dpdu) * n;
+        if tangent.near_zero() {
+            return n;
+        }
+        let tangent = tangent.unit_vector();
+
+        let bitangent = rec.dpdv - dot(&n, &rec.dpdv) * n - dot(&tangent, &rec.dpdv) * tangent;
+        let bitangent = if bitangent.near_zero() {
+            cross(&n, &tangent)
+        } else {
+            bitangent.unit_vector()
+        };
+
+        let m = 2.0 * self.map.value_at(rec) - 1.0;
+        let perturbed = m.x() * tangent + m.y() * bitangent + m.z() * n;
+
+        if perturbed.near_zero() || dot(&perturbed, &n) <= 0.0 {
+            n
+        } else {
+            perturbed.unit_vector()
+        }
+    }
+}
+
+impl Material for NormalMap {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        let mut shading_rec = rec.clone();
+        shading_rec.normal = self.perturbed_normal(rec);
+        self.base
+            .scatter(r_in, &shading_rec, attenuation, scattered)
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, wi: &Vec3) -> Color {
+        let mut shading_rec = rec.clone();
+        shading_rec.normal = self.perturbed_normal(rec);
+        self.base.eval(r_in, &shading_rec, wi)
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, wi: &Vec3) -> Option<f64> {
+        let mut shading_rec = rec.clone();
+        shading_rec.normal = self.perturbed_normal(rec);
+        self.base.scattering_pdf(r_in, &shading_rec, wi)
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        self.base.emitted(r_in, rec)
+    }
+
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        self.base.alpha(u, v, p)
+    }
+
+    fn interior_medium(&self) -> Option<Arc<Box<dyn Medium>>> {
+        self.base.interior_medium()
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_normal_map {
+    ( $base:expr, $map:expr ) => {
+        Arc::new(Box::new(NormalMap::new($base, $map)))
+    };
+}
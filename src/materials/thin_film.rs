@@ -0,0 +1,144 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use crate::{
+    spectrum::rgb_to_spectrum,
+    textures::texture::{SolidColor, Texture},
+    vec3::{Color, Point3},
+};
+
+/// What a thin film sits on top of.
+#[derive(Clone, Copy)]
+pub enum Substrate {
+    /// A dielectric with the given index of refraction.
+    Dielectric(f64),
+    /// A metal with the given reflectance at normal incidence.
+    Conductor(Color),
+}
+
+/// A coating a few hundred nanometres thick whose reflections off its top and bottom interfere,
+/// giving soap bubble and oil slick colours. The thickness comes from a texture, mapping the
+/// mean of its channels from `[0, 1]` to `[min_thickness, max_thickness]` nanometres.
+pub struct ThinFilm {
+    thickness: Arc<Box<dyn Texture>>,
+    min_thickness: f64,
+    max_thickness: f64,
+    ior: f64,
+}
+
+impl ThinFilm {
+    // NOTE: wavelengths in nanometres standing in for the red, green and blue channels
+    const RGB_WAVELENGTHS: [f64; 3] = [650.0, 532.0, 450.0];
+
+    pub fn new(thickness: f64, ior: f64) -> Self {
+        Self {
+            thickness: Arc::new(Box::new(SolidColor::new(Color::with_value(1.0)))),
+            min_thickness: thickness,
+            max_thickness: thickness,
+            ior,
+        }
+    }
+
+    pub fn with_texture(
+        thickness: &Arc<Box<dyn Texture>>,
+        min_thickness: f64,
+        max_thickness: f64,
+        ior: f64,
+    ) -> Self {
+        Self {
+            thickness: Arc::clone(thickness),
+            min_thickness,
+            max_thickness,
+            ior,
+        }
+    }
+
+    fn thickness_at(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        let t = self.thickness.value(u, v, p);
+        let t = ((t.x() + t.y() + t.z()) / 3.0).clamp(0.0, 1.0);
+        self.min_thickness + t * (self.max_thickness - self.min_thickness)
+    }
+
+    /// Reflectance of the coated surface for light arriving from air at `cos_theta` to the
+    /// normal. In spectral mode every channel holds the reflectance at `lambda`.
+    pub fn reflectance(
+        &self,
+        u: f64,
+        v: f64,
+        p: &Point3,
+        cos_theta: f64,
+        substrate: Substrate,
+        lambda: Option<f64>,
+    ) -> Color {
+        let d = self.thickness_at(u, v, p);
+
+        match lambda {
+            Some(lambda) => {
+                let f0 = match substrate {
+                    Substrate::Conductor(albedo) => rgb_to_spectrum(&albedo, lambda),
+                    Substrate::Dielectric(_) => 0.0,
+                };
+                Color::with_value(self.reflectance_at(d, cos_theta, substrate, f0, lambda))
+            }
+            None => {
+                let mut color = Color::new();
+                for (i, &lambda) in Self::RGB_WAVELENGTHS.iter().enumerate() {
+                    let f0 = match substrate {
+                        Substrate::Conductor(albedo) => albedo[i],
+                        Substrate::Dielectric(_) => 0.0,
+                    };
+                    color[i] = self.reflectance_at(d, cos_theta, substrate, f0, lambda);
+                }
+                color
+            }
+        }
+    }
+
+    fn reflectance_at(
+        &self,
+        d: f64,
+        cos_theta: f64,
+        substrate: Substrate,
+        conductor_f0: f64,
+        lambda: f64,
+    ) -> f64 {
+        let n2 = self.ior;
+        let cos1 = cos_theta.clamp(0.0, 1.0);
+        let sin2_sq = (1.0 - cos1 * cos1) / (n2 * n2);
+        let cos2 = (1.0 - sin2_sq).sqrt();
+
+        let (r12s, r12p) = fresnel_amplitudes(1.0, cos1, n2, cos2);
+        let (r23s, r23p) = match substrate {
+            Substrate::Dielectric(n3) => {
+                let sin3_sq = n2 * n2 * sin2_sq / (n3 * n3);
+                if sin3_sq >= 1.0 {
+                    (1.0, 1.0)
+                } else {
+                    fresnel_amplitudes(n2, cos2, n3, (1.0 - sin3_sq).sqrt())
+                }
+            }
+            Substrate::Conductor(_) => {
+                // NOTE: metals reflect with a half wave phase shift, like a denser dielectric
+                let f = conductor_f0 + (1.0 - conductor_f0) * (1.0 - cos2).powi(5);
+                (-f.sqrt(), -f.sqrt())
+            }
+        };
+
+        let delta = 4.0 * PI * n2 * d * cos2 / lambda;
+        0.5 * (airy(r12s, r23s, delta) + airy(r12p, r23p, delta))
+    }
+}
+
+/// Reflection amplitudes for s and p polarised light at an interface.
+fn fresnel_amplitudes(n_i: f64, cos_i: f64, n_t: f64, cos_t: f64) -> (f64, f64) {
+    let rs = (n_i * cos_i - n_t * cos_t) / (n_i * cos_i + n_t * cos_t);
+    let rp = (n_t * cos_i - n_i * cos_t) / (n_t * cos_i + n_i * cos_t);
+    (rs, rp)
+}
+
+/// Reflectance of a film from the amplitudes at its two interfaces and the phase difference
+/// between successive reflections, summing every internal bounce.
+fn airy(r12: f64, r23: f64, delta: f64) -> f64 {
+    let cross = 2.0 * r12 * r23 * delta.cos();
+    (r12 * r12 + r23 * r23 + cross) / (1.0 + r12 * r12 * r23 * r23 + cross)
+}
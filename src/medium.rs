@@ -0,0 +1,167 @@
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::{
+    hittables::hittable::HitRecord,
+    materials::{
+        chromatic_phase::ChromaticPhase,
+        henyey_greenstein::{DualLobeHenyeyGreenstein, HenyeyGreenstein},
+        isotropic::Isotropic,
+        material::Material,
+    },
+    ray::Ray,
+    textures::density::DensityField,
+    vec3::{Color, Vec3},
+};
+
+/// A participating medium that fills the inside of a surface, attached through
+/// `Material::interior_medium`. Unlike `ConstantMedium` it has no boundary of its own, the
+/// integrator tracks which medium a ray is travelling through.
+pub trait Medium: Send + Sync {
+    /// Samples where `ray` next interacts with the medium. Fills `rec` and returns true if
+    /// that happens before `t_max`.
+    fn sample(&self, ray: &Ray, t_max: f64, rec: &mut HitRecord) -> bool;
+}
+
+pub struct HomogeneousMedium {
+    phase_function: Arc<Box<dyn Material>>,
+    neg_inv_density: f64,
+}
+
+impl HomogeneousMedium {
+    pub fn new(d: f64, a: &Arc<Box<dyn Material>>) -> Self {
+        Self {
+            neg_inv_density: -1.0 / d,
+            phase_function: Arc::clone(a),
+        }
+    }
+
+    pub fn with_color(d: f64, a: Color) -> Self {
+        Self {
+            neg_inv_density: -1.0 / d,
+            phase_function: Arc::new(Box::new(Isotropic::new(a))),
+        }
+    }
+
+    pub fn with_henyey_greenstein(d: f64, a: Color, g: f64) -> Self {
+        Self {
+            neg_inv_density: -1.0 / d,
+            phase_function: Arc::new(Box::new(HenyeyGreenstein::new(a, g))),
+        }
+    }
+
+    pub fn with_dual_lobe(d: f64, a: Color, g1: f64, g2: f64, weight: f64) -> Self {
+        Self {
+            neg_inv_density: -1.0 / d,
+            phase_function: Arc::new(Box::new(DualLobeHenyeyGreenstein::new(a, g1, g2, weight))),
+        }
+    }
+
+    pub fn chromatic(phase: ChromaticPhase) -> Self {
+        Self {
+            neg_inv_density: -1.0 / phase.majorant(),
+            phase_function: Arc::new(Box::new(phase)),
+        }
+    }
+}
+
+impl Medium for HomogeneousMedium {
+    fn sample(&self, ray: &Ray, t_max: f64, rec: &mut HitRecord) -> bool {
+        let mut rng = rand::thread_rng();
+
+        let ray_length = ray.direction().length();
+        let hit_distance = self.neg_inv_density * rng.gen_range(0.0..1.0f64).ln();
+        let t = hit_distance / ray_length;
+        if t >= t_max {
+            return false;
+        }
+
+        set_medium_record(ray, t, &self.phase_function, rec);
+        true
+    }
+}
+
+/// Medium with a spatially varying density, sampled with delta tracking.
+pub struct DensityMedium {
+    density: Arc<Box<dyn DensityField>>,
+    phase_function: Arc<Box<dyn Material>>,
+}
+
+impl DensityMedium {
+    pub fn new(d: &Arc<Box<dyn DensityField>>, a: &Arc<Box<dyn Material>>) -> Self {
+        Self {
+            density: Arc::clone(d),
+            phase_function: Arc::clone(a),
+        }
+    }
+
+    pub fn with_color(d: &Arc<Box<dyn DensityField>>, a: Color) -> Self {
+        Self {
+            density: Arc::clone(d),
+            phase_function: Arc::new(Box::new(Isotropic::new(a))),
+        }
+    }
+}
+
+impl Medium for DensityMedium {
+    fn sample(&self, ray: &Ray, t_max: f64, rec: &mut HitRecord) -> bool {
+        let majorant = self.density.max_density();
+        if majorant <= 0.0 {
+            return false;
+        }
+
+        let mut rng = rand::thread_rng();
+        let ray_length = ray.direction().length();
+
+        let mut t = 0.0;
+        loop {
+            t -= (1.0 - rng.gen::<f64>()).ln() / (majorant * ray_length);
+            if t >= t_max {
+                return false;
+            }
+
+            if rng.gen::<f64>() * majorant < self.density.density(&ray.at(t)) {
+                set_medium_record(ray, t, &self.phase_function, rec);
+                return true;
+            }
+        }
+    }
+}
+
+fn set_medium_record(
+    ray: &Ray,
+    t: f64,
+    phase_function: &Arc<Box<dyn Material>>,
+    rec: &mut HitRecord,
+) {
+    rec.t = t;
+    rec.p = ray.at(t);
+    rec.normal = Vec3::with_values(1.0, 0.0, 0.0); // arbitrary
+    rec.front_face = true; // also arbitrary
+    rec.vertex_color = None;
+    rec.mat_ptr = Arc::clone(phase_function);
+}
+
+#[macro_export]
+macro_rules! rc_box_homogeneous_medium {
+    ( $phase:expr, Chromatic ) => {
+        Arc::new(Box::new(HomogeneousMedium::chromatic($phase)))
+    };
+    ( $d:expr, $color:expr, Color ) => {
+        Arc::new(Box::new(HomogeneousMedium::with_color($d, $color)))
+    };
+    ( $d:expr, $color:expr, $g1:expr, $g2:expr, $weight:expr, DualLobe ) => {
+        Arc::new(Box::new(HomogeneousMedium::with_dual_lobe(
+            $d, $color, $g1, $g2, $weight,
+        )))
+    };
+    ( $d:expr, $color:expr, $g:expr, HenyeyGreenstein ) => {
+        Arc::new(Box::new(HomogeneousMedium::with_henyey_greenstein(
+            $d, $color, $g,
+        )))
+    };
+    ( $d:expr, $mat_ptr:expr ) => {
+        Arc::new(Box::new(HomogeneousMedium::new($d, $mat_ptr)))
+    };
+}
@@ -0,0 +1,42 @@
+use crate::vec3::{cross, Vec3};
+
+/// Orthonormal basis with `w` along a given direction.
+pub struct Onb {
+    axis: [Vec3; 3],
+}
+
+impl Onb {
+    pub fn build_from_w(n: &Vec3) -> Self {
+        let w = n.unit_vector();
+        let a = if w.x().abs() > 0.9 {
+            Vec3::with_values(0.0, 1.0, 0.0)
+        } else {
+            Vec3::with_values(1.0, 0.0, 0.0)
+        };
+        let v = cross(&w, &a).unit_vector();
+        let u = cross(&w, &v);
+
+        Self { axis: [u, v, w] }
+    }
+
+    /// Basis from three orthonormal vectors.
+    pub fn from_axes(u: Vec3, v: Vec3, w: Vec3) -> Self {
+        Self { axis: [u, v, w] }
+    }
+
+    pub fn u(&self) -> Vec3 {
+        self.axis[0]
+    }
+
+    pub fn v(&self) -> Vec3 {
+        self.axis[1]
+    }
+
+    pub fn w(&self) -> Vec3 {
+        self.axis[2]
+    }
+
+    pub fn local(&self, a: f64, b: f64, c: f64) -> Vec3 {
+        a * self.u() + b * self.v() + c * self.w()
+    }
+}
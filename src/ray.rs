@@ -4,11 +4,17 @@ pub struct Ray {
     orig: Point3,
     dir: Vec3,
     tm: f64,
+    lambda: Option<f64>,
 }
 
 impl Ray {
     pub fn new(orig: Point3, dir: Vec3, tm: f64) -> Self {
-        Self { orig, dir, tm }
+        Self {
+            orig,
+            dir,
+            tm,
+            lambda: None,
+        }
     }
 
     pub fn origin(&self) -> Point3 {
@@ -23,6 +29,15 @@ impl Ray {
         self.tm
     }
 
+    /// Wavelength in nanometres carried by the ray in spectral mode, `None` when rendering RGB.
+    pub fn wavelength(&self) -> Option<f64> {
+        self.lambda
+    }
+
+    pub fn set_wavelength(&mut self, lambda: Option<f64>) {
+        self.lambda = lambda;
+    }
+
     pub fn at(&self, t: f64) -> Point3 {
         self.orig + self.dir * t
     }
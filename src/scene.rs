@@ -1,176 +1,1725 @@
 use rand::Rng;
+use std::f64::consts::PI;
 use std::sync::Arc;
 
 use crate::{
+    aabb::AABB,
     camera::Camera,
     hittables::{
+        animated::{AnimatedTransform, Keyframe},
         bvh::BVHNode,
+        capsule::Capsule,
+        cone::Cone,
         constant_medium::ConstantMedium,
+        csg::{Csg, CsgOperation},
         cuboid::Cuboid,
+        curve::{Curve, CurveType},
+        cylinder::Cylinder,
+        disk::Disk,
+        flip_face::FlipFace,
+        heightfield::Heightfield,
+        heterogeneous_medium::HeterogeneousMedium,
         hittable::{Hittable, HittableList},
+        instance::Instance,
+        mesh::{MeshData, TriangleMesh},
         moving_sphere::MovingSphere,
+        plane::Plane,
+        ply,
+        quad::Quad,
         rect::{xy_rect::XYRect, xz_rect::XZRect, yz_rect::YZRect},
         rotate::RotateY,
+        sdf::{
+            Repeat, Sdf, SdfCapsule, SdfHittable, SdfRoundBox, SdfTorus, SmoothSubtraction,
+            SmoothUnion,
+        },
         sphere::Sphere,
+        stl,
+        subsurface::Subsurface,
+        torus::Torus,
         translate::Translate,
     },
+    lights::{
+        directional_light::DirectionalLight,
+        ies::IesProfile,
+        light::{Light, LightList},
+        point_light::PointLight,
+        spot_light::SpotLight,
+    },
     materials::{
-        dielectric::Dielectric, diffuse_light::DiffuseLight, lambertian::Lambertian,
-        material::Material, metal::Metal,
+        alpha_mask::AlphaMask,
+        bump_map::BumpMap,
+        chromatic_phase::ChromaticPhase,
+        coated::Coated,
+        dielectric::{Dielectric, Dispersion},
+        diffuse_light::{DiffuseLight, Power},
+        hair::Hair,
+        lambertian::Lambertian,
+        material::Material,
+        metal::Metal,
+        mix::MixMaterial,
+        thin_film::ThinFilm,
     },
-    rc_box_bvh_node, rc_box_checker_texture, rc_box_constant_medium, rc_box_cuboid,
-    rc_box_dielectric, rc_box_diffuse_light, rc_box_image_texture, rc_box_lambertian, rc_box_metal,
-    rc_box_moving_sphere, rc_box_noise_texture, rc_box_rotate_y, rc_box_sphere, rc_box_translate,
-    rc_box_xy_rect, rc_box_xz_rect, rc_box_yz_rect,
+    medium::{HomogeneousMedium, Medium},
+    rc_box_alpha_mask, rc_box_animated, rc_box_bump_map, rc_box_bvh_node, rc_box_capsule,
+    rc_box_checker_texture, rc_box_coated, rc_box_cone, rc_box_constant_medium, rc_box_csg,
+    rc_box_cuboid, rc_box_curve, rc_box_cylinder, rc_box_dielectric, rc_box_diffuse_light,
+    rc_box_directional_light, rc_box_disk, rc_box_flip_face, rc_box_hair,
+    rc_box_heterogeneous_medium, rc_box_homogeneous_medium, rc_box_image_texture, rc_box_instance,
+    rc_box_lambertian, rc_box_metal, rc_box_mix, rc_box_moving_sphere, rc_box_noise_texture,
+    rc_box_plane, rc_box_point_light, rc_box_quad, rc_box_rotate_y, rc_box_sdf, rc_box_sphere,
+    rc_box_spot_light, rc_box_subsurface, rc_box_torus, rc_box_translate, rc_box_triangle_mesh,
+    rc_box_vertex_color_texture, rc_box_xy_rect, rc_box_xz_rect, rc_box_yz_rect,
+    scene_graph::SceneNode,
     textures::{
-        check_texture::CheckerTexture, image_texture::ImageTexture, perlin::NoiseTexture,
-        texture::Texture,
+        check_texture::CheckerTexture,
+        density::{DensityField, FnDensity, PerlinDensity},
+        image_texture::ImageTexture,
+        perlin::NoiseTexture,
+        texture::{SolidColor, Texture},
+        vertex_color::VertexColorTexture,
     },
-    vec3::{Color, Point3, Vec3},
+    transform::Transform,
+    vec3::{random_unit_vector, Color, Point3, Vec3},
 };
 
-pub struct Settings {
-    pub aspect_ratio: f64,
-    pub image_width: u64,
-    pub image_height: u64,
-    pub samples_per_pixel: u64,
-    pub max_depth: u64,
-}
+/// A downlight with a bright inner beam and a wider ring, the kind of profile that gives the
+/// scalloped pattern on walls.
+const DOWNLIGHT_IES: &str = "IESNA:LM-63-2002
+[TEST] example downlight
+[MANUFAC] maus
+TILT=NONE
+1 1000 1 10 1 1 2 0.1 0.1 0.05
+1 1 12
+0 10 20 30 40 50 60 70 80 90
+0
+900 1200 1500 700 250 550 180 40 8 0
+";
+
+/// A unit cube with the RGB color cube painted on its corners, as a scanner would write it.
+const RGB_CUBE_PLY: &str = "ply
+format ascii 1.0
+comment corners colored by position
+element vertex 8
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+element face 6
+property list uchar int vertex_indices
+end_header
+-0.5 -0.5 -0.5 0 0 0
+0.5 -0.5 -0.5 255 0 0
+-0.5 0.5 -0.5 0 255 0
+0.5 0.5 -0.5 255 255 0
+-0.5 -0.5 0.5 0 0 255
+0.5 -0.5 0.5 255 0 255
+-0.5 0.5 0.5 0 255 255
+0.5 0.5 0.5 255 255 255
+4 0 2 3 1
+4 4 5 7 6
+4 0 1 5 4
+4 2 6 7 3
+4 0 4 6 2
+4 1 3 7 5
+";
+
+/// A square pyramid as a CAD package would export it.
+const PYRAMID_STL: &str = "solid pyramid
+facet normal 0 -1 0
+outer loop
+vertex -0.7 0 -0.7
+vertex 0.7 0 -0.7
+vertex 0.7 0 0.7
+endloop
+endfacet
+facet normal 0 -1 0
+outer loop
+vertex -0.7 0 -0.7
+vertex 0.7 0 0.7
+vertex -0.7 0 0.7
+endloop
+endfacet
+facet normal 0 0.504 0.864
+outer loop
+vertex -0.7 0 0.7
+vertex 0.7 0 0.7
+vertex 0 1.2 0
+endloop
+endfacet
+facet normal 0.864 0.504 0
+outer loop
+vertex 0.7 0 0.7
+vertex 0.7 0 -0.7
+vertex 0 1.2 0
+endloop
+endfacet
+facet normal 0 0.504 -0.864
+outer loop
+vertex 0.7 0 -0.7
+vertex -0.7 0 -0.7
+vertex 0 1.2 0
+endloop
+endfacet
+facet normal -0.864 0.504 0
+outer loop
+vertex -0.7 0 -0.7
+vertex -0.7 0 0.7
+vertex 0 1.2 0
+endloop
+endfacet
+endsolid pyramid
+";
+
+pub struct Settings {
+    pub aspect_ratio: f64,
+    pub image_width: u64,
+    pub image_height: u64,
+    pub samples_per_pixel: u64,
+    pub max_depth: u64,
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        let aspect_ratio = 3.0 / 2.0;
+        let image_width = 1200;
+        Self {
+            aspect_ratio,
+            image_width,
+            image_height: (image_width as f64 / aspect_ratio) as u64,
+            samples_per_pixel: 500,
+            max_depth: 50,
+        }
+    }
+
+    pub fn set_wdith(&mut self, width: u64) {
+        self.image_width = width;
+        self.image_height = (self.image_width as f64 / self.aspect_ratio) as u64;
+    }
+}
+
+pub struct Scene;
+impl Scene {
+    pub fn world_select(world_i: usize) -> (HittableList, LightList, Camera, Color, Settings) {
+        let (lookfrom, lookat, background);
+        let (mut vfov, mut aperture) = (40.0, 0.0);
+        let mut settings = Settings::new();
+
+        let world: HittableList;
+        let mut lights = LightList::new();
+        match world_i {
+            1 => {
+                world = Self::random_scene();
+                background = Color::with_values(0.70, 0.80, 1.00);
+                lookfrom = Point3::with_values(13.0, 2.0, 3.0);
+                lookat = Point3::with_value(0.0);
+
+                settings.samples_per_pixel = 50;
+                settings.set_wdith(200);
+
+                vfov = 20.0;
+                aperture = 0.1;
+            }
+            2 => {
+                world = Self::two_sphere();
+                background = Color::with_values(0.70, 0.80, 1.00);
+                lookfrom = Point3::with_values(13.0, 2.0, 3.0);
+                lookat = Point3::with_value(0.0);
+                vfov = 20.0;
+            }
+            3 => {
+                world = Self::two_perlin_spheres();
+                background = Color::with_values(0.70, 0.80, 1.00);
+                lookfrom = Point3::with_values(13.0, 2.0, 3.0);
+                lookat = Point3::with_value(0.0);
+                vfov = 20.0;
+            }
+            4 => {
+                world = Self::earth();
+                background = Color::with_values(0.70, 0.80, 1.00);
+                lookfrom = Point3::with_values(13.0, 2.0, 3.0);
+                lookat = Point3::with_value(0.0);
+                vfov = 20.0;
+            }
+            5 => {
+                world = HittableList::new();
+                background = Color::with_value(0.0);
+                lookfrom = Point3::with_value(0.0);
+                lookat = Point3::with_value(0.0);
+            }
+            6 => {
+                (world, lights) = Self::simple_light();
+                settings.samples_per_pixel = 400;
+                background = Color::with_value(0.0);
+                lookfrom = Point3::with_values(26.0, 3.0, 6.0);
+                lookat = Point3::with_values(0.0, 2.0, 0.0);
+                vfov = 20.0;
+            }
+            7 => {
+                (world, lights) = Self::cornell_box();
+                settings.aspect_ratio = 1.0;
+                settings.set_wdith(600);
+                settings.samples_per_pixel = 400;
+                background = Color::with_value(0.0);
+                lookfrom = Point3::with_values(278.0, 278.0, -800.0);
+                lookat = Point3::with_values(278.0, 278.0, 0.0);
+                vfov = 40.0;
+            }
+            8 => {
+                (world, lights) = Self::cornell_smoke();
+                settings.aspect_ratio = 1.0;
+                settings.set_wdith(600);
+                settings.samples_per_pixel = 200;
+                background = Color::with_value(0.0);
+                lookfrom = Point3::with_values(278.0, 278.0, -800.0);
+                lookat = Point3::with_values(278.0, 278.0, 0.0);
+                vfov = 40.0;
+            }
+            9 => {
+                world = Self::bump_mapped_spheres();
+                background = Color::with_values(0.70, 0.80, 1.00);
+                lookfrom = Point3::with_values(13.0, 2.0, 3.0);
+                lookat = Point3::with_values(0.0, 1.0, 0.0);
+                vfov = 20.0;
+            }
+            10 => {
+                world = Self::cutout_fence();
+                background = Color::with_values(0.70, 0.80, 1.00);
+                lookfrom = Point3::with_values(0.0, 2.0, 12.0);
+                lookat = Point3::with_values(0.0, 1.0, 0.0);
+                vfov = 25.0;
+            }
+            11 => {
+                world = Self::mixed_materials();
+                background = Color::with_values(0.70, 0.80, 1.00);
+                lookfrom = Point3::with_values(13.0, 2.0, 3.0);
+                lookat = Point3::with_values(0.0, 1.0, 0.0);
+                vfov = 25.0;
+            }
+            12 => {
+                world = Self::subsurface_spheres();
+                settings.samples_per_pixel = 400;
+                settings.max_depth = 200;
+                background = Color::with_values(0.05, 0.05, 0.08);
+                lookfrom = Point3::with_values(13.0, 3.0, 3.0);
+                lookat = Point3::with_values(0.0, 1.0, 0.0);
+                vfov = 25.0;
+            }
+            13 => {
+                world = Self::clouds();
+                background = Color::with_values(0.70, 0.80, 1.00);
+                lookfrom = Point3::with_values(13.0, 2.0, 3.0);
+                lookat = Point3::with_values(0.0, 2.0, 0.0);
+                vfov = 30.0;
+            }
+            14 => {
+                (world, lights) = Self::cornell_fog();
+                settings.aspect_ratio = 1.0;
+                settings.set_wdith(600);
+                settings.samples_per_pixel = 400;
+                background = Color::with_value(0.0);
+                lookfrom = Point3::with_values(278.0, 278.0, -800.0);
+                lookat = Point3::with_values(278.0, 278.0, 0.0);
+                vfov = 40.0;
+            }
+            15 => {
+                world = Self::cornell_media();
+                settings.aspect_ratio = 1.0;
+                settings.set_wdith(600);
+                settings.samples_per_pixel = 400;
+                background = Color::with_value(0.0);
+                lookfrom = Point3::with_values(278.0, 278.0, -800.0);
+                lookat = Point3::with_values(278.0, 278.0, 0.0);
+                vfov = 40.0;
+            }
+            16 => {
+                world = Self::glowing_volumes();
+                settings.samples_per_pixel = 400;
+                background = Color::with_values(0.02, 0.02, 0.04);
+                lookfrom = Point3::with_values(13.0, 3.0, 3.0);
+                lookat = Point3::with_values(0.0, 1.5, 0.0);
+                vfov = 25.0;
+            }
+            17 => {
+                world = Self::dispersion();
+                background = Color::with_value(0.0);
+                lookfrom = Point3::with_values(0.0, 1.0, 10.0);
+                lookat = Point3::with_values(0.0, 1.0, 0.0);
+                vfov = 25.0;
+            }
+            18 => {
+                world = Self::iridescence();
+                background = Color::with_values(0.70, 0.80, 1.00);
+                lookfrom = Point3::with_values(13.0, 3.0, 3.0);
+                lookat = Point3::with_values(0.0, 1.0, 0.0);
+                vfov = 25.0;
+            }
+            19 => {
+                (world, lights) = Self::delta_lights();
+                background = Color::with_value(0.0);
+                lookfrom = Point3::with_values(13.0, 4.0, 3.0);
+                lookat = Point3::with_values(0.0, 1.0, 0.0);
+                vfov = 25.0;
+            }
+            20 => {
+                world = Self::light_temperatures();
+                settings.samples_per_pixel = 400;
+                background = Color::with_value(0.0);
+                lookfrom = Point3::with_values(13.0, 2.0, 3.0);
+                lookat = Point3::with_values(0.0, 1.5, 0.0);
+                vfov = 30.0;
+            }
+            21 => {
+                (world, lights) = Self::ies_lights();
+                settings.samples_per_pixel = 200;
+                background = Color::with_value(0.0);
+                lookfrom = Point3::with_values(0.0, 2.5, 12.0);
+                lookat = Point3::with_values(0.0, 2.0, -2.0);
+                vfov = 35.0;
+            }
+            22 => {
+                (world, lights) = Self::caustics();
+                settings.aspect_ratio = 1.0;
+                settings.set_wdith(600);
+                settings.samples_per_pixel = 200;
+                background = Color::with_value(0.0);
+                lookfrom = Point3::with_values(278.0, 278.0, -800.0);
+                lookat = Point3::with_values(278.0, 278.0, 0.0);
+                vfov = 40.0;
+            }
+            23 => {
+                (world, lights) = Self::planar_shapes();
+                settings.samples_per_pixel = 200;
+                background = Color::with_values(0.02, 0.02, 0.03);
+                lookfrom = Point3::with_values(0.0, 3.0, 10.0);
+                lookat = Point3::with_values(0.0, 1.0, 0.0);
+                vfov = 35.0;
+            }
+            24 => {
+                world = Self::primitives();
+                background = Color::with_values(0.70, 0.80, 1.00);
+                lookfrom = Point3::with_values(0.0, 4.0, 10.0);
+                lookat = Point3::with_values(0.0, 0.8, 0.0);
+                vfov = 35.0;
+            }
+            25 => {
+                world = Self::csg();
+                background = Color::with_values(0.70, 0.80, 1.00);
+                lookfrom = Point3::with_values(0.0, 3.0, 9.0);
+                lookat = Point3::with_values(0.0, 1.0, 0.0);
+                vfov = 30.0;
+            }
+            26 => {
+                world = Self::sdf();
+                background = Color::with_values(0.70, 0.80, 1.00);
+                lookfrom = Point3::with_values(0.0, 3.5, 10.0);
+                lookat = Point3::with_values(0.0, 1.0, 0.0);
+                vfov = 35.0;
+            }
+            27 => {
+                (world, lights) = Self::terrain();
+                background = Color::with_values(0.55, 0.70, 0.95);
+                lookfrom = Point3::with_values(0.0, 9.0, 22.0);
+                lookat = Point3::with_values(0.0, 1.5, 0.0);
+                vfov = 45.0;
+            }
+            28 => {
+                world = Self::animated_transforms();
+                background = Color::with_values(0.70, 0.80, 1.00);
+                lookfrom = Point3::with_values(0.0, 3.0, 10.0);
+                lookat = Point3::with_values(0.0, 1.0, 0.0);
+                vfov = 35.0;
+            }
+            29 => {
+                (world, lights) = Self::forest();
+                background = Color::with_values(0.55, 0.70, 0.95);
+                lookfrom = Point3::with_values(0.0, 6.0, 30.0);
+                lookat = Point3::with_values(0.0, 2.0, 0.0);
+                vfov = 40.0;
+            }
+            30 => {
+                world = Self::scene_graph();
+                background = Color::with_values(0.70, 0.80, 1.00);
+                lookfrom = Point3::with_values(6.0, 5.0, 9.0);
+                lookat = Point3::with_values(0.0, 0.8, 0.0);
+                vfov = 35.0;
+            }
+            31 => {
+                (world, lights) = Self::hair_and_grass();
+                background = Color::with_values(0.55, 0.70, 0.95);
+                lookfrom = Point3::with_values(0.0, 2.0, 7.0);
+                lookat = Point3::with_values(0.0, 0.8, 0.0);
+                vfov = 35.0;
+            }
+            32 => {
+                (world, lights) = Self::meshes();
+                background = Color::with_values(0.55, 0.70, 0.95);
+                lookfrom = Point3::with_values(0.0, 2.5, 6.5);
+                lookat = Point3::with_values(0.0, 0.6, 0.0);
+                vfov = 32.0;
+            }
+            _ => {
+                (world, lights) = Self::final_scene();
+                settings.aspect_ratio = 1.0;
+                settings.set_wdith(800);
+                settings.samples_per_pixel = 10000;
+                background = Color::with_value(0.0);
+                lookfrom = Point3::with_values(478.0, 278.0, -600.0);
+                lookat = Point3::with_values(278.0, 278.0, 0.0);
+                vfov = 40.0;
+            }
+        }
+
+        let camera = Self::setup_camera(lookfrom, lookat, vfov, aperture, &settings);
+        (world, lights, camera, background, settings)
+    }
+
+    fn setup_camera(
+        lookfrom: Point3,
+        lookat: Point3,
+        vfov: f64,
+        aperture: f64,
+        settings: &Settings,
+    ) -> Camera {
+        let vup = Vec3::with_values(0.0, 1.0, 0.0);
+        let dist_to_focus = 10.0;
+        Camera::new(
+            lookfrom,
+            lookat,
+            vup,
+            vfov,
+            settings.aspect_ratio,
+            aperture,
+            dist_to_focus,
+            0.0,
+            1.0,
+        )
+    }
+
+    pub fn bump_mapped_spheres() -> HittableList {
+        let mut objects = HittableList::new();
+
+        let odd = Color::with_values(0.2, 0.3, 0.1);
+        let even = Color::with_values(0.9, 0.9, 0.9);
+        let checker_texture: Arc<Box<dyn Texture>> = rc_box_checker_texture!(odd, even);
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(&checker_texture);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, -1000.0, 0.0),
+            1000.0,
+            &ground
+        ));
+
+        let bumps: Arc<Box<dyn Texture>> = rc_box_noise_texture!(4.0);
+        let clay: Arc<Box<dyn Material>> = rc_box_lambertian!(0.8, 0.5, 0.3);
+        let bumpy_clay: Arc<Box<dyn Material>> = rc_box_bump_map!(&clay, &bumps, 0.05);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, 1.0, -1.2),
+            1.0,
+            &bumpy_clay
+        ));
+
+        let steel: Arc<Box<dyn Material>> = rc_box_metal!(0.8, 0.8, 0.9, 0.0);
+        let hammered_steel: Arc<Box<dyn Material>> = rc_box_bump_map!(&steel, &bumps, 0.02);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, 1.0, 1.2),
+            1.0,
+            &hammered_steel
+        ));
+
+        objects
+    }
+
+    pub fn cutout_fence() -> HittableList {
+        let mut objects = HittableList::new();
+
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.48, 0.83, 0.53);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, -1000.0, 0.0),
+            1000.0,
+            &ground
+        ));
+
+        let ball: Arc<Box<dyn Material>> = rc_box_lambertian!(0.7, 0.3, 0.1);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, 1.0, -2.0),
+            1.0,
+            &ball
+        ));
+
+        let holes: Arc<Box<dyn Texture>> =
+            rc_box_checker_texture!(Color::with_value(0.0), Color::with_value(1.0));
+        let wood: Arc<Box<dyn Material>> = rc_box_lambertian!(0.45, 0.3, 0.15);
+        let fence: Arc<Box<dyn Material>> = rc_box_alpha_mask!(&wood, &holes);
+        objects.add(rc_box_xy_rect!(-3.0, 3.0, 0.0, 2.5, 0.5, &fence));
+
+        objects
+    }
+
+    pub fn mixed_materials() -> HittableList {
+        let mut objects = HittableList::new();
+
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.5);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, -1000.0, 0.0),
+            1000.0,
+            &ground
+        ));
+
+        let red: Arc<Box<dyn Material>> = rc_box_lambertian!(0.65, 0.05, 0.05);
+        let gold: Arc<Box<dyn Material>> = rc_box_metal!(0.8, 0.6, 0.2, 0.1);
+
+        let odd = Color::with_value(0.0);
+        let even = Color::with_value(1.0);
+        let checker_mask: Arc<Box<dyn Texture>> = rc_box_checker_texture!(odd, even);
+        let inlaid: Arc<Box<dyn Material>> = rc_box_mix!(&red, &gold, &checker_mask);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, 1.0, -2.2),
+            1.0,
+            &inlaid
+        ));
+
+        let marble_mask: Arc<Box<dyn Texture>> = rc_box_noise_texture!(4.0);
+        let marbled: Arc<Box<dyn Material>> = rc_box_mix!(&red, &gold, &marble_mask);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, 1.0, 0.0),
+            1.0,
+            &marbled
+        ));
+
+        let car_paint: Arc<Box<dyn Material>> = rc_box_coated!(&red, 1.5);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, 1.0, 2.2),
+            1.0,
+            &car_paint
+        ));
+
+        objects
+    }
+
+    pub fn subsurface_spheres() -> HittableList {
+        let mut objects = HittableList::new();
+
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.5);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, -1000.0, 0.0),
+            1000.0,
+            &ground
+        ));
+
+        let light: Arc<Box<dyn Material>> = rc_box_diffuse_light!(6.0);
+        objects.add(rc_box_xz_rect!(-2.0, 2.0, -2.0, 2.0, 6.0, &light));
+        objects.add(rc_box_yz_rect!(0.5, 2.0, -3.0, 3.0, -4.0, &light));
+
+        let clear: Arc<Box<dyn Material>> = rc_box_dielectric!(1.4);
+
+        let skin: Arc<Box<dyn Hittable>> =
+            rc_box_sphere!(Point3::with_values(0.0, 1.0, -2.2), 1.0, &clear);
+        objects.add(rc_box_subsurface!(
+            &skin,
+            1.4,
+            Color::with_values(0.9, 0.6, 0.45),
+            Color::with_values(0.5, 0.2, 0.1),
+            MeanFreePath
+        ));
+
+        let wax: Arc<Box<dyn Hittable>> =
+            rc_box_sphere!(Point3::with_values(0.0, 1.0, 0.0), 1.0, &clear);
+        objects.add(rc_box_subsurface!(
+            &wax,
+            1.45,
+            Color::with_values(0.97, 0.9, 0.7),
+            Color::with_value(0.25),
+            MeanFreePath
+        ));
+
+        let milk: Arc<Box<dyn Hittable>> =
+            rc_box_sphere!(Point3::with_values(0.0, 1.0, 2.2), 1.0, &clear);
+        objects.add(rc_box_subsurface!(
+            &milk,
+            1.35,
+            Color::with_values(0.999, 0.998, 0.99),
+            Color::with_value(0.15),
+            MeanFreePath
+        ));
+
+        objects
+    }
+
+    pub fn clouds() -> HittableList {
+        let mut objects = HittableList::new();
+
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.48, 0.83, 0.53);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, -1000.0, 0.0),
+            1000.0,
+            &ground
+        ));
+
+        let clear: Arc<Box<dyn Material>> = rc_box_dielectric!(1.0);
+
+        let cloud_boundary: Arc<Box<dyn Hittable>> =
+            rc_box_sphere!(Point3::with_values(0.0, 3.5, -1.5), 2.5, &clear);
+        let cloud_density: Arc<Box<dyn DensityField>> =
+            Arc::new(Box::new(PerlinDensity::new(1.5, 8.0, 0.3)));
+        objects.add(rc_box_heterogeneous_medium!(
+            &cloud_boundary,
+            &cloud_density,
+            Color::with_value(0.95),
+            Color
+        ));
+
+        // NOTE: a plume that spreads out and thins as it rises from (0, 0, 2.5)
+        let plume_boundary: Arc<Box<dyn Hittable>> = rc_box_cuboid!(
+            Point3::with_values(-1.5, 0.0, 1.0),
+            Point3::with_values(1.5, 5.0, 4.0),
+            &clear
+        );
+        let plume_density: Arc<Box<dyn DensityField>> = Arc::new(Box::new(FnDensity::new(
+            |p: &Point3| {
+                let radius = 0.2 + 0.25 * p.y();
+                let r = (p.x() * p.x() + (p.z() - 2.5) * (p.z() - 2.5)).sqrt();
+                3.0 * (1.0 - r / radius).max(0.0) / (1.0 + p.y())
+            },
+            3.0,
+        )));
+        objects.add(rc_box_heterogeneous_medium!(
+            &plume_boundary,
+            &plume_density,
+            Color::with_value(0.3),
+            Color
+        ));
+
+        objects
+    }
+
+    pub fn glowing_volumes() -> HittableList {
+        let mut objects = HittableList::new();
+
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.5);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, -1000.0, 0.0),
+            1000.0,
+            &ground
+        ));
+
+        let clear: Arc<Box<dyn Material>> = rc_box_dielectric!(1.0);
+
+        let fire_boundary: Arc<Box<dyn Hittable>> =
+            rc_box_sphere!(Point3::with_values(0.0, 1.5, -1.2), 1.5, &clear);
+        let fire_density: Arc<Box<dyn DensityField>> =
+            Arc::new(Box::new(PerlinDensity::new(1.5, 10.0, 0.2)));
+        let fire: Arc<Box<dyn Material>> = Arc::new(Box::new(ChromaticPhase::with_color(
+            Color::with_value(0.8),
+            Color::with_value(0.2),
+            Color::with_values(12.0, 4.0, 0.8),
+        )));
+        objects.add(rc_box_heterogeneous_medium!(
+            &fire_boundary,
+            &fire_density,
+            &fire
+        ));
+
+        // NOTE: absorbs red the most, so it tints whatever is seen through it cyan
+        let liquid_boundary: Arc<Box<dyn Hittable>> = rc_box_cuboid!(
+            Point3::with_values(-0.8, 0.0, 1.0),
+            Point3::with_values(0.8, 2.0, 2.6),
+            &clear
+        );
+        objects.add(rc_box_constant_medium!(
+            &liquid_boundary,
+            ChromaticPhase::with_color(
+                Color::with_values(1.2, 0.2, 0.1),
+                Color::with_value(0.3),
+                Color::new()
+            ),
+            Chromatic
+        ));
+
+        objects
+    }
+
+    pub fn dispersion() -> HittableList {
+        let mut objects = HittableList::new();
+
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.5);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, -1000.0, 0.0),
+            1000.0,
+            &ground
+        ));
+
+        // NOTE: sharp light and dark edges behind the glass show off the colour fringes
+        let stripes: Arc<Box<dyn Texture>> =
+            rc_box_checker_texture!(Color::with_value(0.0), Color::with_value(4.0));
+        let backdrop: Arc<Box<dyn Material>> = rc_box_diffuse_light!(&stripes);
+        objects.add(rc_box_xy_rect!(-6.0, 6.0, 0.0, 6.0, -4.0, &backdrop));
+
+        let diamond: Arc<Box<dyn Material>> = rc_box_dielectric!(
+            Dispersion::Sellmeier {
+                b: [4.3356, 0.3306, 0.0],
+                c: [0.1060 * 0.1060, 0.1750 * 0.1750, 0.0],
+            },
+            Dispersion
+        );
+        objects.add(rc_box_sphere!(
+            Point3::with_values(-1.1, 1.0, 0.0),
+            1.0,
+            &diamond
+        ));
+
+        let flint: Arc<Box<dyn Material>> =
+            rc_box_dielectric!(Dispersion::Cauchy { a: 1.67, b: 0.0074 }, Dispersion);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(1.1, 1.0, 0.0),
+            1.0,
+            &flint
+        ));
+
+        objects
+    }
+
+    pub fn iridescence() -> HittableList {
+        let mut objects = HittableList::new();
+
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.5);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, -1000.0, 0.0),
+            1000.0,
+            &ground
+        ));
+
+        let swirls: Arc<Box<dyn Texture>> = rc_box_noise_texture!(2.0);
+
+        let oil_slick: Arc<Box<dyn Material>> = rc_box_metal!(
+            Color::with_value(0.04),
+            0.0,
+            ThinFilm::with_texture(&swirls, 200.0, 900.0, 1.45),
+            ThinFilm
+        );
+        objects.add(rc_box_xz_rect!(-3.0, 3.0, -4.0, 4.0, 0.001, &oil_slick));
+
+        let bubble: Arc<Box<dyn Material>> = rc_box_dielectric!(
+            1.0,
+            ThinFilm::with_texture(&swirls, 250.0, 750.0, 1.33),
+            ThinFilm
+        );
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, 1.2, -1.3),
+            1.0,
+            &bubble
+        ));
+
+        let anodised: Arc<Box<dyn Material>> = rc_box_metal!(
+            Color::with_values(0.55, 0.5, 0.45),
+            0.05,
+            ThinFilm::new(380.0, 2.4),
+            ThinFilm
+        );
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, 1.0, 1.3),
+            1.0,
+            &anodised
+        ));
+
+        objects
+    }
+
+    pub fn delta_lights() -> (HittableList, LightList) {
+        let mut objects = HittableList::new();
+        let mut lights = LightList::new();
+
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.5);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, -1000.0, 0.0),
+            1000.0,
+            &ground
+        ));
+
+        let red: Arc<Box<dyn Material>> = rc_box_lambertian!(0.7, 0.2, 0.2);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, 1.0, -1.5),
+            1.0,
+            &red
+        ));
+
+        let white: Arc<Box<dyn Material>> = rc_box_lambertian!(0.8);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, 1.0, 1.5),
+            1.0,
+            &white
+        ));
+
+        let glass: Arc<Box<dyn Material>> = rc_box_dielectric!(1.5);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(2.0, 0.6, 0.0),
+            0.6,
+            &glass
+        ));
+
+        // NOTE: dim moonlight so the shadowed side isn't pitch black
+        lights.add(rc_box_directional_light!(
+            Vec3::with_values(-1.0, -2.0, -0.5),
+            Color::with_values(0.05, 0.06, 0.1)
+        ));
+        lights.add(rc_box_point_light!(
+            Point3::with_values(3.0, 3.0, -3.0),
+            Color::with_values(20.0, 14.0, 8.0)
+        ));
+        lights.add(rc_box_spot_light!(
+            Point3::with_values(0.0, 6.0, 1.5),
+            Point3::with_values(0.0, 0.0, 1.5),
+            Color::with_values(30.0, 30.0, 40.0),
+            25.0,
+            8.0
+        ));
+
+        (objects, lights)
+    }
+
+    pub fn light_temperatures() -> HittableList {
+        let mut objects = HittableList::new();
+
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.5);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, -1000.0, 0.0),
+            1000.0,
+            &ground
+        ));
+
+        let white: Arc<Box<dyn Material>> = rc_box_lambertian!(0.8);
+        for (z, kelvin) in [(-2.5, 2700.0), (0.0, 4000.0), (2.5, 6500.0)] {
+            objects.add(rc_box_sphere!(
+                Point3::with_values(0.0, 0.8, z),
+                0.8,
+                &white
+            ));
+
+            // NOTE: rects face up, so the flip makes them shine down only
+            let light: Arc<Box<dyn Material>> = rc_box_diffuse_light!(kelvin, 6.0, true, Kelvin);
+            let panel: Arc<Box<dyn Hittable>> = Arc::new(Box::new(XZRect::new(
+                -0.5,
+                0.5,
+                z - 0.5,
+                z + 0.5,
+                3.5,
+                &light,
+            )));
+            objects.add(rc_box_flip_face!(&panel));
+        }
+
+        let bulb: Arc<Box<dyn Material>> = rc_box_diffuse_light!(
+            Color::with_values(1.0, 0.9, 0.8),
+            Power::Lumens(2000.0),
+            4.0 * PI * 0.15 * 0.15,
+            true,
+            Power
+        );
+        objects.add(rc_box_sphere!(
+            Point3::with_values(2.0, 0.5, 1.25),
+            0.15,
+            &bulb
+        ));
+
+        objects
+    }
+
+    pub fn ies_lights() -> (HittableList, LightList) {
+        let mut objects = HittableList::new();
+        let mut lights = LightList::new();
+
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.4);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(0.0, -1000.0, 0.0),
+            1000.0,
+            &ground
+        ));
+
+        let wall: Arc<Box<dyn Material>> = rc_box_lambertian!(0.8, 0.78, 0.75);
+        objects.add(rc_box_xy_rect!(-8.0, 8.0, 0.0, 6.0, -2.0, &wall));
+
+        let profile =
+            Arc::new(IesProfile::parse(DOWNLIGHT_IES).unwrap_or_else(IesProfile::isotropic));
+        let down = Vec3::with_values(0.0, -1.0, 0.0);
+        for x in [-4.0, 0.0, 4.0] {
+            lights.add(rc_box_point_light!(
+                Point3::with_values(x, 4.5, -1.6),
+                Color::with_values(8.0, 7.0, 5.5),
+                &profile,
+                down,
+                IesProfile
+            ));
+        }
+
+        let white: Arc<Box<dyn Material>> = rc_box_lambertian!(0.8);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(2.0, 0.7, 2.0),
+            0.7,
+            &white
+        ));
+
+        let panel_texture: Arc<Box<dyn Texture>> = Arc::new(Box::new(SolidColor::new(
+            Color::with_values(1.0, 0.95, 0.9),
+        )));
+        let panel_light: Arc<Box<dyn Material>> =
+            rc_box_diffuse_light!(&panel_texture, 6.0, &profile, IesProfile);
+        let panel: Arc<Box<dyn Hittable>> =
+            rc_box_xz_rect!(-3.0, -2.0, 1.5, 2.5, 4.0, &panel_light);
+        objects.add(rc_box_flip_face!(&panel));
+
+        (objects, lights)
+    }
+
+    pub fn caustics() -> (HittableList, LightList) {
+        let mut objects = HittableList::new();
+        let mut lights = LightList::new();
+
+        let red: Arc<Box<dyn Material>> = rc_box_lambertian!(0.65, 0.05, 0.05);
+        let white: Arc<Box<dyn Material>> = rc_box_lambertian!(0.73);
+        let green: Arc<Box<dyn Material>> = rc_box_lambertian!(0.12, 0.45, 0.15);
+        let light: Arc<Box<dyn Material>> = rc_box_diffuse_light!(15.0);
+
+        let light: Arc<Box<dyn Hittable>> =
+            rc_box_xz_rect!(213.0, 343.0, 227.0, 332.0, 554.0, &light);
+        lights.add_emitter(Arc::clone(&light));
+
+        objects.add(rc_box_yz_rect!(000.0, 555.0, 000.0, 555.0, 555.0, &green));
+        objects.add(rc_box_yz_rect!(000.0, 555.0, 000.0, 555.0, 000.0, &red));
+        objects.add(light);
+        objects.add(rc_box_xz_rect!(000.0, 555.0, 000.0, 555.0, 000.0, &white));
+        objects.add(rc_box_xz_rect!(000.0, 555.0, 000.0, 555.0, 555.0, &white));
+        objects.add(rc_box_xy_rect!(000.0, 555.0, 000.0, 555.0, 555.0, &white));
+
+        let glass: Arc<Box<dyn Material>> = rc_box_dielectric!(1.5);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(190.0, 90.0, 190.0),
+            90.0,
+            &glass
+        ));
+        objects.add(rc_box_sphere!(
+            Point3::with_values(380.0, 60.0, 300.0),
+            60.0,
+            &rc_box_metal!(0.9, 0.9, 0.9, 0.0)
+        ));
+
+        (objects, lights)
+    }
+
+    pub fn planar_shapes() -> (HittableList, LightList) {
+        let mut objects = HittableList::new();
+        let mut lights = LightList::new();
+
+        let marble: Arc<Box<dyn Texture>> = rc_box_noise_texture!(2.0);
+        objects.add(rc_box_plane!(
+            Point3::with_value(0.0),
+            Vec3::with_values(0.0, 1.0, 0.0),
+            &rc_box_lambertian!(&marble)
+        ));
+
+        let earth: Arc<Box<dyn Material>> =
+            rc_box_lambertian!(&rc_box_image_texture!("earthmap.jpg"));
+        objects.add(rc_box_quad!(
+            Point3::with_values(-2.5, 0.3, -2.0),
+            Vec3::with_values(3.5, 0.0, 0.0),
+            Vec3::with_values(0.0, 1.75, -0.5),
+            &earth
+        ));
+
+        let glass: Arc<Box<dyn Material>> = rc_box_dielectric!(1.5);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(1.8, 0.7, 0.5),
+            0.7,
+            &glass
+        ));
+        let table: Arc<Box<dyn Material>> = rc_box_lambertian!(0.6, 0.3, 0.2);
+        objects.add(rc_box_disk!(
+            Point3::with_values(-1.8, 0.01, 1.2),
+            Vec3::with_values(0.0, 1.0, 0.0),
+            0.9,
+            &table
+        ));
+
+        // NOTE: tilted area lights, no rotation wrappers needed
+        let panel_light: Arc<Box<dyn Material>> = rc_box_diffuse_light!(4.0);
+        let panel: Arc<Box<dyn Hittable>> = rc_box_quad!(
+            Point3::with_values(-3.0, 4.0, 1.0),
+            Vec3::with_values(0.0, -0.8, 1.2),
+            Vec3::with_values(1.5, 0.0, 0.0),
+            &panel_light
+        );
+        lights.add_emitter(Arc::clone(&panel));
+        objects.add(panel);
+
+        let disk_light: Arc<Box<dyn Material>> = rc_box_diffuse_light!(8.0, 6.0, 4.0);
+        let disk: Arc<Box<dyn Hittable>> = rc_box_disk!(
+            Point3::with_values(3.0, 3.0, 2.0),
+            Vec3::with_values(-1.0, -1.0, 0.0),
+            0.6,
+            &disk_light
+        );
+        lights.add_emitter(Arc::clone(&disk));
+        objects.add(disk);
+
+        (objects, lights)
+    }
+
+    pub fn primitives() -> HittableList {
+        let mut objects = HittableList::new();
+
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.5);
+        objects.add(rc_box_plane!(
+            Point3::with_value(0.0),
+            Vec3::with_values(0.0, 1.0, 0.0),
+            &ground
+        ));
+
+        let red: Arc<Box<dyn Material>> = rc_box_lambertian!(0.7, 0.2, 0.2);
+        objects.add(rc_box_cylinder!(
+            Point3::with_values(-3.2, 0.0, 0.0),
+            0.7,
+            1.6,
+            &red
+        ));
+
+        let steel: Arc<Box<dyn Material>> = rc_box_metal!(0.8, 0.8, 0.85, 0.1);
+        objects.add(rc_box_cone!(
+            Point3::with_values(-1.2, 0.0, 0.5),
+            0.8,
+            1.8,
+            &steel
+        ));
+
+        let gold: Arc<Box<dyn Material>> = rc_box_metal!(0.9, 0.7, 0.3, 0.2);
+        objects.add(rc_box_torus!(
+            Point3::with_values(1.0, 0.35, 1.0),
+            0.9,
+            0.35,
+            &gold
+        ));
+
+        let glass: Arc<Box<dyn Material>> = rc_box_dielectric!(1.5);
+        let capsule: Arc<Box<dyn Hittable>> =
+            rc_box_capsule!(Point3::with_values(0.0, 0.5, 0.0), 0.5, 1.2, &glass);
+        objects.add(rc_box_translate!(
+            &capsule,
+            Vec3::with_values(3.2, 0.0, 0.0)
+        ));
+
+        // NOTE: an open tube, turned so its seam faces away
+        let checker: Arc<Box<dyn Texture>> = rc_box_checker_texture!(
+            Color::with_values(0.2, 0.3, 0.1),
+            Color::with_values(0.9, 0.9, 0.9)
+        );
+        let tube: Arc<Box<dyn Hittable>> = rc_box_cylinder!(
+            Point3::with_value(0.0),
+            0.4,
+            0.9,
+            false,
+            &rc_box_lambertian!(&checker)
+        );
+        objects.add(rc_box_translate!(
+            &rc_box_rotate_y!(&tube, 45.0),
+            Vec3::with_values(-0.5, 0.0, 3.0)
+        ));
+
+        objects
+    }
+
+    pub fn csg() -> HittableList {
+        let mut objects = HittableList::new();
+
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.5);
+        objects.add(rc_box_plane!(
+            Point3::with_value(0.0),
+            Vec3::with_values(0.0, 1.0, 0.0),
+            &ground
+        ));
+
+        // NOTE: a sphere with a corner bitten out of it, the bite keeps the box's material
+        let red: Arc<Box<dyn Material>> = rc_box_lambertian!(0.7, 0.2, 0.2);
+        let white: Arc<Box<dyn Material>> = rc_box_lambertian!(0.9);
+        let ball: Arc<Box<dyn Hittable>> =
+            rc_box_sphere!(Point3::with_values(-2.2, 1.0, 0.0), 1.0, &red);
+        let bite: Arc<Box<dyn Hittable>> = rc_box_cuboid!(
+            Point3::with_values(-2.2, 1.0, 0.0),
+            Point3::with_values(-1.0, 2.2, 1.2),
+            &white
+        );
+        objects.add(rc_box_csg!(&ball, &bite, Difference));
+
+        // NOTE: a biconvex lens where two spheres overlap
+        let glass: Arc<Box<dyn Material>> = rc_box_dielectric!(1.5);
+        let front: Arc<Box<dyn Hittable>> =
+            rc_box_sphere!(Point3::with_values(0.0, 1.0, -1.6), 2.0, &glass);
+        let back: Arc<Box<dyn Hittable>> =
+            rc_box_sphere!(Point3::with_values(0.0, 1.0, 1.6), 2.0, &glass);
+        objects.add(rc_box_csg!(&front, &back, Intersection));
+
+        // NOTE: a union is hollow inside, unlike two overlapping objects
+        let gold: Arc<Box<dyn Material>> = rc_box_metal!(0.9, 0.7, 0.3, 0.1);
+        let post: Arc<Box<dyn Hittable>> =
+            rc_box_cylinder!(Point3::with_values(2.2, 0.0, 0.0), 0.4, 1.4, &gold);
+        let knob: Arc<Box<dyn Hittable>> =
+            rc_box_sphere!(Point3::with_values(2.2, 1.6, 0.0), 0.6, &gold);
+        let handle: Arc<Box<dyn Hittable>> = rc_box_csg!(&post, &knob, Union);
+        let core: Arc<Box<dyn Hittable>> =
+            rc_box_cylinder!(Point3::with_values(2.2, -0.1, 0.0), 0.2, 2.5, &white);
+        objects.add(rc_box_csg!(&handle, &core, Difference));
+
+        objects
+    }
+
+    pub fn sdf() -> HittableList {
+        let mut objects = HittableList::new();
+
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.5);
+        objects.add(rc_box_plane!(
+            Point3::with_value(0.0),
+            Vec3::with_values(0.0, 1.0, 0.0),
+            &ground
+        ));
 
-impl Settings {
-    pub fn new() -> Self {
-        let aspect_ratio = 3.0 / 2.0;
-        let image_width = 1200;
-        Self {
-            aspect_ratio,
-            image_width,
-            image_height: (image_width as f64 / aspect_ratio) as u64,
-            samples_per_pixel: 500,
-            max_depth: 50,
-        }
+        // NOTE: a rounded box melting into a ring with a pill scooped out of its top
+        let red: Arc<Box<dyn Material>> = rc_box_lambertian!(0.7, 0.2, 0.2);
+        let rounded: Arc<Box<dyn Sdf>> = Arc::new(Box::new(SdfRoundBox::new(
+            Point3::with_values(-2.5, 0.8, 0.0),
+            Vec3::with_values(0.8, 0.8, 0.8),
+            0.15,
+        )));
+        let ring: Arc<Box<dyn Sdf>> = Arc::new(Box::new(SdfTorus::new(
+            Point3::with_values(-2.5, 0.2, 0.0),
+            1.1,
+            0.2,
+        )));
+        let pill: Arc<Box<dyn Sdf>> = Arc::new(Box::new(SdfCapsule::new(
+            Point3::with_values(-3.3, 1.6, 0.0),
+            Point3::with_values(-1.7, 1.6, 0.0),
+            0.35,
+        )));
+        let blob: Arc<Box<dyn Sdf>> = Arc::new(Box::new(SmoothUnion::new(&rounded, &ring, 0.3)));
+        let scooped: Arc<Box<dyn Sdf>> =
+            Arc::new(Box::new(SmoothSubtraction::new(&blob, &pill, 0.2)));
+        objects.add(rc_box_sdf!(
+            &scooped,
+            AABB::new(
+                Point3::with_values(-3.9, 0.0, -1.4),
+                Point3::with_values(-1.1, 1.7, 1.4)
+            ),
+            &red
+        ));
+
+        // NOTE: a power 8 Mandelbulb, its distance estimate is a plain closure
+        let white: Arc<Box<dyn Material>> = rc_box_lambertian!(0.9);
+        let mandelbulb: Arc<Box<dyn Sdf>> = Arc::new(Box::new(|p: &Point3| {
+            let center = Point3::with_values(0.0, 1.1, 0.0);
+            let c = (*p - center) / 1.1;
+            let mut z = c;
+            let mut dr = 1.0;
+            let mut r = 0.0;
+            for _ in 0..12 {
+                r = z.length();
+                if r > 2.0 {
+                    break;
+                }
+
+                let theta = (z.z() / r).acos() * 8.0;
+                let phi = z.y().atan2(z.x()) * 8.0;
+                dr = 8.0 * r.powi(7) * dr + 1.0;
+                z = r.powi(8)
+                    * Vec3::with_values(
+                        theta.sin() * phi.cos(),
+                        theta.sin() * phi.sin(),
+                        theta.cos(),
+                    )
+                    + c;
+            }
+            1.1 * 0.5 * r.ln() * r / dr
+        }));
+        objects.add(rc_box_sdf!(
+            &mandelbulb,
+            AABB::new(
+                Point3::with_values(-1.3, -0.2, -1.3),
+                Point3::with_values(1.3, 2.4, 1.3)
+            ),
+            &white
+        ));
+
+        // NOTE: a tray of marbles from a single repeated sphere
+        let gold: Arc<Box<dyn Material>> = rc_box_metal!(0.9, 0.7, 0.3, 0.1);
+        let marble: Arc<Box<dyn Sdf>> = Arc::new(Box::new(|p: &Point3| p.length() - 0.2));
+        let marbles: Arc<Box<dyn Sdf>> = Arc::new(Box::new(Repeat::new(
+            &marble,
+            Vec3::with_values(0.5, 0.0, 0.5),
+        )));
+        let shifted: Arc<Box<dyn Sdf>> = Arc::new(Box::new(move |p: &Point3| {
+            marbles.distance(&(*p - Point3::with_values(0.25, 0.2, 0.25)))
+        }));
+        objects.add(rc_box_sdf!(
+            &shifted,
+            AABB::new(
+                Point3::with_values(1.5, 0.0, -1.5),
+                Point3::with_values(3.5, 0.4, 1.5)
+            ),
+            &gold
+        ));
+
+        objects
     }
 
-    pub fn set_wdith(&mut self, width: u64) {
-        self.image_width = width;
-        self.image_height = (self.image_width as f64 / self.aspect_ratio) as u64;
+    pub fn terrain() -> (HittableList, LightList) {
+        let mut objects = HittableList::new();
+        let mut lights = LightList::new();
+
+        // NOTE: a 384 by 384 grid is a single hittable rather than 300k triangles
+        let grass: Arc<Box<dyn Material>> = rc_box_lambertian!(0.35, 0.45, 0.25);
+        objects.add(Arc::new(Box::new(Heightfield::from_fbm(
+            6.0,
+            6,
+            [384, 384],
+            Point3::with_values(-40.0, 0.0, -50.0),
+            Vec3::with_values(80.0, 8.0, 80.0),
+            &grass,
+        ))));
+
+        let water: Arc<Box<dyn Material>> = rc_box_metal!(0.3, 0.4, 0.5, 0.05);
+        objects.add(rc_box_plane!(
+            Point3::with_values(0.0, 3.2, 0.0),
+            Vec3::with_values(0.0, 1.0, 0.0),
+            &water
+        ));
+
+        lights.add(rc_box_directional_light!(
+            Vec3::with_values(-1.0, -0.8, -0.6),
+            Color::with_values(2.5, 2.3, 2.0)
+        ));
+
+        (objects, lights)
     }
-}
 
-pub struct Scene;
-impl Scene {
-    pub fn world_select(world_i: usize) -> (HittableList, Camera, Color, Settings) {
-        let (lookfrom, lookat, background);
-        let (mut vfov, mut aperture) = (40.0, 0.0);
-        let mut settings = Settings::new();
+    pub fn animated_transforms() -> HittableList {
+        let mut objects = HittableList::new();
 
-        let world: HittableList;
-        match world_i {
-            1 => {
-                world = Self::random_scene();
-                background = Color::with_values(0.70, 0.80, 1.00);
-                lookfrom = Point3::with_values(13.0, 2.0, 3.0);
-                lookat = Point3::with_value(0.0);
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.5);
+        objects.add(rc_box_plane!(
+            Point3::with_value(0.0),
+            Vec3::with_values(0.0, 1.0, 0.0),
+            &ground
+        ));
 
-                settings.samples_per_pixel = 50;
-                settings.set_wdith(200);
+        let mut moving = HittableList::new();
+        let up = Vec3::with_values(0.0, 1.0, 0.0);
+
+        // NOTE: a box sliding to the right and lifting off
+        let red: Arc<Box<dyn Material>> = rc_box_lambertian!(0.7, 0.2, 0.2);
+        let cube: Arc<Box<dyn Hittable>> =
+            rc_box_cuboid!(Point3::with_value(-0.5), Point3::with_value(0.5), &red);
+        moving.add(rc_box_animated!(
+            &cube,
+            vec![
+                Keyframe::translation(0.0, Vec3::with_values(-3.2, 0.5, 0.0)),
+                Keyframe::translation(0.6, Vec3::with_values(-2.6, 0.5, 0.0)),
+                Keyframe::translation(1.0, Vec3::with_values(-2.4, 1.0, 0.0)),
+            ]
+        ));
 
-                vfov = 20.0;
-                aperture = 0.1;
-            }
-            2 => {
-                world = Self::two_sphere();
-                background = Color::with_values(0.70, 0.80, 1.00);
-                lookfrom = Point3::with_values(13.0, 2.0, 3.0);
-                lookat = Point3::with_value(0.0);
-                vfov = 20.0;
-            }
-            3 => {
-                world = Self::two_perlin_spheres();
-                background = Color::with_values(0.70, 0.80, 1.00);
-                lookfrom = Point3::with_values(13.0, 2.0, 3.0);
-                lookat = Point3::with_value(0.0);
-                vfov = 20.0;
-            }
-            4 => {
-                world = Self::earth();
-                background = Color::with_values(0.70, 0.80, 1.00);
-                lookfrom = Point3::with_values(13.0, 2.0, 3.0);
-                lookat = Point3::with_value(0.0);
-                vfov = 20.0;
-            }
-            5 => {
-                world = HittableList::new();
-                background = Color::with_value(0.0);
-                lookfrom = Point3::with_value(0.0);
-                lookat = Point3::with_value(0.0);
-            }
-            6 => {
-                world = Self::simple_light();
-                settings.samples_per_pixel = 400;
-                background = Color::with_value(0.0);
-                lookfrom = Point3::with_values(26.0, 3.0, 6.0);
-                lookat = Point3::with_values(0.0, 2.0, 0.0);
-                vfov = 20.0;
-            }
-            7 => {
-                world = Self::cornell_box();
-                settings.aspect_ratio = 1.0;
-                settings.set_wdith(600);
-                settings.samples_per_pixel = 400;
-                background = Color::with_value(0.0);
-                lookfrom = Point3::with_values(278.0, 278.0, -800.0);
-                lookat = Point3::with_values(278.0, 278.0, 0.0);
-                vfov = 40.0;
-            }
-            8 => {
-                world = Self::cornell_smoke();
-                settings.aspect_ratio = 1.0;
-                settings.set_wdith(600);
-                settings.samples_per_pixel = 200;
-                background = Color::with_value(0.0);
-                lookfrom = Point3::with_values(278.0, 278.0, -800.0);
-                lookat = Point3::with_values(278.0, 278.0, 0.0);
-                vfov = 40.0;
+        // NOTE: a ring tumbling forward, its whole silhouette smears
+        let gold: Arc<Box<dyn Material>> = rc_box_metal!(0.9, 0.7, 0.3, 0.1);
+        let ring: Arc<Box<dyn Hittable>> = rc_box_torus!(Point3::with_value(0.0), 0.8, 0.25, &gold);
+        let tilt = Vec3::with_values(1.0, 0.0, 0.0);
+        moving.add(rc_box_animated!(
+            &ring,
+            vec![
+                Keyframe::new(
+                    0.0,
+                    Vec3::with_values(0.0, 1.1, 0.0),
+                    tilt,
+                    60.0,
+                    Vec3::with_value(1.0)
+                ),
+                Keyframe::new(
+                    1.0,
+                    Vec3::with_values(0.0, 1.1, 0.0),
+                    tilt,
+                    130.0,
+                    Vec3::with_value(1.0)
+                ),
+            ]
+        ));
+
+        // NOTE: a post squashing down as it turns
+        let blue: Arc<Box<dyn Material>> = rc_box_lambertian!(0.2, 0.3, 0.7);
+        let post: Arc<Box<dyn Hittable>> = rc_box_cuboid!(
+            Point3::with_values(-0.4, 0.0, -0.4),
+            Point3::with_values(0.4, 1.6, 0.4),
+            &blue
+        );
+        moving.add(rc_box_animated!(
+            &post,
+            vec![
+                Keyframe::new(
+                    0.0,
+                    Vec3::with_values(2.8, 0.0, 0.0),
+                    up,
+                    0.0,
+                    Vec3::with_value(1.0)
+                ),
+                Keyframe::new(
+                    1.0,
+                    Vec3::with_values(2.8, 0.0, 0.0),
+                    up,
+                    45.0,
+                    Vec3::with_values(1.3, 0.6, 1.3)
+                ),
+            ]
+        ));
+
+        objects.add(rc_box_bvh_node!(&mut moving, 0.0, 1.0));
+        objects
+    }
+
+    pub fn forest() -> (HittableList, LightList) {
+        let mut rng = rand::thread_rng();
+        let mut objects = HittableList::new();
+        let mut lights = LightList::new();
+
+        let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.4, 0.45, 0.25);
+        objects.add(rc_box_plane!(
+            Point3::with_value(0.0),
+            Vec3::with_values(0.0, 1.0, 0.0),
+            &ground
+        ));
+
+        // NOTE: one tree with its own BVH, shared by every instance below
+        let bark: Arc<Box<dyn Material>> = rc_box_lambertian!(0.35, 0.25, 0.15);
+        let needles: Arc<Box<dyn Material>> = rc_box_lambertian!(0.1, 0.35, 0.15);
+        let mut tree = HittableList::new();
+        tree.add(rc_box_cylinder!(Point3::with_value(0.0), 0.15, 1.0, &bark));
+        tree.add(rc_box_cone!(
+            Point3::with_values(0.0, 0.6, 0.0),
+            1.0,
+            1.6,
+            &needles
+        ));
+        tree.add(rc_box_cone!(
+            Point3::with_values(0.0, 1.5, 0.0),
+            0.75,
+            1.4,
+            &needles
+        ));
+        tree.add(rc_box_cone!(
+            Point3::with_values(0.0, 2.3, 0.0),
+            0.5,
+            1.2,
+            &needles
+        ));
+        let tree: Arc<Box<dyn Hittable>> = rc_box_bvh_node!(&mut tree, 0.0, 1.0);
+
+        let rock: Arc<Box<dyn Hittable>> = rc_box_sphere!(Point3::with_value(0.0), 1.0, &bark);
+
+        let mut instances = HittableList::new();
+        for _ in 0..5000 {
+            let x = rng.gen_range(-60.0..60.0);
+            let z = rng.gen_range(-80.0..18.0);
+            let size = rng.gen_range(0.6..1.5);
+            let lean = Vec3::with_values(rng.gen_range(-1.0..1.0), 0.0, rng.gen_range(-1.0..1.0));
+            let transform = Transform::new(
+                Point3::with_values(x, 0.0, z),
+                lean,
+                rng.gen_range(0.0..6.0),
+                Vec3::with_values(size, size * rng.gen_range(0.8..1.3), size),
+            );
+            instances.add(rc_box_instance!(&tree, transform));
+        }
+
+        // NOTE: squashed, turned copies of one sphere, each in its own shade of stone
+        for _ in 0..1000 {
+            let size = rng.gen_range(0.2..0.6);
+            let transform = Transform::new(
+                Point3::with_values(rng.gen_range(-40.0..40.0), 0.0, rng.gen_range(-60.0..22.0)),
+                Vec3::random_range(-1.0, 1.0),
+                rng.gen_range(0.0..360.0),
+                Vec3::with_values(size * 1.6, size * 0.7, size),
+            );
+            let stone: Arc<Box<dyn Material>> =
+                rc_box_lambertian!(Color, Color::with_value(rng.gen_range(0.3..0.6)));
+            instances.add(rc_box_instance!(&rock, transform, &stone));
+        }
+        objects.add(rc_box_bvh_node!(&mut instances, 0.0, 1.0));
+
+        lights.add(rc_box_directional_light!(
+            Vec3::with_values(-1.0, -1.2, -0.8),
+            Color::with_values(2.5, 2.3, 2.0)
+        ));
+
+        (objects, lights)
+    }
+
+    pub fn scene_graph() -> HittableList {
+        let wood: Arc<Box<dyn Material>> = rc_box_lambertian!(0.45, 0.3, 0.15);
+        let glass: Arc<Box<dyn Material>> = rc_box_dielectric!(1.5);
+        let up = Vec3::with_values(0.0, 1.0, 0.0);
+
+        let mut root = SceneNode::new("room");
+        let mut floor = SceneNode::new("floor");
+        floor.material = Some(rc_box_lambertian!(0.5));
+        floor.add_object(rc_box_plane!(Point3::with_value(0.0), up, &wood));
+        root.add_child(floor);
+
+        // NOTE: legs are placed relative to the table, which is turned as a whole
+        let mut table = SceneNode::with_transform(
+            "table",
+            Transform::new(Vec3::new(), up, 20.0, Vec3::with_value(1.0)),
+        );
+        table.material = Some(Arc::clone(&wood));
+        let mut top = SceneNode::new("top");
+        top.add_object(rc_box_cuboid!(
+            Point3::with_values(-1.5, 1.0, -0.9),
+            Point3::with_values(1.5, 1.1, 0.9),
+            &wood
+        ));
+        table.add_child(top);
+        let mut legs = SceneNode::new("legs");
+        for (x, z) in [(-1.3, -0.7), (1.3, -0.7), (-1.3, 0.7), (1.3, 0.7)] {
+            legs.add_object(rc_box_cylinder!(
+                Point3::with_values(x, 0.0, z),
+                0.08,
+                1.0,
+                &wood
+            ));
+        }
+        table.add_child(legs);
+        let mut vase = SceneNode::new("vase");
+        vase.material = Some(Arc::clone(&glass));
+        vase.add_object(rc_box_capsule!(
+            Point3::with_values(0.3, 1.35, 0.0),
+            0.25,
+            0.5,
+            &glass
+        ));
+        table.add_child(vase);
+        root.add_child(table);
+
+        // NOTE: two chairs from the same builder, placed by their node's transform
+        let mut chairs = SceneNode::new("chairs");
+        for (name, x, angle) in [("chair-left", -2.3, 90.0), ("chair-right", 2.3, -90.0)] {
+            let mut chair = SceneNode::with_transform(
+                name,
+                Transform::new(
+                    Vec3::with_values(x, 0.0, 0.0),
+                    up,
+                    angle,
+                    Vec3::with_value(1.0),
+                ),
+            );
+            chair.add_object(rc_box_cuboid!(
+                Point3::with_values(-0.45, 0.55, -0.45),
+                Point3::with_values(0.45, 0.62, 0.45),
+                &wood
+            ));
+            chair.add_object(rc_box_cuboid!(
+                Point3::with_values(-0.45, 0.62, -0.45),
+                Point3::with_values(0.45, 1.5, -0.38),
+                &wood
+            ));
+            for (lx, lz) in [(-0.4, -0.4), (0.4, -0.4), (-0.4, 0.4), (0.4, 0.4)] {
+                chair.add_object(rc_box_cuboid!(
+                    Point3::with_values(lx - 0.04, 0.0, lz - 0.04),
+                    Point3::with_values(lx + 0.04, 0.55, lz + 0.04),
+                    &wood
+                ));
             }
-            _ => {
-                world = Self::final_scene();
-                settings.aspect_ratio = 1.0;
-                settings.set_wdith(800);
-                settings.samples_per_pixel = 10000;
-                background = Color::with_value(0.0);
-                lookfrom = Point3::with_values(478.0, 278.0, -600.0);
-                lookat = Point3::with_values(278.0, 278.0, 0.0);
-                vfov = 40.0;
+            chairs.add_child(chair);
+        }
+        root.add_child(chairs);
+
+        let mut ball = SceneNode::new("ball");
+        ball.add_object(rc_box_sphere!(
+            Point3::with_value(0.0),
+            0.3,
+            &rc_box_metal!(0.8, 0.8, 0.8, 0.0)
+        ));
+        root.add_child(ball);
+
+        // NOTE: edits by name, as a tool would make them, before flattening for the render
+        if let Some(chairs) = root.find_mut("chairs") {
+            chairs.material = Some(rc_box_lambertian!(0.2, 0.3, 0.6));
+        }
+        if let Some(chair) = root.find_mut("chair-right") {
+            chair.transform = Transform::new(
+                Vec3::with_values(2.6, 0.0, 0.8),
+                up,
+                -60.0,
+                Vec3::with_value(1.0),
+            );
+        }
+        if let Some(ball) = root.find_mut("ball") {
+            ball.transform.translation = Vec3::with_values(1.0, 0.3, 2.0);
+        }
+        if let Some(vase) = root.find_mut("vase") {
+            vase.material = Some(rc_box_metal!(0.9, 0.7, 0.3, 0.1));
+        }
+
+        root.flatten()
+    }
+
+    pub fn hair_and_grass() -> (HittableList, LightList) {
+        let mut rng = rand::thread_rng();
+        let mut objects = HittableList::new();
+        let mut lights = LightList::new();
+
+        let soil: Arc<Box<dyn Material>> = rc_box_lambertian!(0.3, 0.22, 0.15);
+        objects.add(rc_box_plane!(
+            Point3::with_value(0.0),
+            Vec3::with_values(0.0, 1.0, 0.0),
+            &soil
+        ));
+
+        // NOTE: tapering blades leaning every which way, in a few shades of green
+        let greens: Vec<Arc<Box<dyn Material>>> = (0..4)
+            .map(|i| -> Arc<Box<dyn Material>> {
+                let shade = 0.8 + 0.1 * i as f64;
+                rc_box_lambertian!(Color, shade * Color::with_values(0.2, 0.45, 0.1))
+            })
+            .collect();
+        let mut curves = HittableList::new();
+        for _ in 0..30000 {
+            let base = Point3::with_values(rng.gen_range(-6.0..6.0), 0.0, rng.gen_range(-6.0..3.0));
+            let height = rng.gen_range(0.15..0.45);
+            let lean = height
+                * rng.gen_range(0.1..0.6)
+                * Vec3::with_values(rng.gen_range(-1.0..1.0), 0.0, rng.gen_range(-1.0..1.0));
+            let blade = [
+                base,
+                base + Vec3::with_values(0.0, height / 3.0, 0.0),
+                base + 0.4 * lean + Vec3::with_values(0.0, 2.0 * height / 3.0, 0.0),
+                base + lean + Vec3::with_values(0.0, 0.9 * height, 0.0),
+            ];
+            let green = &greens[rng.gen_range(0..greens.len())];
+            curves
+                .objects
+                .extend(Curve::split(blade, 0.02, 0.0, CurveType::Flat, green, 2).objects);
+        }
+
+        // NOTE: fur balls of brown hair and dyed hair, each strand drooping under its weight
+        let furs: [(Point3, Arc<Box<dyn Material>>); 2] = [
+            (
+                Point3::with_values(-0.9, 0.7, 0.0),
+                rc_box_hair!(Melanin, 1.3, 0.2, 0.3, 0.3),
+            ),
+            (
+                Point3::with_values(0.9, 0.7, 0.0),
+                rc_box_hair!(Color, Color::with_values(0.1, 0.35, 0.8), 0.3, 0.3),
+            ),
+        ];
+        let skin: Arc<Box<dyn Material>> = rc_box_lambertian!(0.2, 0.15, 0.1);
+        for (center, fur) in furs.iter() {
+            objects.add(rc_box_sphere!(*center, 0.55, &skin));
+            for _ in 0..20000 {
+                let normal = random_unit_vector();
+                let root = *center + 0.55 * normal;
+                let length = rng.gen_range(0.2..0.3);
+                let droop = Vec3::with_values(0.0, -0.5 * length, 0.0);
+                let strand = [
+                    root,
+                    root + (length / 3.0) * normal,
+                    root + (2.0 * length / 3.0) * normal + 0.4 * droop,
+                    root + length * normal + droop,
+                ];
+                curves
+                    .objects
+                    .extend(Curve::split(strand, 0.006, 0.002, CurveType::Flat, fur, 2).objects);
             }
         }
+        objects.add(rc_box_bvh_node!(&mut curves, 0.0, 1.0));
 
-        let camera = Self::setup_camera(lookfrom, lookat, vfov, aperture, &settings);
-        (world, camera, background, settings)
+        lights.add(rc_box_directional_light!(
+            Vec3::with_values(-1.0, -1.5, -1.0),
+            Color::with_values(2.5, 2.3, 2.0)
+        ));
+
+        (objects, lights)
     }
 
-    fn setup_camera(
-        lookfrom: Point3,
-        lookat: Point3,
-        vfov: f64,
-        aperture: f64,
-        settings: &Settings,
-    ) -> Camera {
-        let vup = Vec3::with_values(0.0, 1.0, 0.0);
-        let dist_to_focus = 10.0;
-        Camera::new(
-            lookfrom,
-            lookat,
-            vup,
-            vfov,
-            settings.aspect_ratio,
-            aperture,
-            dist_to_focus,
-            0.0,
-            1.0,
-        )
+    pub fn meshes() -> (HittableList, LightList) {
+        let mut objects = HittableList::new();
+        let mut lights = LightList::new();
+
+        let floor: Arc<Box<dyn Material>> = rc_box_lambertian!(0.5);
+        objects.add(rc_box_plane!(
+            Point3::with_value(0.0),
+            Vec3::with_values(0.0, 1.0, 0.0),
+            &floor
+        ));
+
+        // NOTE: vertex colors are blended across the faces of a scanned mesh
+        let painted: Arc<Box<dyn Material>> =
+            rc_box_lambertian!(&rc_box_vertex_color_texture!(Color::with_value(0.5)));
+        let cube: Arc<Box<dyn Hittable>> = Arc::new(Box::new(TriangleMesh::new(
+            ply::parse(RGB_CUBE_PLY.as_bytes()).unwrap_or_default(),
+            &painted,
+        )));
+        objects.add(rc_box_instance!(
+            &cube,
+            Transform::new(
+                Vec3::with_values(-1.8, 0.75, 0.0),
+                Vec3::with_values(1.0, 1.0, 0.0),
+                50.0,
+                Vec3::with_value(1.1)
+            )
+        ));
+
+        let steel: Arc<Box<dyn Material>> = rc_box_metal!(0.8, 0.8, 0.85, 0.15);
+        objects.add(rc_box_triangle_mesh!(
+            stl::parse(PYRAMID_STL.as_bytes()).unwrap_or_default(),
+            &steel
+        ));
+
+        // NOTE: a mesh built in code, with smooth normals and a rainbow around the ring
+        let (rings, sides) = (96, 32);
+        let mut torus = MeshData::default();
+        for i in 0..rings {
+            let phi = 2.0 * PI * i as f64 / rings as f64;
+            let hue = Color::with_values(
+                phi.cos(),
+                (phi - 2.0 * PI / 3.0).cos(),
+                (phi + 2.0 * PI / 3.0).cos(),
+            );
+            for j in 0..sides {
+                let theta = 2.0 * PI * j as f64 / sides as f64;
+                let out = Vec3::with_values(phi.cos(), 0.0, phi.sin());
+                let normal = theta.cos() * out + Vec3::with_values(0.0, theta.sin(), 0.0);
+                torus.positions.push(0.55 * out + 0.22 * normal);
+                torus.normals.push(normal);
+                torus.colors.push(0.5 * Color::with_value(1.0) + 0.4 * hue);
+
+                let next_i = (i + 1) % rings;
+                let next_j = (j + 1) % sides;
+                let (a, b) = (i * sides + j, next_i * sides + j);
+                let (c, d) = (next_i * sides + next_j, i * sides + next_j);
+                torus.triangles.push([a, c, b]);
+                torus.triangles.push([a, d, c]);
+            }
+        }
+        let ring: Arc<Box<dyn Hittable>> = rc_box_triangle_mesh!(torus, &painted);
+        objects.add(rc_box_instance!(
+            &ring,
+            Transform::new(
+                Vec3::with_values(1.8, 0.7, 0.0),
+                Vec3::with_values(1.0, 0.0, 0.0),
+                70.0,
+                Vec3::with_value(1.0)
+            )
+        ));
+
+        lights.add(rc_box_directional_light!(
+            Vec3::with_values(-1.0, -1.5, -1.0),
+            Color::with_values(2.5, 2.3, 2.0)
+        ));
+
+        (objects, lights)
     }
 
-    pub fn final_scene() -> HittableList {
+    pub fn final_scene() -> (HittableList, LightList) {
         let mut rng = rand::thread_rng();
         let mut boxes1 = HittableList::new();
         let ground: Arc<Box<dyn Material>> = rc_box_lambertian!(0.48, 0.83, 0.53);
@@ -197,10 +1746,14 @@ impl Scene {
         }
 
         let mut objects = HittableList::new();
+        let mut lights = LightList::new();
         objects.add(rc_box_bvh_node!(&mut boxes1, 0.0, 1.0));
 
         let light: Arc<Box<dyn Material>> = rc_box_diffuse_light!(7.0);
-        objects.add(rc_box_xz_rect!(123.0, 423.0, 147.0, 412.0, 554.0, &light));
+        let light: Arc<Box<dyn Hittable>> =
+            rc_box_xz_rect!(123.0, 423.0, 147.0, 412.0, 554.0, &light);
+        lights.add_emitter(Arc::clone(&light));
+        objects.add(light);
 
         let center1 = Point3::with_values(400.0, 400.0, 200.0);
         let center2 = center1 + Point3::with_values(30.0, 0.0, 0.0);
@@ -275,20 +1828,25 @@ impl Scene {
             Vec3::with_values(-100.0, 270.0, 395.0)
         ));
 
-        objects
+        (objects, lights)
     }
 
-    pub fn cornell_smoke() -> HittableList {
+    pub fn cornell_smoke() -> (HittableList, LightList) {
         let mut objects = HittableList::new();
+        let mut lights = LightList::new();
 
         let red: Arc<Box<dyn Material>> = rc_box_lambertian!(0.65, 0.05, 0.05);
         let white: Arc<Box<dyn Material>> = rc_box_lambertian!(0.73);
         let green: Arc<Box<dyn Material>> = rc_box_lambertian!(0.12, 0.45, 0.15);
         let light: Arc<Box<dyn Material>> = rc_box_diffuse_light!(7.0);
 
+        let light: Arc<Box<dyn Hittable>> =
+            rc_box_xz_rect!(113.0, 443.0, 127.0, 432.0, 554.0, &light);
+        lights.add_emitter(Arc::clone(&light));
+
         objects.add(rc_box_yz_rect!(000.0, 555.0, 000.0, 555.0, 555.0, &green));
         objects.add(rc_box_yz_rect!(000.0, 555.0, 000.0, 555.0, 000.0, &red));
-        objects.add(rc_box_xz_rect!(113.0, 443.0, 127.0, 432.0, 554.0, &light));
+        objects.add(light);
         objects.add(rc_box_xz_rect!(000.0, 555.0, 000.0, 555.0, 555.0, &white));
         objects.add(rc_box_xz_rect!(000.0, 555.0, 000.0, 555.0, 000.0, &white));
         objects.add(rc_box_xy_rect!(000.0, 555.0, 000.0, 555.0, 555.0, &white));
@@ -319,20 +1877,94 @@ impl Scene {
             Color
         ));
 
+        (objects, lights)
+    }
+
+    pub fn cornell_fog() -> (HittableList, LightList) {
+        let (mut objects, lights) = Self::cornell_box();
+
+        let clear: Arc<Box<dyn Material>> = rc_box_dielectric!(1.0);
+        let room: Arc<Box<dyn Hittable>> =
+            rc_box_cuboid!(Point3::with_value(1.0), Point3::with_value(553.0), &clear);
+        // NOTE: mostly forward scattering, with some light thrown back towards the lamp
+        objects.add(rc_box_constant_medium!(
+            &room,
+            0.0015,
+            Color::with_value(0.9),
+            0.8,
+            -0.3,
+            0.85,
+            DualLobe
+        ));
+
+        (objects, lights)
+    }
+
+    pub fn cornell_media() -> HittableList {
+        let mut objects = HittableList::new();
+
+        let red: Arc<Box<dyn Material>> = rc_box_lambertian!(0.65, 0.05, 0.05);
+        let white: Arc<Box<dyn Material>> = rc_box_lambertian!(0.73);
+        let green: Arc<Box<dyn Material>> = rc_box_lambertian!(0.12, 0.45, 0.15);
+        let light: Arc<Box<dyn Material>> = rc_box_diffuse_light!(7.0);
+
+        objects.add(rc_box_yz_rect!(000.0, 555.0, 000.0, 555.0, 555.0, &green));
+        objects.add(rc_box_yz_rect!(000.0, 555.0, 000.0, 555.0, 000.0, &red));
+        objects.add(rc_box_xz_rect!(113.0, 443.0, 127.0, 432.0, 554.0, &light));
+        objects.add(rc_box_xz_rect!(000.0, 555.0, 000.0, 555.0, 555.0, &white));
+        objects.add(rc_box_xz_rect!(000.0, 555.0, 000.0, 555.0, 000.0, &white));
+        objects.add(rc_box_xy_rect!(000.0, 555.0, 000.0, 555.0, 555.0, &white));
+
+        // NOTE: two overlapping boxes make a non-convex L shaped boundary
+        let mut l_shape = HittableList::new();
+        l_shape.add(rc_box_cuboid!(
+            Point3::with_value(0.0),
+            Point3::with_values(165.0, 330.0, 80.0),
+            &white
+        ));
+        l_shape.add(rc_box_cuboid!(
+            Point3::with_value(0.0),
+            Point3::with_values(165.0, 80.0, 240.0),
+            &white
+        ));
+        let mut smoke: Arc<Box<dyn Hittable>> = Arc::new(Box::new(l_shape));
+        smoke = rc_box_rotate_y!(&smoke, 15.0);
+        smoke = rc_box_translate!(&smoke, Vec3::with_values(265.0, 0.0, 250.0));
+        objects.add(rc_box_constant_medium!(
+            &smoke,
+            0.01,
+            Color::with_value(0.0),
+            Color
+        ));
+
+        let murk: Arc<Box<dyn Medium>> =
+            rc_box_homogeneous_medium!(0.02, Color::with_values(0.2, 0.4, 0.9), Color);
+        let murky_glass: Arc<Box<dyn Material>> = rc_box_dielectric!(1.5, &murk);
+        objects.add(rc_box_sphere!(
+            Point3::with_values(190.0, 90.0, 190.0),
+            90.0,
+            &murky_glass
+        ));
+
         objects
     }
 
-    pub fn cornell_box() -> HittableList {
+    pub fn cornell_box() -> (HittableList, LightList) {
         let mut objects = HittableList::new();
+        let mut lights = LightList::new();
 
         let red: Arc<Box<dyn Material>> = rc_box_lambertian!(0.65, 0.05, 0.05);
         let white: Arc<Box<dyn Material>> = rc_box_lambertian!(0.73);
         let green: Arc<Box<dyn Material>> = rc_box_lambertian!(0.12, 0.45, 0.15);
         let light: Arc<Box<dyn Material>> = rc_box_diffuse_light!(15.0);
 
+        let light: Arc<Box<dyn Hittable>> =
+            rc_box_xz_rect!(213.0, 343.0, 227.0, 332.0, 554.0, &light);
+        lights.add_emitter(Arc::clone(&light));
+
         objects.add(rc_box_yz_rect!(000.0, 555.0, 000.0, 555.0, 555.0, &green));
         objects.add(rc_box_yz_rect!(000.0, 555.0, 000.0, 555.0, 000.0, &red));
-        objects.add(rc_box_xz_rect!(213.0, 343.0, 227.0, 332.0, 554.0, &light));
+        objects.add(light);
         objects.add(rc_box_xz_rect!(000.0, 555.0, 000.0, 555.0, 000.0, &white));
         objects.add(rc_box_xz_rect!(000.0, 555.0, 000.0, 555.0, 555.0, &white));
         objects.add(rc_box_xy_rect!(000.0, 555.0, 000.0, 555.0, 555.0, &white));
@@ -352,11 +1984,12 @@ impl Scene {
         box2 = rc_box_translate!(&box2, Vec3::with_values(130.0, 0.0, 65.0));
         objects.add(box2);
 
-        objects
+        (objects, lights)
     }
 
-    pub fn simple_light() -> HittableList {
+    pub fn simple_light() -> (HittableList, LightList) {
         let mut objects = HittableList::new();
+        let mut lights = LightList::new();
 
         let perlin_texture: Arc<Box<dyn Texture>> = rc_box_noise_texture!(4.0);
         let perlin: Arc<Box<dyn Material>> = rc_box_lambertian!(&perlin_texture);
@@ -370,9 +2003,10 @@ impl Scene {
 
         let difflight: Arc<Box<dyn Material>> = rc_box_diffuse_light!(4.0);
         let xy_rect: Arc<Box<dyn Hittable>> = rc_box_xy_rect!(3.0, 5.0, 1.0, 3.0, -2.0, &difflight);
+        lights.add_emitter(Arc::clone(&xy_rect));
         objects.add(xy_rect);
 
-        objects
+        (objects, lights)
     }
 
     pub fn earth() -> HittableList {
@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use crate::{
+    aabb::AABB,
+    hittables::{
+        bvh::BVHNode,
+        hittable::{Hittable, HittableList},
+        instance::Instance,
+    },
+    materials::material::Material,
+    transform::Transform,
+};
+
+/// A named group in a scene graph. Its `objects` and `children` are placed by `transform`
+/// relative to the parent, and a `material` here is used for everything below that doesn't
+/// set its own. Nodes can be looked up by name to hide, move or re-material them, then the
+/// graph is flattened again for rendering.
+pub struct SceneNode {
+    pub name: String,
+    pub transform: Transform,
+    pub material: Option<Arc<Box<dyn Material>>>,
+    pub visible: bool,
+    pub objects: Vec<Arc<Box<dyn Hittable>>>,
+    pub children: Vec<SceneNode>,
+}
+
+impl SceneNode {
+    pub fn new(name: &str) -> Self {
+        Self::with_transform(name, Transform::identity())
+    }
+
+    pub fn with_transform(name: &str, transform: Transform) -> Self {
+        Self {
+            name: name.to_string(),
+            transform,
+            material: None,
+            visible: true,
+            objects: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn add_object(&mut self, object: Arc<Box<dyn Hittable>>) {
+        self.objects.push(object);
+    }
+
+    pub fn add_child(&mut self, child: SceneNode) {
+        self.children.push(child);
+    }
+
+    /// The first node called `name`, searching depth first from this one.
+    pub fn find(&self, name: &str) -> Option<&SceneNode> {
+        if self.name == name {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(name))
+    }
+
+    pub fn find_mut(&mut self, name: &str) -> Option<&mut SceneNode> {
+        if self.name == name {
+            return Some(self);
+        }
+        self.children
+            .iter_mut()
+            .find_map(|child| child.find_mut(name))
+    }
+
+    /// Everything visible in the graph, ready to render. Untransformed groups are merged
+    /// into their parent so they share its `BVHNode`, transformed ones become an `Instance`
+    /// of a BVH over their own contents.
+    pub fn flatten(&self) -> HittableList {
+        HittableList {
+            objects: group(self.build(None)),
+        }
+    }
+
+    /// This node's contents, placed in its parent's space.
+    fn build(&self, inherited: Option<&Arc<Box<dyn Material>>>) -> Vec<Arc<Box<dyn Hittable>>> {
+        if !self.visible {
+            return Vec::new();
+        }
+
+        // NOTE: the override goes on each object, so the nearest node's material wins
+        let material = self.material.as_ref().or(inherited);
+        let mut pieces: Vec<Arc<Box<dyn Hittable>>> = self
+            .objects
+            .iter()
+            .map(|object| -> Arc<Box<dyn Hittable>> {
+                match material {
+                    Some(material) => Arc::new(Box::new(Instance::new(
+                        object,
+                        Transform::identity(),
+                        Some(material),
+                    ))),
+                    None => Arc::clone(object),
+                }
+            })
+            .collect();
+        for child in &self.children {
+            pieces.extend(child.build(material));
+        }
+
+        if self.transform.is_identity() || pieces.is_empty() {
+            return pieces;
+        }
+
+        let mut pieces = group(pieces);
+        let content: Arc<Box<dyn Hittable>> = if pieces.len() == 1 {
+            pieces.remove(0)
+        } else {
+            Arc::new(Box::new(HittableList { objects: pieces }))
+        };
+        vec![Arc::new(Box::new(Instance::new(
+            &content,
+            self.transform,
+            None,
+        )))]
+    }
+}
+
+/// A BVH over the bounded `pieces`, alongside the unbounded ones it can't hold.
+fn group(pieces: Vec<Arc<Box<dyn Hittable>>>) -> Vec<Arc<Box<dyn Hittable>>> {
+    let (bounded, mut unbounded): (Vec<_>, Vec<_>) = pieces
+        .into_iter()
+        .partition(|piece| piece.bounding_box(0.0, 1.0, &mut AABB::default()));
+
+    match bounded.len() {
+        0 => {}
+        1 => unbounded.extend(bounded),
+        _ => {
+            let mut bounded = HittableList { objects: bounded };
+            unbounded.push(Arc::new(Box::new(BVHNode::new(&mut bounded, 0.0, 1.0))));
+        }
+    }
+    unbounded
+}
@@ -0,0 +1,110 @@
+use std::sync::OnceLock;
+
+use rand::Rng;
+
+use crate::vec3::{Color, Vec3};
+
+/// Visible range sampled by the spectral mode, in nanometres.
+pub const LAMBDA_MIN: f64 = 380.0;
+pub const LAMBDA_MAX: f64 = 780.0;
+
+pub fn sample_wavelength() -> f64 {
+    rand::thread_rng().gen_range(LAMBDA_MIN..LAMBDA_MAX)
+}
+
+/// Value at `lambda` of a smooth spectrum for an RGB colour. The three basis spectra sum to one
+/// everywhere, so white stays flat and reflectances stay within `[0, 1]`.
+pub fn rgb_to_spectrum(color: &Color, lambda: f64) -> f64 {
+    let blue_green = smoothstep(480.0, 510.0, lambda);
+    let green_red = smoothstep(570.0, 600.0, lambda);
+
+    color.z() * (1.0 - blue_green) + color.y() * (blue_green - green_red) + color.x() * green_red
+}
+
+/// CIE 1931 colour matching functions at `lambda`, using the multi-lobe Gaussian fit from
+/// Wyman, Sloan and Shirley, "Simple Analytic Approximations to the CIE XYZ Color Matching
+/// Functions".
+pub fn cie_xyz(lambda: f64) -> Vec3 {
+    let g = |mu: f64, sigma1: f64, sigma2: f64| {
+        let t = (lambda - mu) / if lambda < mu { sigma1 } else { sigma2 };
+        (-0.5 * t * t).exp()
+    };
+
+    Vec3::with_values(
+        1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2),
+        0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1),
+        1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8),
+    )
+}
+
+pub fn xyz_to_linear_srgb(xyz: &Vec3) -> Color {
+    Color::with_values(
+        3.2406 * xyz.x() - 1.5372 * xyz.y() - 0.4986 * xyz.z(),
+        -0.9689 * xyz.x() + 1.8758 * xyz.y() + 0.0415 * xyz.z(),
+        0.0557 * xyz.x() - 0.2040 * xyz.y() + 1.0570 * xyz.z(),
+    )
+}
+
+/// Linear sRGB estimate for a radiance `value` carried at a uniformly sampled `lambda`.
+/// Averaged over many wavelengths a flat spectrum of one comes out as white `(1, 1, 1)`.
+pub fn spectral_to_rgb(value: f64, lambda: f64) -> Color {
+    let xyz = value * (LAMBDA_MAX - LAMBDA_MIN) * cie_xyz(lambda);
+    xyz_to_linear_srgb(&xyz) / *white_point()
+}
+
+/// Relative luminance of a linear sRGB colour.
+pub fn luminance(color: &Color) -> f64 {
+    0.2126 * color.x() + 0.7152 * color.y() + 0.0722 * color.z()
+}
+
+/// Spectral radiance of a black body at `kelvin` for `lambda` in nanometres, from Planck's law.
+pub fn blackbody(lambda: f64, kelvin: f64) -> f64 {
+    const C: f64 = 299_792_458.0;
+    const H: f64 = 6.626_070_15e-34;
+    const KB: f64 = 1.380_649e-23;
+
+    let l = lambda * 1e-9;
+    (2.0 * H * C * C) / (l.powi(5) * ((H * C / (l * KB * kelvin)).exp() - 1.0))
+}
+
+/// White balanced linear sRGB colour of a black body at `kelvin`, scaled to a luminance of one.
+/// Colours outside the sRGB gamut, like the deep red of very low temperatures, are clipped, and
+/// temperatures too low to give off visible light are black.
+pub fn blackbody_rgb(kelvin: f64) -> Color {
+    let steps = 400;
+    let step = (LAMBDA_MAX - LAMBDA_MIN) / steps as f64;
+    let xyz: Vec3 = (0..steps)
+        .map(|i| {
+            let lambda = LAMBDA_MIN + (i as f64 + 0.5) * step;
+            blackbody(lambda, kelvin) * cie_xyz(lambda)
+        })
+        .sum();
+
+    let rgb = xyz_to_linear_srgb(&xyz) / *white_point();
+    let rgb = Color::with_values(rgb.x().max(0.0), rgb.y().max(0.0), rgb.z().max(0.0));
+
+    // NOTE: bodies too cold to glow visibly underflow to nothing, keep them black
+    let luma = luminance(&rgb);
+    if !(luma > 0.0 && luma.is_finite()) {
+        return Color::new();
+    }
+    rgb / luma
+}
+
+/// Linear sRGB of a flat spectrum, used to white balance `spectral_to_rgb`.
+fn white_point() -> &'static Color {
+    static WHITE: OnceLock<Color> = OnceLock::new();
+    WHITE.get_or_init(|| {
+        let steps = 4000;
+        let step = (LAMBDA_MAX - LAMBDA_MIN) / steps as f64;
+        let xyz: Vec3 = (0..steps)
+            .map(|i| step * cie_xyz(LAMBDA_MIN + (i as f64 + 0.5) * step))
+            .sum();
+        xyz_to_linear_srgb(&xyz)
+    })
+}
+
+fn smoothstep(edge0: f64, edge1: f64, x: f64) -> f64 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
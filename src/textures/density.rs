@@ -0,0 +1,227 @@
+use std::fs;
+use std::path::Path;
+
+use crate::{
+    aabb::AABB,
+    vec3::{Point3, Vec3},
+};
+
+use super::perlin::Perlin;
+
+/// A scalar field giving the density of a participating medium at each point.
+pub trait DensityField: Send + Sync {
+    fn density(&self, p: &Point3) -> f64;
+
+    /// Upper bound of `density` anywhere in the field, used as the majorant when tracking.
+    fn max_density(&self) -> f64;
+}
+
+/// Cloud like density from Perlin turbulence. `coverage` in `[0, 1)` carves out empty space
+/// between the puffs, higher values give sparser clouds.
+pub struct PerlinDensity {
+    noise: Perlin,
+    scale: f64,
+    density: f64,
+    coverage: f64,
+}
+
+impl PerlinDensity {
+    pub fn new(scale: f64, density: f64, coverage: f64) -> Self {
+        assert!(
+            (0.0..1.0).contains(&coverage),
+            "PerlinDensity coverage must be in [0, 1)"
+        );
+        Self {
+            noise: Perlin::new(),
+            scale,
+            density,
+            coverage,
+        }
+    }
+}
+
+impl DensityField for PerlinDensity {
+    fn density(&self, p: &Point3) -> f64 {
+        let turbulence = self.noise.turb(&(self.scale * p), None);
+        let shaped = (turbulence - self.coverage) / (1.0 - self.coverage);
+        self.density * shaped.clamp(0.0, 1.0)
+    }
+
+    fn max_density(&self) -> f64 {
+        self.density
+    }
+}
+
+/// Density from a procedural function, which should stay within `[0, max_density]`.
+pub struct FnDensity {
+    function: Box<dyn Fn(&Point3) -> f64 + Send + Sync>,
+    max_density: f64,
+}
+
+impl FnDensity {
+    pub fn new<F>(function: F, max_density: f64) -> Self
+    where
+        F: Fn(&Point3) -> f64 + Send + Sync + 'static,
+    {
+        Self {
+            function: Box::new(function),
+            max_density,
+        }
+    }
+}
+
+impl DensityField for FnDensity {
+    fn density(&self, p: &Point3) -> f64 {
+        (self.function)(p).clamp(0.0, self.max_density)
+    }
+
+    fn max_density(&self) -> f64 {
+        self.max_density
+    }
+}
+
+/// Densities on a regular grid stretched over `bounds`, trilinearly interpolated. Samples are
+/// stored x fastest, then y, then z.
+pub struct VoxelGrid {
+    resolution: [usize; 3],
+    data: Vec<f64>,
+    bounds: AABB,
+    max_density: f64,
+}
+
+impl VoxelGrid {
+    pub fn new(resolution: [usize; 3], data: Vec<f64>, bounds: AABB) -> Self {
+        assert!(
+            resolution.iter().all(|&n| n >= 1),
+            "VoxelGrid needs at least one voxel along each axis"
+        );
+        assert_eq!(
+            resolution[0] * resolution[1] * resolution[2],
+            data.len(),
+            "VoxelGrid data doesn't match its resolution"
+        );
+
+        let max_density = data.iter().cloned().fold(0.0, f64::max);
+        Self {
+            resolution,
+            data,
+            bounds,
+            max_density,
+        }
+    }
+
+    /// Loads a single channel, float32 Mitsuba `.vol` file. The grid is empty if the file
+    /// can't be read.
+    pub fn from_file<T: AsRef<Path>>(file: T) -> Self {
+        match fs::read(&file)
+            .ok()
+            .and_then(|bytes| Self::parse_vol(&bytes))
+        {
+            Some(grid) => grid,
+            None => {
+                eprintln!("Unable to load volume {}", file.as_ref().display());
+                Self::new([1, 1, 1], vec![0.0], AABB::default())
+            }
+        }
+    }
+
+    fn parse_vol(bytes: &[u8]) -> Option<Self> {
+        if bytes.get(0..4)? != b"VOL\x03" {
+            return None;
+        }
+
+        let read_i32 = |offset: usize| -> Option<i32> {
+            Some(i32::from_le_bytes(
+                bytes.get(offset..offset + 4)?.try_into().ok()?,
+            ))
+        };
+        let read_f32 = |offset: usize| -> Option<f64> {
+            Some(f32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?) as f64)
+        };
+
+        // NOTE: 1 is the float32 encoding, and only density (one channel) grids are supported
+        if read_i32(4)? != 1 || read_i32(20)? != 1 {
+            return None;
+        }
+
+        let resolution = [
+            usize::try_from(read_i32(8)?).ok()?,
+            usize::try_from(read_i32(12)?).ok()?,
+            usize::try_from(read_i32(16)?).ok()?,
+        ];
+        let bounds = AABB::new(
+            Point3::with_values(read_f32(24)?, read_f32(28)?, read_f32(32)?),
+            Point3::with_values(read_f32(36)?, read_f32(40)?, read_f32(44)?),
+        );
+
+        // NOTE: headers claiming an empty or impossibly large grid are rejected here
+        if resolution.contains(&0) {
+            return None;
+        }
+        let count = resolution[0]
+            .checked_mul(resolution[1])?
+            .checked_mul(resolution[2])?;
+        let data = (0..count)
+            .map(|i| read_f32(48 + 4 * i))
+            .collect::<Option<Vec<f64>>>()?;
+
+        Some(Self::new(resolution, data, bounds))
+    }
+
+    fn voxel(&self, x: usize, y: usize, z: usize) -> f64 {
+        self.data[(z * self.resolution[1] + y) * self.resolution[0] + x]
+    }
+}
+
+impl DensityField for VoxelGrid {
+    fn density(&self, p: &Point3) -> f64 {
+        let extent = self.bounds.max() - self.bounds.min();
+        let local = (p - self.bounds.min()) / extent;
+
+        let mut index = [0usize; 3];
+        let mut fraction = Vec3::new();
+        for a in 0..3 {
+            if !(0.0..=1.0).contains(&local[a]) {
+                return 0.0;
+            }
+
+            // NOTE: voxel centres sit half a voxel in from the bounds
+            let x = (local[a] * self.resolution[a] as f64 - 0.5)
+                .clamp(0.0, (self.resolution[a] - 1) as f64);
+            index[a] = (x.floor() as usize).min(self.resolution[a].saturating_sub(2));
+            fraction[a] = x - index[a] as f64;
+        }
+
+        let mut accum = 0.0;
+        for dz in 0..2 {
+            for dy in 0..2 {
+                for dx in 0..2 {
+                    let x = (index[0] + dx).min(self.resolution[0] - 1);
+                    let y = (index[1] + dy).min(self.resolution[1] - 1);
+                    let z = (index[2] + dz).min(self.resolution[2] - 1);
+
+                    let weight = (if dx == 1 {
+                        fraction[0]
+                    } else {
+                        1.0 - fraction[0]
+                    }) * (if dy == 1 {
+                        fraction[1]
+                    } else {
+                        1.0 - fraction[1]
+                    }) * (if dz == 1 {
+                        fraction[2]
+                    } else {
+                        1.0 - fraction[2]
+                    });
+                    accum += weight * self.voxel(x, y, z);
+                }
+            }
+        }
+
+        accum
+    }
+
+    fn max_density(&self) -> f64 {
+        self.max_density
+    }
+}
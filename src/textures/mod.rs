@@ -1,4 +1,6 @@
 pub mod check_texture;
+pub mod density;
 pub mod image_texture;
 pub mod perlin;
 pub mod texture;
+pub mod vertex_color;
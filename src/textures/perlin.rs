@@ -72,6 +72,22 @@ impl Perlin {
         accum.abs()
     }
 
+    /// Fractal Brownian motion, `octaves` layers of noise each at twice the frequency and
+    /// half the weight of the last. Unlike `turb` the result keeps its sign.
+    pub fn fbm(&self, p: &Point3, octaves: u64) -> f64 {
+        let mut accum = 0.0;
+        let mut temp_p = *p;
+        let mut weight = 1.0;
+
+        for _ in 0..octaves {
+            accum += weight * self.noise(&temp_p);
+            weight *= 0.5;
+            temp_p *= 2.0;
+        }
+
+        accum
+    }
+
     fn perlin_interp(c: &[[[Vec3; 2]; 2]; 2], u: &f64, v: &f64, w: &f64) -> f64 {
         let uu = u * u * (3.0 - 2.0 * u);
         let vv = v * v * (3.0 - 2.0 * v);
@@ -1,7 +1,15 @@
-use crate::vec3::{Color, Point3};
+use crate::{
+    hittables::hittable::HitRecord,
+    vec3::{Color, Point3},
+};
 
-pub trait Texture {
+pub trait Texture: Send + Sync {
     fn value(&self, u: f64, v: f64, p: &Point3) -> Color;
+
+    /// The value at a hit, for textures that need more of it than where it is.
+    fn value_at(&self, rec: &HitRecord) -> Color {
+        self.value(rec.u, rec.v, &rec.p)
+    }
 }
 
 pub struct SolidColor {
@@ -0,0 +1,36 @@
+use crate::{
+    hittables::hittable::HitRecord,
+    vec3::{Color, Point3},
+};
+
+use super::texture::Texture;
+
+/// The colors stored at a `TriangleMesh`'s vertices, blended across each triangle, with
+/// `fallback` for meshes that have none. Only meshes set vertex colors on their hits, so it
+/// belongs on a mesh's material.
+pub struct VertexColorTexture {
+    fallback: Color,
+}
+
+impl VertexColorTexture {
+    pub fn new(fallback: Color) -> Self {
+        Self { fallback }
+    }
+}
+
+impl Texture for VertexColorTexture {
+    fn value(&self, _: f64, _: f64, _: &Point3) -> Color {
+        self.fallback
+    }
+
+    fn value_at(&self, rec: &HitRecord) -> Color {
+        rec.vertex_color.unwrap_or(self.fallback)
+    }
+}
+
+#[macro_export]
+macro_rules! rc_box_vertex_color_texture {
+    ( $fallback:expr ) => {
+        Arc::new(Box::new(VertexColorTexture::new($fallback)))
+    };
+}
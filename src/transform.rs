@@ -0,0 +1,156 @@
+use crate::{
+    aabb::AABB,
+    ray::Ray,
+    vec3::{cross, dot, Point3, Vec3},
+};
+
+/// Unit quaternion, so rotations can be blended along the shortest arc.
+#[derive(Clone, Copy)]
+pub struct Rotation {
+    w: f64,
+    v: Vec3,
+}
+
+impl Rotation {
+    /// Turns by `angle` degrees around `axis`.
+    pub fn new(axis: &Vec3, angle: f64) -> Self {
+        let half = 0.5 * angle.to_radians();
+        Self {
+            w: half.cos(),
+            v: half.sin() * axis.unit_vector(),
+        }
+    }
+
+    pub fn slerp(&self, other: &Self, f: f64) -> Self {
+        // NOTE: q and -q are the same rotation, take the one on the near side
+        let mut cos_theta = self.w * other.w + dot(&self.v, &other.v);
+        let (ow, ov) = if cos_theta < 0.0 {
+            cos_theta = -cos_theta;
+            (-other.w, -other.v)
+        } else {
+            (other.w, other.v)
+        };
+
+        let (a, b) = if cos_theta > 0.9995 {
+            (1.0 - f, f)
+        } else {
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+            (
+                ((1.0 - f) * theta).sin() / sin_theta,
+                (f * theta).sin() / sin_theta,
+            )
+        };
+
+        let w = a * self.w + b * ow;
+        let v = a * self.v + b * ov;
+        let norm = (w * w + v.length_squared()).sqrt();
+        Self {
+            w: w / norm,
+            v: v / norm,
+        }
+    }
+
+    pub fn rotate(&self, p: &Vec3) -> Vec3 {
+        let t = 2.0 * cross(&self.v, p);
+        *p + self.w * t + cross(&self.v, &t)
+    }
+
+    pub fn inverse(&self) -> Self {
+        Self {
+            w: self.w,
+            v: -self.v,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self {
+            w: 1.0,
+            v: Vec3::new(),
+        }
+    }
+
+    /// Angle turned through, in radians from `0` to `π`.
+    pub fn angle_to(&self, other: &Self) -> f64 {
+        let cos_half = (self.w * other.w + dot(&self.v, &other.v)).abs().min(1.0);
+        2.0 * cos_half.acos()
+    }
+}
+
+/// Scales about the origin, then rotates, then translates.
+#[derive(Clone, Copy)]
+pub struct Transform {
+    pub translation: Vec3,
+    pub rotation: Rotation,
+    pub scale: Vec3,
+}
+
+impl Transform {
+    /// Scale by `scale`, turn by `angle` degrees around `axis` and move by `translation`.
+    pub fn new(translation: Vec3, axis: Vec3, angle: f64, scale: Vec3) -> Self {
+        Self {
+            translation,
+            rotation: Rotation::new(&axis, angle),
+            scale,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self {
+            translation: Vec3::new(),
+            rotation: Rotation::identity(),
+            scale: Vec3::with_value(1.0),
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.translation.near_zero()
+            && (self.scale - Vec3::with_value(1.0)).near_zero()
+            && self.rotation.angle_to(&Rotation::identity()) < 1e-8
+    }
+
+    pub fn point(&self, p: &Point3) -> Point3 {
+        self.rotation.rotate(&(self.scale * *p)) + self.translation
+    }
+
+    /// Maps a tangent or other direction, ignoring the translation.
+    pub fn vector(&self, v: &Vec3) -> Vec3 {
+        self.rotation.rotate(&(self.scale * *v))
+    }
+
+    /// Maps a unit normal, by the inverse scale so it stays perpendicular to the surface.
+    pub fn normal(&self, n: &Vec3) -> Vec3 {
+        self.rotation.rotate(&(*n / self.scale)).unit_vector()
+    }
+
+    /// `ray` in the space before the transform. An affine map keeps ray distances, so hits
+    /// found with it have the same `t`.
+    pub fn inverse_ray(&self, ray: &Ray) -> Ray {
+        let inverse = self.rotation.inverse();
+        let origin = inverse.rotate(&(ray.origin() - self.translation)) / self.scale;
+        let direction = inverse.rotate(&ray.direction()) / self.scale;
+        let mut local = Ray::new(origin, direction, ray.time());
+        local.set_wavelength(ray.wavelength());
+        local
+    }
+
+    /// Box around the transformed corners of `bbox`.
+    pub fn bounds(&self, bbox: &AABB) -> AABB {
+        let ends = [bbox.min(), bbox.max()];
+        let mut min = Point3::with_value(f64::INFINITY);
+        let mut max = Point3::with_value(f64::NEG_INFINITY);
+        for corner in 0..8 {
+            let world = self.point(&Point3::with_values(
+                ends[corner & 1].x(),
+                ends[(corner >> 1) & 1].y(),
+                ends[corner >> 2].z(),
+            ));
+            for c in 0..3 {
+                min[c] = min[c].min(world[c]);
+                max[c] = max[c].max(world[c]);
+            }
+        }
+
+        AABB::new(min, max)
+    }
+}